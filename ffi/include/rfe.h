@@ -111,6 +111,9 @@ typedef enum Result {
   RESULT_NO_DATA,
   RESULT_NULL_PTR_ERROR,
   RESULT_TIMEOUT_ERROR,
+  RESULT_INVALID_MESSAGE_ERROR,
+  RESULT_VALUE_CLAMPED_ERROR,
+  RESULT_CALLBACK_PANICKED_ERROR,
 } Result;
 
 enum RfPower
@@ -8,6 +8,9 @@ pub enum Result {
     NoData,
     NullPtrError,
     TimeoutError,
+    InvalidMessageError,
+    ValueClampedError,
+    CallbackPanickedError,
 }
 
 impl<T> From<rfe::Result<T>> for Result {
@@ -25,8 +28,11 @@ impl From<rfe::Error> for Result {
             rfe::Error::IncompatibleFirmware(_) => Result::IncompatibleFirmwareError,
             rfe::Error::InvalidInput(_) => Result::InvalidInputError,
             rfe::Error::InvalidOperation(_) => Result::InvalidOperationError,
+            rfe::Error::InvalidMessage(_) => Result::InvalidMessageError,
             rfe::Error::Io(_) => Result::IoError,
             rfe::Error::TimedOut(_) => Result::TimeoutError,
+            rfe::Error::ValueClamped(_) => Result::ValueClampedError,
+            rfe::Error::CallbackPanicked(_) => Result::CallbackPanickedError,
         }
     }
 }
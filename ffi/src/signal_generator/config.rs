@@ -28,13 +28,13 @@ impl From<Config> for SignalGeneratorConfig {
             step_hz: config.step.as_hz(),
             attenuation: config.attenuation,
             power_level: config.power_level,
-            sweep_power_steps: config.sweep_power_steps,
+            sweep_power_steps: config.sweep_power_steps_raw(),
             start_attenuation: config.start_attenuation,
             start_power_level: config.start_power_level,
             stop_attenuation: config.stop_attenuation,
             stop_power_level: config.stop_power_level,
             rf_power: config.rf_power,
-            sweep_delay_ms: config.sweep_delay.as_millis() as u64,
+            sweep_delay_ms: u64::from(config.sweep_delay_ms()),
         }
     }
 }
@@ -55,13 +55,13 @@ impl From<ConfigAmpSweep> for SignalGeneratorConfigAmpSweep {
     fn from(config: ConfigAmpSweep) -> Self {
         SignalGeneratorConfigAmpSweep {
             cw_hz: config.cw.as_hz(),
-            sweep_power_steps: config.sweep_power_steps,
+            sweep_power_steps: config.sweep_power_steps_raw(),
             start_attenuation: config.start_attenuation,
             start_power_level: config.start_power_level,
             stop_attenuation: config.stop_attenuation,
             stop_power_level: config.stop_power_level,
             rf_power: config.rf_power,
-            sweep_delay_ms: config.sweep_delay.as_millis() as u64,
+            sweep_delay_ms: u64::from(config.sweep_delay_ms()),
         }
     }
 }
@@ -109,7 +109,7 @@ impl From<ConfigFreqSweep> for SignalGeneratorConfigFreqSweep {
             attenuation: config.attenuation,
             power_level: config.power_level,
             rf_power: config.rf_power,
-            sweep_delay_ms: config.sweep_delay.as_millis() as u64,
+            sweep_delay_ms: u64::from(config.sweep_delay_ms()),
         }
     }
 }
@@ -7,7 +7,7 @@ use std::{
 use rfe::{
     signal_generator::{
         Attenuation, Config, ConfigAmpSweep, ConfigCw, ConfigFreqSweep, PowerLevel,
-        SignalGenerator, Temperature,
+        SignalGenerator, SweepDelay, Temperature,
     },
     ScreenData,
 };
@@ -429,6 +429,9 @@ pub extern "C" fn rfe_signal_generator_start_amp_sweep(
     stop_power_level: PowerLevel,
     step_delay_sec: u8,
 ) -> Result {
+    let Ok(step_delay) = SweepDelay::new(Duration::from_secs(u64::from(step_delay_sec))) else {
+        return Result::InvalidInputError;
+    };
     if let Some(rfe) = rfe {
         rfe.start_amp_sweep(
             cw_hz,
@@ -436,7 +439,7 @@ pub extern "C" fn rfe_signal_generator_start_amp_sweep(
             start_power_level,
             stop_attenuation,
             stop_power_level,
-            Duration::from_secs(u64::from(step_delay_sec)),
+            step_delay,
         )
         .into()
     } else {
@@ -453,13 +456,16 @@ pub extern "C" fn rfe_signal_generator_start_amp_sweep_exp(
     stop_power_dbm: f64,
     step_delay_sec: u8,
 ) -> Result {
+    let Ok(step_delay) = SweepDelay::new(Duration::from_secs(u64::from(step_delay_sec))) else {
+        return Result::InvalidInputError;
+    };
     if let Some(rfe) = rfe {
         rfe.start_amp_sweep_exp(
             cw_hz,
             start_power_dbm,
             step_power_db,
             stop_power_dbm,
-            Duration::from_secs(u64::from(step_delay_sec)),
+            step_delay,
         )
         .into()
     } else {
@@ -504,6 +510,9 @@ pub extern "C" fn rfe_signal_generator_start_freq_sweep(
     step_hz: u64,
     step_delay_sec: u8,
 ) -> Result {
+    let Ok(step_delay) = SweepDelay::new(Duration::from_secs(u64::from(step_delay_sec))) else {
+        return Result::InvalidInputError;
+    };
     if let Some(rfe) = rfe {
         rfe.start_freq_sweep(
             start_hz,
@@ -511,7 +520,7 @@ pub extern "C" fn rfe_signal_generator_start_freq_sweep(
             power_level,
             sweep_steps,
             step_hz,
-            Duration::from_secs(u64::from(step_delay_sec)),
+            step_delay,
         )
         .into()
     } else {
@@ -528,15 +537,12 @@ pub extern "C" fn rfe_signal_generator_start_freq_sweep_exp(
     step_hz: u64,
     step_delay_sec: u8,
 ) -> Result {
+    let Ok(step_delay) = SweepDelay::new(Duration::from_secs(u64::from(step_delay_sec))) else {
+        return Result::InvalidInputError;
+    };
     if let Some(rfe) = rfe {
-        rfe.start_freq_sweep_exp(
-            start_hz,
-            power_dbm,
-            sweep_steps,
-            step_hz,
-            Duration::from_secs(u64::from(step_delay_sec)),
-        )
-        .into()
+        rfe.start_freq_sweep_exp(start_hz, power_dbm, sweep_steps, step_hz, step_delay)
+            .into()
     } else {
         Result::NullPtrError
     }
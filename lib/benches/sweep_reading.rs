@@ -0,0 +1,189 @@
+//! Measures reading sweeps, both through a connected spectrum analyzer and by framing and parsing
+//! the same bytes directly.
+//!
+//! The `bytes_to_callback` benchmarks measure the time from the bytes arriving at the serial port
+//! to the sweep callback being called, through the same reader thread, framing, and parsing that a
+//! real device goes through. The reader thread pauses for 10 ms after each message it reads so that
+//! commands can be sent between messages, so that pause dominates these numbers, and they barely
+//! change with how the bytes are buffered or parsed.
+//!
+//! The `parse_capture` benchmarks frame the bytes at line endings and parse each message with the
+//! same parser the reader thread uses, without the reader thread, so they show the cost of framing
+//! and parsing on its own.
+
+use std::{
+    io::{self, Read, Write},
+    sync::{mpsc, Arc, Condvar, Mutex},
+    time::{Duration, Instant},
+};
+
+use criterion::{
+    black_box, criterion_group, criterion_main, BatchSize, Criterion, SamplingMode, Throughput,
+};
+use rfe::{spectrum_analyzer::Message, SpectrumAnalyzer};
+
+const SETUP_INFO: &[u8] = b"#C2-M:003,004,01.12B26\r\n";
+const SWEEPS: usize = 100;
+// Serial ports hand over the bytes received so far rather than whole messages
+const SERIAL_READ_LEN: usize = 512;
+// The default read timeout of a serial port
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Returns a config for sweeps of `sweep_len` points.
+fn config(sweep_len: u16) -> Vec<u8> {
+    format!("#C2-F:5249000,0196428,-030,-118,{sweep_len:04},1,000,4850000,6100000,0600000,00200,0000,000\r\n")
+        .into_bytes()
+}
+
+/// Returns sweeps with amplitudes between -40 and -120 dBm, which don't include line ending bytes.
+fn sweeps(sweep_len: u16) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for sweep in 0..SWEEPS {
+        match u8::try_from(sweep_len) {
+            Ok(len) => bytes.extend_from_slice(&[b'$', b'S', len]),
+            Err(_) => {
+                bytes.extend_from_slice(b"$z");
+                bytes.extend_from_slice(&sweep_len.to_be_bytes());
+            }
+        }
+        bytes.extend((0..sweep_len).map(|i| (80 + (usize::from(i) * 7 + sweep) % 160) as u8));
+        bytes.extend_from_slice(b"\r\n");
+    }
+    bytes
+}
+
+/// A stream that hands over the bytes sent to it at most `SERIAL_READ_LEN` bytes per read, and
+/// times out reads when no bytes arrive, like a serial port.
+#[derive(Clone, Default)]
+struct SerialStream {
+    shared: Arc<(Mutex<State>, Condvar)>,
+}
+
+#[derive(Default)]
+struct State {
+    output: Vec<u8>,
+    read_len: usize,
+    first_read_at: Option<Instant>,
+}
+
+impl SerialStream {
+    /// Replaces the bytes to be read with `bytes`.
+    fn send(&self, bytes: &[u8]) {
+        let (lock, condvar) = &*self.shared;
+        let mut state = lock.lock().unwrap();
+        state.output.clear();
+        state.output.extend_from_slice(bytes);
+        state.read_len = 0;
+        state.first_read_at = None;
+        condvar.notify_all();
+    }
+
+    /// Waits until the first of the bytes last sent is read, returning when it was read.
+    fn first_read_at(&self) -> Instant {
+        let (lock, condvar) = &*self.shared;
+        let state = condvar
+            .wait_while(lock.lock().unwrap(), |state| state.first_read_at.is_none())
+            .unwrap();
+        state.first_read_at.unwrap()
+    }
+}
+
+impl Read for SerialStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let (lock, condvar) = &*self.shared;
+        let (mut state, wait_result) = condvar
+            .wait_timeout_while(lock.lock().unwrap(), READ_TIMEOUT, |state| {
+                state.read_len == state.output.len()
+            })
+            .unwrap();
+        if wait_result.timed_out() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+
+        let start = state.read_len;
+        let len = buf
+            .len()
+            .min(SERIAL_READ_LEN)
+            .min(state.output.len() - start);
+        buf[..len].copy_from_slice(&state.output[start..start + len]);
+        state.read_len += len;
+        if state.first_read_at.is_none() {
+            state.first_read_at = Some(Instant::now());
+            condvar.notify_all();
+        }
+        Ok(len)
+    }
+}
+
+impl Write for SerialStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn sweep_reading(c: &mut Criterion) {
+    for sweep_len in [112, 4096] {
+        let stream = SerialStream::default();
+        let mut device_info = SETUP_INFO.to_vec();
+        device_info.extend_from_slice(&config(sweep_len));
+        stream.send(&device_info);
+        let rfe = SpectrumAnalyzer::connect_with_stream(stream.clone()).unwrap();
+
+        let (sweeps_received, received_at) = mpsc::channel();
+        let mut sweep_count = 0;
+        rfe.set_sweep_callback(move |amplitudes_dbm| {
+            black_box(amplitudes_dbm);
+            sweep_count += 1;
+            if sweep_count % SWEEPS == 0 {
+                sweeps_received.send(Instant::now()).unwrap();
+            }
+        });
+
+        let sweeps = sweeps(sweep_len);
+        let mut group = c.benchmark_group(format!("sweep_reading/{sweep_len}_point_sweeps"));
+        group
+            .sample_size(10)
+            .measurement_time(Duration::from_secs(11))
+            .sampling_mode(SamplingMode::Flat)
+            .throughput(Throughput::Elements(SWEEPS as u64));
+        group.bench_function("bytes_to_callback", |b| {
+            // Only the time from the first byte being read to the last sweep's callback is
+            // measured, not the time the reader thread spends waiting for the bytes to be sent
+            b.iter_custom(|iters| {
+                (0..iters)
+                    .map(|_| {
+                        stream.send(&sweeps);
+                        let first_read_at = stream.first_read_at();
+                        received_at.recv().unwrap() - first_read_at
+                    })
+                    .sum()
+            })
+        });
+        group.finish();
+        assert_eq!(rfe.parse_error_count(), 0);
+
+        let mut group = c.benchmark_group(format!("sweep_reading/{sweep_len}_point_sweeps"));
+        group
+            .measurement_time(Duration::from_secs(10))
+            .throughput(Throughput::Elements(SWEEPS as u64));
+        group.bench_function("parse_capture", |b| {
+            b.iter_batched(
+                || sweeps.clone(),
+                |sweeps| {
+                    rfe::parse_capture::<Message>(sweeps)
+                        .map(|(_, message)| black_box(message.unwrap()))
+                        .count()
+                },
+                BatchSize::LargeInput,
+            )
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, sweep_reading);
+criterion_main!(benches);
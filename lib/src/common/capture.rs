@@ -0,0 +1,160 @@
+use std::{fs, io, marker::PhantomData, path::Path};
+
+use thiserror::Error;
+
+use super::{device::find_message_in_buf, MessageParseError};
+
+/// A `MessageParseError` that owns its data, so that it can outlive the bytes that failed to
+/// parse.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum OwnedMessageParseError {
+    #[error("Attempted to parse an incomplete message")]
+    Incomplete,
+
+    #[error("Attempted to parse a truncated message")]
+    Truncated,
+
+    #[error("Attempted to parse an invalid message, expected {expected} at byte {offset}")]
+    Invalid {
+        /// The offset of the byte where parsing failed from the start of the capture.
+        offset: u64,
+        /// A description of what the parser expected to find at `offset`.
+        expected: &'static str,
+    },
+
+    #[error("Attempted to parse an unknown message type")]
+    UnknownMessageType,
+}
+
+impl OwnedMessageParseError {
+    fn new(error: &MessageParseError, message: &[u8], message_offset: u64) -> Self {
+        match error {
+            MessageParseError::Incomplete => OwnedMessageParseError::Incomplete,
+            MessageParseError::Truncated { .. } => OwnedMessageParseError::Truncated,
+            MessageParseError::Invalid { expected, .. } => OwnedMessageParseError::Invalid {
+                offset: message_offset + error.offset(message).unwrap_or_default() as u64,
+                expected,
+            },
+            MessageParseError::UnknownMessageType => OwnedMessageParseError::UnknownMessageType,
+        }
+    }
+}
+
+/// An iterator over the messages in a capture of the bytes received from an RF Explorer.
+///
+/// Each item is the offset of the message from the start of the capture along with the result of
+/// parsing it.
+#[derive(Debug, Clone)]
+pub struct CaptureMessages<M> {
+    bytes: Vec<u8>,
+    // The start of the next message
+    message_start: usize,
+    // Where to look for the end of the next message, which is past `message_start` when a
+    // message's binary data contains a line ending
+    search_start: usize,
+    _message: PhantomData<M>,
+}
+
+impl<M> Iterator for CaptureMessages<M>
+where
+    M: for<'a> TryFrom<&'a [u8], Error = MessageParseError<'a>>,
+{
+    type Item = (u64, Result<M, OwnedMessageParseError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.message_start >= self.bytes.len() {
+                return None;
+            }
+
+            // Messages are delimited by \r\n, but binary data like sweep amplitudes can contain
+            // line endings too, so a message is extended to the next line ending if it's incomplete
+            let message_end = self.bytes[self.search_start..]
+                .iter()
+                .position(|byte| *byte == b'\n')
+                .map_or(self.bytes.len(), |position| {
+                    self.search_start + position + 1
+                });
+            let message = &self.bytes[self.message_start..message_end];
+            let message_offset = self.message_start as u64;
+
+            let result = match find_message_in_buf::<M>(message) {
+                Err(MessageParseError::Incomplete) if message_end < self.bytes.len() => {
+                    self.search_start = message_end;
+                    continue;
+                }
+                Ok(message) => Ok(message),
+                Err(error) => Err(OwnedMessageParseError::new(&error, message, message_offset)),
+            };
+
+            self.message_start = message_end;
+            self.search_start = message_end;
+            return Some((message_offset, result));
+        }
+    }
+}
+
+/// Parses the messages in a capture of the bytes received from an RF Explorer, e.g. a logic
+/// analyzer export of its serial connection.
+///
+/// The type of message, `rfe::spectrum_analyzer::Message` or `rfe::signal_generator::Message`,
+/// decides which kind of RF Explorer the capture is parsed as.
+pub fn parse_capture<M>(bytes: impl Into<Vec<u8>>) -> CaptureMessages<M>
+where
+    M: for<'a> TryFrom<&'a [u8], Error = MessageParseError<'a>>,
+{
+    CaptureMessages {
+        bytes: bytes.into(),
+        message_start: 0,
+        search_start: 0,
+        _message: PhantomData,
+    }
+}
+
+/// Parses the messages in a file containing a capture of the bytes received from an RF Explorer.
+///
+/// See `parse_capture` for details.
+pub fn parse_capture_file<M>(path: impl AsRef<Path>) -> io::Result<CaptureMessages<M>>
+where
+    M: for<'a> TryFrom<&'a [u8], Error = MessageParseError<'a>>,
+{
+    Ok(parse_capture(fs::read(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spectrum_analyzer::Message;
+
+    #[test]
+    fn parse_messages_with_offsets() {
+        let config =
+            b"#C2-F:5249000,0196428,-030,-118,0112,1,000,4850000,6100000,0600000,00200,0000,000\r\n";
+        let invalid_config =
+            b"#C2-F:52490x0,0196428,-030,-118,0112,1,000,4850000,6100000,0600000,00200,0000,000\r\n";
+        let mut capture = Vec::new();
+        capture.extend_from_slice(config);
+        capture.extend_from_slice(b"#Unknown\r\n");
+        capture.extend_from_slice(invalid_config);
+
+        let results: Vec<_> = parse_capture::<Message>(capture).collect();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].0, 0);
+        assert!(matches!(results[0].1, Ok(Message::Config(_))));
+        assert_eq!(results[1].0, config.len() as u64);
+        assert_eq!(
+            results[1].1.as_ref().unwrap_err(),
+            &OwnedMessageParseError::UnknownMessageType
+        );
+        let invalid_config_offset = (config.len() + b"#Unknown\r\n".len()) as u64;
+        assert_eq!(results[2].0, invalid_config_offset);
+        // The invalid start frequency begins after "#C2-F:"
+        assert_eq!(
+            results[2].1.as_ref().unwrap_err(),
+            &OwnedMessageParseError::Invalid {
+                offset: invalid_config_offset + 6,
+                expected: "a number",
+            }
+        );
+    }
+}
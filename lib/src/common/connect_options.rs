@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+/// Options for the serial connection to an RF Explorer.
+///
+/// Options that are `None` keep the crate's default behavior.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct ConnectOptions {
+    /// How long a read from the serial port waits for data before the port is treated as idle.
+    ///
+    /// Commands can't be sent while a read is waiting, so a command sent while the RF Explorer
+    /// isn't sending anything can be delayed by up to the timeout. The reader pauses for 100 ms
+    /// after each timeout, so a shorter timeout delays more messages that arrive after an idle
+    /// period.
+    ///
+    /// Defaults to 1 second.
+    pub read_timeout: Option<Duration>,
+
+    /// The size (in bytes) of the buffer used to read from the serial port.
+    ///
+    /// Defaults to 1 byte on Windows and 8 KiB on other platforms.
+    pub read_buffer_size: Option<usize>,
+}
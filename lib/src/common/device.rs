@@ -1,18 +1,44 @@
 use std::{
     borrow::Cow,
+    cell::Cell,
     fmt::Debug,
     io::{self, ErrorKind},
     sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, Weak,
     },
     thread::{self, JoinHandle},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use tracing::debug;
+use serialport::SerialPortInfo;
+use tracing::{debug, warn};
 
-use super::{serial_port, ConnectionResult, MessageContainer, MessageParseError, SerialPort};
+use super::{
+    serial_port, ConnectOptions, ConnectionError, ConnectionResult, DeviceType, Framer,
+    FramerState, LockResultExt, MessageContainer, MessageParseError, SerialPort,
+};
+use crate::rf_explorer::parsers::strip_line_ending;
+
+pub(crate) const DEFAULT_MAX_CONCURRENT_CONNECTIONS: usize = 4;
+
+thread_local! {
+    static IS_READER_THREAD: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Returns an error if called from a thread that reads messages from a device, e.g. from a
+/// callback.
+///
+/// Blocking for a message on that thread would never return since the message can't be read until
+/// the call returns.
+pub(crate) fn ensure_not_reader_thread() -> crate::Result<()> {
+    if IS_READER_THREAD.get() {
+        return Err(crate::Error::InvalidOperation(
+            "cannot block for messages from within a message callback".to_string(),
+        ));
+    }
+    Ok(())
+}
 
 #[derive(Debug)]
 pub struct Device<M: MessageContainer + 'static> {
@@ -20,6 +46,9 @@ pub struct Device<M: MessageContainer + 'static> {
     is_reading: Arc<AtomicBool>,
     read_thread_handle: Option<JoinHandle<()>>,
     messages: Arc<M>,
+    parse_error_count: Arc<AtomicU64>,
+    other_device_type: Arc<Mutex<Option<DeviceType>>>,
+    framer: Arc<Mutex<Framer>>,
 }
 
 impl<M: MessageContainer> Device<M> {
@@ -32,14 +61,31 @@ impl<M: MessageContainer> Device<M> {
             is_reading: Arc::new(AtomicBool::new(true)),
             read_thread_handle: None,
             messages: Arc::new(M::default()),
+            parse_error_count: Arc::new(AtomicU64::new(0)),
+            other_device_type: Arc::new(Mutex::new(None)),
+            framer: Arc::new(Mutex::new(Framer::default())),
         };
 
+        if let Ok(baud_rate) = device.serial_port.baud_rate() {
+            device.messages.baud_rate_changed(baud_rate);
+        }
+
         // Read messages from the device on a background thread
         let messages = device.messages.clone();
         let serial_port = device.serial_port.clone();
         let is_reading = device.is_reading.clone();
+        let parse_error_count = device.parse_error_count.clone();
+        let other_device_type = device.other_device_type.clone();
+        let framer = device.framer.clone();
         device.read_thread_handle = Some(thread::spawn(move || {
-            Self::read_messages(serial_port, messages, is_reading)
+            Self::read_messages(
+                serial_port,
+                messages,
+                is_reading,
+                parse_error_count,
+                other_device_type,
+                framer,
+            )
         }));
 
         if let Err(err) = device.serial_port.send_bytes(device_init_command) {
@@ -49,6 +95,14 @@ impl<M: MessageContainer> Device<M> {
 
         if let Err(err) = device.messages().wait_for_device_info() {
             device.stop_reading_messages();
+
+            // Report a more helpful error if the device turned out to be a different type
+            if let Some(found) = *device.other_device_type.lock().unpoisoned() {
+                return Err(ConnectionError::WrongDeviceType {
+                    expected: M::DEVICE_TYPE,
+                    found,
+                });
+            }
             return Err(err);
         }
 
@@ -59,20 +113,87 @@ impl<M: MessageContainer> Device<M> {
     }
 
     pub fn connect(device_init_command: impl AsRef<[u8]>) -> Option<Self> {
+        Self::connect_all(device_init_command).find_map(|device| device.ok())
+    }
+
+    /// Returns an iterator that connects to each available device in turn.
+    ///
+    /// Devices are only connected to as the iterator is advanced, so a device that is dropped
+    /// before the next one is requested is disconnected first. Ports that are in use by another
+    /// process are returned as `ConnectionError::PortBusy` errors.
+    pub fn connect_all(
+        device_init_command: impl AsRef<[u8]>,
+    ) -> impl Iterator<Item = ConnectionResult<Self>> {
         // For every Silabs CP210X port, we first try to connect using the RF Explorer's fast
         // default baud rate (500 kbps) and then try to connect using its slow default baud rate
         // (2.4 kbps)
-        serial_port::silabs_cp210x_ports()
-            .flat_map(|port_info| {
-                [
-                    (port_info.clone(), serial_port::FAST_BAUD_RATE),
-                    (port_info, serial_port::SLOW_BAUD_RATE),
-                ]
-            })
-            .find_map(|(port_info, baud_rate)| {
-                let serial_port = SerialPort::open(&port_info, baud_rate).ok()?;
-                Self::connect_internal(serial_port, device_init_command.as_ref()).ok()
-            })
+        serial_port::silabs_cp210x_ports().filter_map(move |port_info| {
+            Self::connect_to_port(&port_info, device_init_command.as_ref())
+        })
+    }
+
+    /// Connects to every available device, attempting up to `max_concurrent` connections at a time.
+    ///
+    /// The devices are returned in the order their ports were enumerated, along with a
+    /// `ConnectionError::PortBusy` error for each port that's in use by another process.
+    pub fn connect_all_with_concurrency(
+        device_init_command: impl AsRef<[u8]> + Sync,
+        max_concurrent: usize,
+    ) -> Vec<ConnectionResult<Self>> {
+        let ports = Mutex::new(serial_port::silabs_cp210x_ports().enumerate());
+        // The lock is released before connecting so that other threads can take the next port
+        let next_port = || ports.lock().unpoisoned().next();
+        let mut devices = thread::scope(|scope| {
+            let workers = (0..max_concurrent.max(1))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut devices = Vec::new();
+                        while let Some((index, port_info)) = next_port() {
+                            let device =
+                                Self::connect_to_port(&port_info, device_init_command.as_ref());
+                            devices.extend(device.map(|device| (index, device)));
+                        }
+                        devices
+                    })
+                })
+                .collect::<Vec<_>>();
+            workers
+                .into_iter()
+                .flat_map(|worker| {
+                    worker
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect::<Vec<_>>()
+        });
+        devices.sort_by_key(|(index, _)| *index);
+        devices.into_iter().map(|(_, device)| device).collect()
+    }
+
+    /// Connects to the device on a port using its fast default baud rate, falling back to its
+    /// slow default baud rate.
+    ///
+    /// Returns `None` if no device could be connected to, or a `ConnectionError::PortBusy` error if
+    /// the port is in use by another process.
+    fn connect_to_port(
+        port_info: &SerialPortInfo,
+        device_init_command: &[u8],
+    ) -> Option<ConnectionResult<Self>> {
+        for baud_rate in [serial_port::FAST_BAUD_RATE, serial_port::SLOW_BAUD_RATE] {
+            match SerialPort::open(port_info, baud_rate) {
+                Ok(serial_port) => {
+                    if let Ok(device) = Self::connect_internal(serial_port, device_init_command) {
+                        return Some(Ok(device));
+                    }
+                }
+                Err(error @ ConnectionError::PortBusy { .. }) => {
+                    warn!(%error, "Skipped a serial port that's in use");
+                    return Some(Err(error));
+                }
+                Err(_) => (),
+            }
+        }
+        None
     }
 
     pub fn connect_with_baud_rate(
@@ -90,44 +211,193 @@ impl<M: MessageContainer> Device<M> {
         baud_rate: u32,
         device_init_command: impl AsRef<[u8]>,
     ) -> ConnectionResult<Self> {
-        let serial_port = SerialPort::open_with_name(name, baud_rate)?;
+        Self::connect_with_options(
+            name,
+            baud_rate,
+            &ConnectOptions::default(),
+            device_init_command,
+        )
+    }
+
+    pub fn connect_with_options(
+        name: &str,
+        baud_rate: u32,
+        options: &ConnectOptions,
+        device_init_command: impl AsRef<[u8]>,
+    ) -> ConnectionResult<Self> {
+        let serial_port = SerialPort::open_with_name(name, baud_rate, options)?;
+        Self::connect_internal(serial_port, device_init_command.as_ref())
+    }
+
+    /// Connects to a device over a byte stream instead of a serial port.
+    pub fn connect_with_stream(
+        stream: impl io::Read + io::Write + Send + 'static,
+        device_init_command: impl AsRef<[u8]>,
+    ) -> ConnectionResult<Self> {
+        let serial_port = SerialPort::from_stream(
+            stream,
+            serial_port::FAST_BAUD_RATE,
+            &ConnectOptions::default(),
+        );
         Self::connect_internal(serial_port, device_init_command.as_ref())
     }
 
-    fn read_messages(serial_port: Arc<SerialPort>, messages: Arc<M>, is_reading: Arc<AtomicBool>) {
+    fn read_messages(
+        serial_port: Arc<SerialPort>,
+        messages: Arc<M>,
+        is_reading: Arc<AtomicBool>,
+        parse_error_count: Arc<AtomicU64>,
+        other_device_type: Arc<Mutex<Option<DeviceType>>>,
+        framer: Arc<Mutex<Framer>>,
+    ) {
         debug!("Started reading messages from device");
+        IS_READER_THREAD.set(true);
+        // The buffer is reused for every message, since clearing it keeps its capacity, and
+        // parsers borrow from it, so framing a message doesn't allocate once the buffer has grown.
         let mut message_buf = Vec::new();
+        // Set when a skipped message was followed by the start of another message in the buffer
+        let mut has_next_message = false;
         while is_reading.load(Ordering::Relaxed) {
             // Messages from devices are delimited by \r\n, so we try to read a line from
-            // the serial port into the message buffer
-            if let Err(error) = serial_port.read_line(&mut message_buf) {
+            // the serial port into the message buffer. The start of a message that's already in
+            // the buffer is parsed first, since it may be complete.
+            let read_result = if has_next_message {
+                has_next_message = false;
+                Ok(0)
+            } else {
+                serial_port.read_line(&mut message_buf)
+            };
+
+            // Discard a message whose remaining bytes stopped arriving, e.g. because the device was
+            // interrupted midway through sending it, so that the framer doesn't wait for it forever
+            let now = Instant::now();
+            let is_stale = {
+                let mut framer = framer.lock().unpoisoned();
+                framer.update(&message_buf, now);
+                framer.is_stale(now)
+            };
+            if is_stale {
+                parse_error_count.fetch_add(1, Ordering::Relaxed);
+                warn!(
+                    len = message_buf.len(),
+                    bytes = ?&message_buf[..message_buf.len().min(32)],
+                    "Discarded an incomplete message from device"
+                );
+                messages.message_parse_failed(
+                    &message_buf,
+                    crate::Error::InvalidMessage(format!(
+                        "Discarded an incomplete message of {} bytes that stopped arriving",
+                        message_buf.len()
+                    )),
+                );
+                message_buf.clear();
+                framer.lock().unpoisoned().update(&message_buf, now);
+            }
+
+            if let Err(error) = read_result {
                 // Time out errors are recoverable so we try to read again
                 // Other errors are not recoverable so we break out of the loop
                 if error.kind() == ErrorKind::TimedOut {
                     thread::sleep(Duration::from_millis(100));
                     continue;
                 }
+                warn!(%error, "Failed to read from device");
                 break;
             }
 
-            match find_message_in_buf(&message_buf) {
+            match parse_message_in_buf(&message_buf) {
                 Ok(message) => {
                     messages.cache_message(message);
                     message_buf.clear()
                 }
                 Err(MessageParseError::Incomplete) => (),
-                Err(_) => message_buf.clear(),
+                Err(MessageParseError::UnknownMessageType)
+                    if DeviceType::from_setup_info_message(&message_buf)
+                        .is_some_and(|device_type| device_type != M::DEVICE_TYPE) =>
+                {
+                    *other_device_type.lock().unpoisoned() =
+                        DeviceType::from_setup_info_message(&message_buf);
+                    messages.other_device_type_found();
+                    message_buf.clear()
+                }
+                Err(error) if error.is_recoverable() => {
+                    // Only the skipped message is removed from the buffer, keeping the start of
+                    // the message that followed a truncated message so that it's parsed next
+                    let next_message_start = match error {
+                        MessageParseError::Truncated {
+                            remainder: Some(remainder),
+                        } => {
+                            let next_message_start =
+                                remainder.as_ptr() as usize - message_buf.as_ptr() as usize;
+                            Self::message_parse_failed(
+                                &messages,
+                                &parse_error_count,
+                                &message_buf[..next_message_start],
+                                &error,
+                            );
+                            next_message_start
+                        }
+                        _ => {
+                            debug!(
+                                %error,
+                                bytes = ?&message_buf[..message_buf.len().min(32)],
+                                "Skipped message from device"
+                            );
+                            message_buf.len()
+                        }
+                    };
+                    message_buf.drain(..next_message_start);
+                    has_next_message = !message_buf.is_empty();
+                }
+                Err(error) => {
+                    Self::message_parse_failed(&messages, &parse_error_count, &message_buf, &error);
+                    message_buf.clear()
+                }
             }
+            framer
+                .lock()
+                .unpoisoned()
+                .update(&message_buf, Instant::now());
 
             thread::sleep(Duration::from_millis(10));
         }
         debug!("Stopped reading messages from device");
     }
 
+    /// Counts and reports a message that failed to parse.
+    fn message_parse_failed(
+        messages: &M,
+        parse_error_count: &AtomicU64,
+        message: &[u8],
+        error: &MessageParseError,
+    ) {
+        parse_error_count.fetch_add(1, Ordering::Relaxed);
+        let offset = error.offset(message);
+        warn!(
+            %error,
+            ?offset,
+            bytes = ?&message[..message.len().min(32)],
+            "Failed to parse message from device"
+        );
+        let description = match offset {
+            Some(offset) => format!("{error} at byte {offset}"),
+            None => error.to_string(),
+        };
+        messages.message_parse_failed(message, crate::Error::InvalidMessage(description));
+    }
+
     pub fn messages(&self) -> &M {
         &self.messages
     }
 
+    pub(crate) fn messages_weak(&self) -> Weak<M> {
+        Arc::downgrade(&self.messages)
+    }
+
+    pub(crate) fn serial_port_weak(&self) -> Weak<SerialPort> {
+        Arc::downgrade(&self.serial_port)
+    }
+
     pub(crate) fn serial_port(&self) -> &SerialPort {
         &self.serial_port
     }
@@ -148,6 +418,29 @@ impl<M: MessageContainer> Device<M> {
         self.serial_port.baud_rate()
     }
 
+    pub fn parse_error_count(&self) -> u64 {
+        self.parse_error_count.load(Ordering::Relaxed)
+    }
+
+    pub fn framer_state(&self) -> FramerState {
+        self.framer.lock().unpoisoned().state(Instant::now())
+    }
+
+    pub fn set_framer_stale_timeout(&self, stale_timeout: Option<Duration>) {
+        self.framer
+            .lock()
+            .unpoisoned()
+            .set_stale_timeout(stale_timeout);
+    }
+
+    /// Stops reading messages and closes the serial port, after which every command fails.
+    pub(crate) fn close(&mut self) {
+        self.stop_reading_messages();
+        let port_info = self.serial_port.port_info().clone();
+        let baud_rate = self.baud_rate().unwrap_or(serial_port::FAST_BAUD_RATE);
+        self.serial_port = Arc::new(SerialPort::closed(port_info, baud_rate));
+    }
+
     fn stop_reading_messages(&mut self) {
         self.is_reading.store(false, Ordering::Relaxed);
         if let Some(read_thread_handle) = self.read_thread_handle.take() {
@@ -162,14 +455,199 @@ impl<M: MessageContainer> Drop for Device<M> {
     }
 }
 
-fn find_message_in_buf<M>(message_buf: &[u8]) -> Result<M, MessageParseError>
+/// Parses the message at the start of `message_buf`, with or without a line ending.
+fn parse_message_in_buf<M>(message_buf: &[u8]) -> Result<M, MessageParseError<'_>>
 where
     M: for<'a> TryFrom<&'a [u8], Error = MessageParseError<'a>>,
 {
-    M::try_from(message_buf).or_else(|e| match e {
+    // Firmware versions terminate messages differently, so the line ending is stripped before the
+    // message is parsed. If the last byte of a binary message like a sweep looks like a line ending,
+    // stripping it leaves the message incomplete, so the whole message is parsed instead.
+    let message = strip_line_ending(message_buf);
+    match M::try_from(message) {
+        Err(MessageParseError::Incomplete) if message.len() < message_buf.len() => {
+            M::try_from(message_buf)
+        }
+        result => result,
+    }
+}
+
+/// Parses the message at the start of `message_buf`, or the message that follows it if it's
+/// truncated.
+pub(crate) fn find_message_in_buf<M>(message_buf: &[u8]) -> Result<M, MessageParseError<'_>>
+where
+    M: for<'a> TryFrom<&'a [u8], Error = MessageParseError<'a>>,
+{
+    parse_message_in_buf(message_buf).or_else(|e| match e {
         MessageParseError::Truncated {
             remainder: Some(remaining_bytes),
         } => find_message_in_buf(remaining_bytes),
         error => Err(error),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_support::ScriptedStream;
+
+    #[test]
+    fn accept_every_line_ending() {
+        let mut sweep = b"$S\x03\x10\x20\x0d".to_vec();
+        let mut screen_data = b"$D".to_vec();
+        screen_data.resize(2 + 8 * 128, b'\n');
+        let analyzer_messages: [&[u8]; 8] = [
+            b"#C2-F:5249000,0196428,-030,-118,0112,1,000,4850000,6100000,0600000,00200,0000,000",
+            b"#C2-M:006,005,01.12B26",
+            b"#Sn0SME38SI2X7NGR48",
+            b"DSP:0",
+            b"#a1",
+            &[b'#', b'K', 0],
+            &sweep,
+            &screen_data,
+        ];
+        let generator_messages: [&[u8]; 6] = [
+            b"#C3-*:0510000,0186525,0005,0001000,0,3,0000,0,0,1,3,0,00100",
+            b"#C3-G:0186525,0186525,0005,0001000,0,3,0",
+            b"#C3-A:0186525,0000,0,0,1,3,0,00100",
+            b"#C3-F:0186525,0005,0001000,0,3,0,00100",
+            b"#C3-M:060,255,01.15",
+            b"#T:3",
+        ];
+
+        for line_ending in [&b"\r\n"[..], b"\r", b"\n", b""] {
+            for message in analyzer_messages {
+                let bytes = [message, line_ending].concat();
+                assert!(
+                    find_message_in_buf::<crate::spectrum_analyzer::Message>(&bytes).is_ok(),
+                    "Failed to parse {}",
+                    bytes.escape_ascii()
+                );
+            }
+            for message in generator_messages {
+                let bytes = [message, line_ending].concat();
+                assert!(
+                    find_message_in_buf::<crate::signal_generator::Message>(&bytes).is_ok(),
+                    "Failed to parse {}",
+                    bytes.escape_ascii()
+                );
+            }
+        }
+
+        // The last amplitude of the sweep looks like a \r, so it must not be stripped
+        sweep.push(b'\n');
+        assert!(matches!(
+            find_message_in_buf::<crate::spectrum_analyzer::Message>(&sweep),
+            Ok(crate::spectrum_analyzer::Message::Sweep(sweep)) if sweep.len() == 3
+        ));
+    }
+
+    #[test]
+    fn count_messages_that_fail_to_parse() {
+        let stream = ScriptedStream::new([
+            &b"#C2-M:006,005,01.12B26\r\n"[..],
+            b"#C2-F:5249000,0196428,-030,-118,0112,1,000,4850000,6100000,0600000,00200,0000,000\r\n",
+            b"#C2-F:not a config\r\n",
+            b"DSP:0\r\n",
+        ]);
+        let rfe = crate::SpectrumAnalyzer::connect_with_stream(stream).unwrap();
+
+        // The malformed Config is received before the DSP mode
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while rfe.dsp_mode().is_none() {
+            assert!(
+                Instant::now() < deadline,
+                "Timed out waiting for the DSP mode"
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(rfe.parse_error_count(), 1);
+    }
+
+    /// Records the messages that are received and that fail to parse.
+    #[derive(Debug, Default)]
+    struct RecordingMessageContainer {
+        messages: Mutex<Vec<crate::spectrum_analyzer::Message>>,
+        parse_failures: Mutex<Vec<Vec<u8>>>,
+    }
+
+    impl MessageContainer for RecordingMessageContainer {
+        type Message = crate::spectrum_analyzer::Message;
+        const DEVICE_TYPE: DeviceType = DeviceType::SpectrumAnalyzer;
+
+        fn cache_message(&self, message: Self::Message) {
+            self.messages.lock().unpoisoned().push(message);
+        }
+
+        fn wait_for_device_info(&self) -> ConnectionResult<()> {
+            Ok(())
+        }
+
+        fn message_parse_failed(&self, message: &[u8], _error: crate::Error) {
+            self.parse_failures
+                .lock()
+                .unpoisoned()
+                .push(message.to_vec());
+        }
+    }
+
+    #[test]
+    fn skip_recoverable_parse_errors() {
+        let config = b"#C2-F:5249000,0196428,-030,-118,0112,1,000,4850000,6100000,0600000,00200,0000,000\r\n";
+        let stream = ScriptedStream::new([
+            // An unknown message type is skipped without being reported
+            &b"#XYZ\r\n"[..],
+            // A sweep truncated by a Config is reported, and the Config is still received
+            b"$S\x70\xc8\xc8\xc8\xc8\r\n",
+            config,
+            // An invalid message is reported and discarded
+            b"#C2-F:not a config\r\n",
+            b"DSP:0\r\n",
+        ]);
+        let device = Device::<RecordingMessageContainer>::connect_with_stream(stream, b"").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while device.messages().messages.lock().unpoisoned().len() < 2 {
+            assert!(
+                Instant::now() < deadline,
+                "Timed out waiting for the messages"
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+        let messages = device.messages().messages.lock().unpoisoned();
+        assert!(matches!(
+            messages[..],
+            [
+                crate::spectrum_analyzer::Message::Config(_),
+                crate::spectrum_analyzer::Message::DspMode(_)
+            ]
+        ));
+        assert_eq!(
+            *device.messages().parse_failures.lock().unpoisoned(),
+            [
+                &b"$S\x70\xc8\xc8\xc8\xc8\r\n"[..],
+                b"#C2-F:not a config\r\n"
+            ]
+        );
+        assert_eq!(device.parse_error_count(), 2);
+    }
+
+    #[test]
+    fn devices_are_debug() {
+        fn assert_debug<T: Debug>() {}
+        assert_debug::<crate::SpectrumAnalyzer>();
+        assert_debug::<crate::SignalGenerator>();
+    }
+
+    #[test]
+    fn blocking_is_rejected_on_reader_thread() {
+        assert!(ensure_not_reader_thread().is_ok());
+        let result = thread::spawn(|| {
+            IS_READER_THREAD.set(true);
+            ensure_not_reader_thread()
+        })
+        .join()
+        .unwrap();
+        assert!(matches!(result, Err(crate::Error::InvalidOperation(_))));
+    }
+}
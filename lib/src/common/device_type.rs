@@ -0,0 +1,30 @@
+use std::fmt::Display;
+
+/// The kinds of RF Explorer devices.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DeviceType {
+    SpectrumAnalyzer,
+    SignalGenerator,
+}
+
+impl DeviceType {
+    /// Identifies the type of device that sent a message by its `SetupInfo` prefix.
+    pub(crate) fn from_setup_info_message(bytes: &[u8]) -> Option<DeviceType> {
+        if bytes.starts_with(b"#C2-M:") {
+            Some(DeviceType::SpectrumAnalyzer)
+        } else if bytes.starts_with(b"#C3-M:") {
+            Some(DeviceType::SignalGenerator)
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for DeviceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeviceType::SpectrumAnalyzer => write!(f, "SpectrumAnalyzer"),
+            DeviceType::SignalGenerator => write!(f, "SignalGenerator"),
+        }
+    }
+}
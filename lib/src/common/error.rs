@@ -12,6 +12,15 @@ pub enum Error {
     #[error("Invalid operation: {}", .0)]
     InvalidOperation(String),
 
+    #[error("Received an invalid message: {}", .0)]
+    InvalidMessage(String),
+
+    #[error("The RF Explorer adjusted a requested value: {}", .0)]
+    ValueClamped(String),
+
+    #[error("A callback panicked: {}", .0)]
+    CallbackPanicked(String),
+
     #[error(transparent)]
     Io(#[from] io::Error),
 
@@ -20,3 +29,33 @@ pub enum Error {
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error as _;
+
+    use super::*;
+
+    #[test]
+    fn error_can_be_boxed_as_a_thread_safe_error() {
+        fn assert_error<E: std::error::Error + Send + Sync + 'static>() {}
+        assert_error::<Error>();
+
+        let error: Box<dyn std::error::Error + Send + Sync> =
+            Box::new(Error::InvalidInput("bad input".to_string()));
+        assert!(error.source().is_none());
+        assert!(Error::InvalidOperation("bad operation".to_string())
+            .source()
+            .is_none());
+    }
+
+    #[test]
+    fn io_errors_convert_with_question_mark() {
+        fn fails() -> Result<()> {
+            Err(io::Error::from(io::ErrorKind::BrokenPipe))?
+        }
+        assert!(
+            matches!(fails(), Err(Error::Io(error)) if error.kind() == io::ErrorKind::BrokenPipe)
+        );
+    }
+}
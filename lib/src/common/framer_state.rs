@@ -0,0 +1,170 @@
+use std::time::{Duration, Instant};
+
+pub(crate) const DEFAULT_FRAMER_STALE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A snapshot of the framer that splits the bytes received from an RF Explorer into messages.
+///
+/// A connection that goes quiet while bytes are buffered is usually stuck waiting for the rest of
+/// a message that will never arrive.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct FramerState {
+    /// The number of bytes buffered for the message currently being received.
+    pub buffered_bytes: usize,
+    /// The first bytes of the message currently being received with non-printable bytes escaped,
+    /// e.g. `#C2-F:` for a `Config`, or `None` if no message is pending.
+    pub pending_prefix: Option<String>,
+    /// How long the message currently being received has been pending, or `None` if no message is
+    /// pending.
+    pub pending_for: Option<Duration>,
+}
+
+/// Tracks the message the thread reading from a device is accumulating.
+#[derive(Debug, Clone)]
+pub(crate) struct Framer {
+    buffered_bytes: usize,
+    pending_prefix: Vec<u8>,
+    pending_since: Option<Instant>,
+    last_progress_at: Option<Instant>,
+    stale_timeout: Option<Duration>,
+}
+
+impl Framer {
+    const MAX_PREFIX_LEN: usize = 6;
+
+    /// Records the bytes currently buffered for the next message.
+    pub(crate) fn update(&mut self, message_buf: &[u8], now: Instant) {
+        let has_progressed = message_buf.len() > self.buffered_bytes;
+        self.buffered_bytes = message_buf.len();
+        if message_buf.is_empty() {
+            self.pending_prefix.clear();
+            self.pending_since = None;
+            self.last_progress_at = None;
+            return;
+        }
+
+        self.pending_since.get_or_insert(now);
+        if has_progressed {
+            self.last_progress_at = Some(now);
+        }
+        if self.pending_prefix.len() < Self::MAX_PREFIX_LEN && !self.pending_prefix.ends_with(b":")
+        {
+            // Message prefixes end with a colon, e.g. "#C2-F:", and are followed by data
+            let prefix_len = message_buf
+                .iter()
+                .take(Self::MAX_PREFIX_LEN)
+                .position(|byte| *byte == b':')
+                .map_or(message_buf.len().min(Self::MAX_PREFIX_LEN), |colon| {
+                    colon + 1
+                });
+            self.pending_prefix = message_buf[..prefix_len].to_vec();
+        }
+    }
+
+    /// Returns `true` if no bytes of the pending message have arrived for longer than the stale
+    /// timeout.
+    ///
+    /// A large sweep can take longer than the timeout to arrive at a slow baud rate, so the timeout
+    /// is measured from the last byte received rather than the first.
+    pub(crate) fn is_stale(&self, now: Instant) -> bool {
+        match (self.last_progress_at, self.stale_timeout) {
+            (Some(last_progress_at), Some(stale_timeout)) => {
+                now.saturating_duration_since(last_progress_at) > stale_timeout
+            }
+            _ => false,
+        }
+    }
+
+    pub(crate) fn set_stale_timeout(&mut self, stale_timeout: Option<Duration>) {
+        self.stale_timeout = stale_timeout;
+    }
+
+    pub(crate) fn state(&self, now: Instant) -> FramerState {
+        FramerState {
+            buffered_bytes: self.buffered_bytes,
+            pending_prefix: self
+                .pending_since
+                .map(|_| self.pending_prefix.escape_ascii().to_string()),
+            pending_for: self
+                .pending_since
+                .map(|pending_since| now.saturating_duration_since(pending_since)),
+        }
+    }
+}
+
+impl Default for Framer {
+    fn default() -> Self {
+        Framer {
+            buffered_bytes: 0,
+            pending_prefix: Vec::new(),
+            pending_since: None,
+            last_progress_at: None,
+            stale_timeout: Some(DEFAULT_FRAMER_STALE_TIMEOUT),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn track_pending_message() {
+        let start = Instant::now();
+        let mut framer = Framer::default();
+        assert_eq!(framer.state(start), FramerState::default());
+
+        framer.update(b"#C2-F:5249", start);
+        framer.update(b"#C2-F:5249000,0196428", start + Duration::from_secs(1));
+        let state = framer.state(start + Duration::from_secs(2));
+        assert_eq!(state.buffered_bytes, 21);
+        assert_eq!(state.pending_prefix.as_deref(), Some("#C2-F:"));
+        assert_eq!(state.pending_for, Some(Duration::from_secs(2)));
+
+        let last_byte_at = start + Duration::from_secs(1);
+        assert!(!framer.is_stale(last_byte_at + DEFAULT_FRAMER_STALE_TIMEOUT));
+        assert!(
+            framer.is_stale(last_byte_at + DEFAULT_FRAMER_STALE_TIMEOUT + Duration::from_secs(1))
+        );
+        framer.set_stale_timeout(None);
+        assert!(!framer.is_stale(start + Duration::from_secs(3600)));
+
+        framer.update(&[], start + Duration::from_secs(3));
+        assert_eq!(framer.state(start), FramerState::default());
+    }
+
+    #[test]
+    fn slow_but_steady_message_is_not_stale() {
+        // A 4,096 point sweep takes about 17 seconds to arrive at 2,400 baud
+        let start = Instant::now();
+        let mut framer = Framer::default();
+        let mut message_buf = b"$z\x10\x00".to_vec();
+        for second in 0..17 {
+            message_buf.extend(std::iter::repeat_n(200, 240));
+            let now = start + Duration::from_secs(second);
+            framer.update(&message_buf, now);
+            assert!(!framer.is_stale(now + Duration::from_millis(900)));
+        }
+        assert_eq!(
+            framer.state(start + Duration::from_secs(17)).pending_for,
+            Some(Duration::from_secs(17))
+        );
+
+        // The same bytes without any more arriving become stale
+        let last_byte_at = start + Duration::from_secs(16);
+        framer.update(&message_buf, last_byte_at + DEFAULT_FRAMER_STALE_TIMEOUT);
+        assert!(
+            framer.is_stale(last_byte_at + DEFAULT_FRAMER_STALE_TIMEOUT + Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn escape_binary_prefix() {
+        let mut framer = Framer::default();
+        let now = Instant::now();
+        framer.update(b"$S\x70\xff\x80\x81\x82", now);
+        assert_eq!(
+            framer.state(now).pending_prefix.as_deref(),
+            Some("$Sp\\xff\\x80\\x81")
+        );
+    }
+}
@@ -1,14 +1,28 @@
 use std::fmt::Debug;
 
-use nom::{error::Error, Err};
+use nom::{
+    error::{Error, ErrorKind},
+    Err,
+};
 use thiserror::Error;
 
-use super::ConnectionResult;
+use super::{ConnectionResult, DeviceType};
 
 pub trait MessageContainer: Default + Debug + Send + Sync {
     type Message: for<'a> TryFrom<&'a [u8], Error = MessageParseError<'a>> + Debug;
+    const DEVICE_TYPE: DeviceType;
     fn cache_message(&self, message: Self::Message);
     fn wait_for_device_info(&self) -> ConnectionResult<()>;
+
+    /// Called with the baud rate of the serial connection when connecting and whenever it changes.
+    fn baud_rate_changed(&self, _baud_rate: u32) {}
+
+    /// Called when the device reports that it's a different type of RF Explorer, so that waiting
+    /// for its device info can stop early.
+    fn other_device_type_found(&self) {}
+
+    /// Called with the bytes of a message received from the device that failed to parse.
+    fn message_parse_failed(&self, _message: &[u8], _error: crate::Error) {}
 }
 
 #[derive(Error, Debug, Eq, PartialEq)]
@@ -19,18 +33,89 @@ pub enum MessageParseError<'a> {
     #[error("Attempted to parse a truncated message")]
     Truncated { remainder: Option<&'a [u8]> },
 
-    #[error("Attempted to parse an invalid message")]
-    Invalid,
+    #[error("Attempted to parse an invalid message, expected {expected}")]
+    Invalid {
+        /// The bytes remaining in the message where parsing failed.
+        remainder: &'a [u8],
+        /// A description of what the parser expected to find at the start of `remainder`.
+        expected: &'static str,
+    },
 
     #[error("Attempted to parse an unknown message type")]
     UnknownMessageType,
 }
 
-impl<'a> From<Err<Error<&[u8]>>> for MessageParseError<'a> {
-    fn from(error: Err<Error<&[u8]>>) -> Self {
+impl<'a> MessageParseError<'a> {
+    /// Returns `true` if parsing can move on to the next message after this error.
+    ///
+    /// Unknown message types can be skipped, and a truncated message can be skipped if it was
+    /// followed by the start of another message. `Incomplete` errors aren't recoverable since more
+    /// bytes need to be read before the message can be parsed, and `Invalid` errors indicate that
+    /// the message was corrupted.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            MessageParseError::UnknownMessageType => true,
+            MessageParseError::Truncated { remainder } => remainder.is_some(),
+            MessageParseError::Incomplete | MessageParseError::Invalid { .. } => false,
+        }
+    }
+
+    /// Returns the byte offset into `message` at which an invalid message failed to parse.
+    ///
+    /// `None` is returned if the error isn't `Invalid` or `message` isn't the message that failed
+    /// to parse.
+    pub fn offset(&self, message: &[u8]) -> Option<usize> {
+        let MessageParseError::Invalid { remainder, .. } = self else {
+            return None;
+        };
+        let offset = (remainder.as_ptr() as usize).checked_sub(message.as_ptr() as usize)?;
+        (offset + remainder.len() <= message.len()).then_some(offset)
+    }
+}
+
+impl<'a> From<Err<Error<&'a [u8]>>> for MessageParseError<'a> {
+    fn from(error: Err<Error<&'a [u8]>>) -> Self {
         match error {
             Err::Incomplete(_) => MessageParseError::Incomplete,
-            _ => MessageParseError::Invalid,
+            Err::Error(error) | Err::Failure(error) => MessageParseError::Invalid {
+                remainder: error.input,
+                expected: expected_description(error.code),
+            },
+        }
+    }
+}
+
+fn expected_description(error_kind: ErrorKind) -> &'static str {
+    match error_kind {
+        ErrorKind::Tag => "a message prefix or separator",
+        ErrorKind::Alt => "one of the allowed values",
+        ErrorKind::Digit | ErrorKind::MapRes | ErrorKind::MapOpt => "a number",
+        ErrorKind::Float => "a decimal number",
+        ErrorKind::Char | ErrorKind::OneOf | ErrorKind::NoneOf => "a particular character",
+        ErrorKind::CrLf => "a line ending",
+        ErrorKind::Eof => "the end of the message",
+        ErrorKind::LengthValue | ErrorKind::Count => "as many bytes as the message's length",
+        _ => "a valid value",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_skippable_errors_are_recoverable() {
+        assert!(MessageParseError::UnknownMessageType.is_recoverable());
+        assert!(MessageParseError::Truncated {
+            remainder: Some(b"$S")
+        }
+        .is_recoverable());
+        assert!(!MessageParseError::Truncated { remainder: None }.is_recoverable());
+        assert!(!MessageParseError::Incomplete.is_recoverable());
+        assert!(!MessageParseError::Invalid {
+            remainder: b"",
+            expected: "a number"
         }
+        .is_recoverable());
     }
 }
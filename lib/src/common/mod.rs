@@ -1,12 +1,27 @@
+mod capture;
+mod connect_options;
 mod device;
+mod device_type;
 mod error;
+mod framer_state;
 mod frequency;
 mod message;
 mod serial_port;
+mod stream_port;
+pub(crate) mod sync;
+#[cfg(test)]
+pub(crate) mod test_support;
 
+pub use capture::{parse_capture, parse_capture_file, CaptureMessages, OwnedMessageParseError};
+pub use connect_options::ConnectOptions;
 pub use device::Device;
+pub(crate) use device::{ensure_not_reader_thread, DEFAULT_MAX_CONCURRENT_CONNECTIONS};
+pub use device_type::DeviceType;
 pub use error::{Error, Result};
+pub(crate) use framer_state::Framer;
+pub use framer_state::FramerState;
 pub use frequency::Frequency;
 pub use message::{MessageContainer, MessageParseError};
 pub use serial_port::{is_driver_installed, port_names, ConnectionError, ConnectionResult};
 pub(crate) use serial_port::{BaudRate, SerialPort};
+pub(crate) use sync::LockResultExt;
@@ -0,0 +1,90 @@
+use std::sync::{LockResult, PoisonError};
+
+// The message containers are built from these types so that their notify and wait paths can be
+// checked with loom by building with `RUSTFLAGS="--cfg loom"`
+#[cfg(not(loom))]
+pub(crate) use std::sync::{atomic, Condvar, Mutex, MutexGuard, WaitTimeoutResult};
+
+#[cfg(loom)]
+pub(crate) use loom::sync::{atomic, Mutex, MutexGuard};
+#[cfg(loom)]
+pub(crate) use loom_condvar::{Condvar, WaitTimeoutResult};
+
+// Loom's Arc has no Weak, which the reader threads use to refer to the message containers, so both
+// builds share std's Arc
+pub(crate) use std::sync::Arc;
+
+/// Recovers the result of a lock operation when the lock is poisoned.
+///
+/// A lock is poisoned when a thread panics while holding it, e.g. when a user's callback
+/// panics. The cached messages are still valid in that case, so the poison is ignored instead of
+/// propagating the panic to every later call.
+pub(crate) trait LockResultExt<T> {
+    fn unpoisoned(self) -> T;
+}
+
+impl<T> LockResultExt<T> for LockResult<T> {
+    fn unpoisoned(self) -> T {
+        self.unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(loom)]
+mod loom_condvar {
+    use std::{sync::LockResult, time::Duration};
+
+    use super::{LockResultExt, MutexGuard};
+
+    /// A `Condvar` with the `std` methods that loom's `Condvar` is missing.
+    ///
+    /// Loom doesn't model time, so timed waits only return once the condition is false and never
+    /// time out. A notification that's missed by a waiter shows up as a deadlock in the model.
+    #[derive(Debug, Default)]
+    pub(crate) struct Condvar(loom::sync::Condvar);
+
+    #[derive(Debug, Clone, Copy)]
+    pub(crate) struct WaitTimeoutResult(bool);
+
+    impl WaitTimeoutResult {
+        pub(crate) fn timed_out(&self) -> bool {
+            self.0
+        }
+    }
+
+    impl Condvar {
+        pub(crate) fn notify_all(&self) {
+            self.0.notify_all();
+        }
+
+        pub(crate) fn wait_while<'a, T>(
+            &self,
+            mut guard: MutexGuard<'a, T>,
+            mut condition: impl FnMut(&mut T) -> bool,
+        ) -> LockResult<MutexGuard<'a, T>> {
+            while condition(&mut *guard) {
+                guard = self.0.wait(guard).unpoisoned();
+            }
+            Ok(guard)
+        }
+
+        pub(crate) fn wait_timeout<'a, T>(
+            &self,
+            guard: MutexGuard<'a, T>,
+            _timeout: Duration,
+        ) -> LockResult<(MutexGuard<'a, T>, WaitTimeoutResult)> {
+            Ok((self.0.wait(guard).unpoisoned(), WaitTimeoutResult(false)))
+        }
+
+        pub(crate) fn wait_timeout_while<'a, T>(
+            &self,
+            guard: MutexGuard<'a, T>,
+            _timeout: Duration,
+            condition: impl FnMut(&mut T) -> bool,
+        ) -> LockResult<(MutexGuard<'a, T>, WaitTimeoutResult)> {
+            Ok((
+                self.wait_while(guard, condition).unpoisoned(),
+                WaitTimeoutResult(false),
+            ))
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::{
+    collections::VecDeque,
+    io::{self, ErrorKind},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use chrono::DateTime;
+
+use super::LockResultExt;
+use crate::spectrum_analyzer::Sweep;
+
+/// A stream that replies to every read with the next scripted bytes, then times out like a serial
+/// port.
+///
+/// Clones share the bytes written to the stream, so a test can keep a clone to check the
+/// commands that were sent.
+#[derive(Debug, Clone)]
+pub(crate) struct ScriptedStream {
+    reads: Arc<Mutex<VecDeque<&'static [u8]>>>,
+    written: Arc<Mutex<Vec<u8>>>,
+}
+
+impl ScriptedStream {
+    pub(crate) fn new(reads: impl IntoIterator<Item = &'static [u8]>) -> Self {
+        ScriptedStream {
+            reads: Arc::new(Mutex::new(reads.into_iter().collect())),
+            written: Arc::default(),
+        }
+    }
+
+    /// Returns every byte written to the stream so far.
+    pub(crate) fn written(&self) -> Vec<u8> {
+        self.written.lock().unpoisoned().clone()
+    }
+}
+
+impl io::Read for ScriptedStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut reads = self.reads.lock().unpoisoned();
+        let Some(bytes) = reads.pop_front() else {
+            drop(reads);
+            thread::sleep(Duration::from_millis(10));
+            return Err(ErrorKind::TimedOut.into());
+        };
+        // Bytes that don't fit in the buffer are returned by the next read
+        let len = bytes.len().min(buf.len());
+        buf[..len].copy_from_slice(&bytes[..len]);
+        if len < bytes.len() {
+            reads.push_front(&bytes[len..]);
+        }
+        Ok(len)
+    }
+}
+
+impl io::Write for ScriptedStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.written.lock().unpoisoned().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Returns a sweep with the given amplitudes.
+///
+/// Every sweep has the same timestamp, so sweeps with the same amplitudes are equal.
+pub(crate) fn sweep(amplitudes_dbm: &[f32]) -> Sweep {
+    Sweep::from_amplitudes(amplitudes_dbm.to_vec(), DateTime::UNIX_EPOCH).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    #[test]
+    fn scripted_reads_fit_in_a_short_buffer() {
+        let mut stream = ScriptedStream::new([&b"ab"[..], b"c"]);
+        let mut buf = [0; 1];
+        let mut bytes = Vec::new();
+        while let Ok(len) = stream.read(&mut buf) {
+            assert_eq!(len, 1);
+            bytes.push(buf[0]);
+        }
+        assert_eq!(bytes, b"abc");
+    }
+}
@@ -4,6 +4,6 @@ pub mod signal_generator;
 pub mod spectrum_analyzer;
 
 pub use common::*;
-pub use rf_explorer::ScreenData;
+pub use rf_explorer::{RfeDevice, ScreenData, SerialNumber, SetupInfo};
 pub use signal_generator::SignalGenerator;
 pub use spectrum_analyzer::SpectrumAnalyzer;
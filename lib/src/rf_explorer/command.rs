@@ -33,6 +33,13 @@ impl From<Command> for Cow<'static, [u8]> {
     }
 }
 
+/// Splits the comma separated ASCII fields of a command that starts with `#`, its length, and
+/// `prefix`.
+pub(crate) fn command_fields<'a>(bytes: &'a [u8], prefix: &[u8]) -> Option<Vec<&'a str>> {
+    let fields = bytes.get(2..)?.strip_prefix(prefix)?;
+    Some(std::str::from_utf8(fields).ok()?.split(',').collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
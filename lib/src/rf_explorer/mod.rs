@@ -1,21 +1,43 @@
 mod command;
 pub(crate) mod parsers;
+mod rfe_device;
 mod screen_data;
 mod serial_number;
 mod setup_info;
 
-pub(crate) use command::Command;
+pub(crate) use command::{command_fields, Command};
+pub use rfe_device::RfeDevice;
+pub(crate) use screen_data::wait_for_next_screen_data;
 pub use screen_data::ScreenData;
-pub(crate) use serial_number::SerialNumber;
-pub(crate) use setup_info::SetupInfo;
+pub use serial_number::SerialNumber;
+pub use setup_info::SetupInfo;
 
-use std::time::Duration;
+use std::{
+    panic::{self, AssertUnwindSafe},
+    time::Duration,
+};
 
 pub(crate) type Callback<T> = Option<Box<dyn FnMut(T) + Send + 'static>>;
 pub(crate) const NEXT_SCREEN_DATA_TIMEOUT: Duration = Duration::from_secs(2);
 pub(crate) const COMMAND_RESPONSE_TIMEOUT: Duration = Duration::from_secs(2);
 pub(crate) const RECEIVE_INITIAL_DEVICE_INFO_TIMEOUT: Duration = Duration::from_secs(2);
 
+/// Calls a user's callback, logging an error instead of unwinding if it panics so that the thread
+/// reading messages from the RF Explorer keeps running.
+///
+/// A panic is returned as an `Error::CallbackPanicked` so that it can be reported to the user.
+pub(crate) fn call_callback(name: &str, cb: impl FnOnce()) -> crate::Result<()> {
+    panic::catch_unwind(AssertUnwindSafe(cb)).map_err(|payload| {
+        let reason = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown reason");
+        tracing::error!("The {name} callback panicked: {reason}");
+        crate::Error::CallbackPanicked(format!("the {name} callback panicked: {reason}"))
+    })
+}
+
 macro_rules! impl_rf_explorer {
     ($rf_explorer:ident, $message_container:ty) => {
         use crate::common::BaudRate;
@@ -30,6 +52,50 @@ macro_rules! impl_rf_explorer {
                 })
             }
 
+            /// Connects to the first available RF Explorer whose `SetupInfo` satisfies the
+            /// predicate.
+            ///
+            /// Ports are connected to one at a time, and RF Explorers that don't satisfy the
+            /// predicate are disconnected before the next port is tried.
+            pub fn connect_first_matching(
+                predicate: impl Fn(&rf_explorer::SetupInfo<Model>) -> bool,
+            ) -> Option<Self> {
+                Device::connect_all(Cow::from(rf_explorer::Command::RequestConfig))
+                    .filter_map(|rfe| rfe.ok())
+                    .map(|rfe| Self { rfe })
+                    .find(|rfe| {
+                        rfe.setup_info()
+                            .is_some_and(|setup_info| predicate(&setup_info))
+                    })
+            }
+
+            /// Connects to every available RF Explorer, attempting up to 4 connections at a time.
+            ///
+            /// Ports that are in use by another process are returned as
+            /// `ConnectionError::PortBusy` errors, while ports without an RF Explorer are skipped.
+            pub fn connect_all() -> Vec<ConnectionResult<Self>> {
+                Self::connect_all_with_concurrency(
+                    crate::common::DEFAULT_MAX_CONCURRENT_CONNECTIONS,
+                )
+            }
+
+            /// Connects to every available RF Explorer, attempting up to `max_concurrent`
+            /// connections at a time.
+            ///
+            /// Connecting to a port can take a few seconds, so connecting to several ports at once
+            /// is faster when many are available.
+            pub fn connect_all_with_concurrency(
+                max_concurrent: usize,
+            ) -> Vec<ConnectionResult<Self>> {
+                Device::connect_all_with_concurrency(
+                    Cow::from(rf_explorer::Command::RequestConfig),
+                    max_concurrent,
+                )
+                .into_iter()
+                .map(|rfe| rfe.map(|rfe| Self { rfe }))
+                .collect()
+            }
+
             /// Connects to the first available RF Explorer with the given name while using the given baud rate.
             pub fn connect_with_name_and_baud_rate(
                 name: &str,
@@ -44,6 +110,39 @@ macro_rules! impl_rf_explorer {
                 })
             }
 
+            /// Connects to the RF Explorer with the given name using the given baud rate and
+            /// connection options.
+            pub fn connect_with_options(
+                name: &str,
+                baud_rate: u32,
+                options: &crate::ConnectOptions,
+            ) -> ConnectionResult<Self> {
+                Ok(Self {
+                    rfe: Device::connect_with_options(
+                        name,
+                        baud_rate,
+                        options,
+                        Cow::from(rf_explorer::Command::RequestConfig),
+                    )?,
+                })
+            }
+
+            /// Connects to an RF Explorer over a byte stream instead of a serial port, e.g. to a
+            /// simulated device in tests or to a serial port shared over a network.
+            ///
+            /// Reads from the stream should fail with `ErrorKind::TimedOut` when no bytes arrive
+            /// for a while, like a serial port's reads do, so that commands can be sent to it.
+            pub fn connect_with_stream(
+                stream: impl io::Read + io::Write + Send + 'static,
+            ) -> ConnectionResult<Self> {
+                Ok(Self {
+                    rfe: Device::connect_with_stream(
+                        stream,
+                        Cow::from(rf_explorer::Command::RequestConfig),
+                    )?,
+                })
+            }
+
             fn messages(&self) -> &$message_container {
                 self.rfe.messages()
             }
@@ -58,14 +157,39 @@ macro_rules! impl_rf_explorer {
                 self.rfe.baud_rate()
             }
 
+            /// The number of messages received from the RF Explorer since connecting that could not be
+            /// parsed.
+            ///
+            /// Incomplete messages that are still being received are not counted.
+            pub fn parse_error_count(&self) -> u64 {
+                self.rfe.parse_error_count()
+            }
+
+            /// Reports the bytes buffered for the message currently being received from the RF
+            /// Explorer, which helps to diagnose a connection that has stopped producing messages.
+            pub fn framer_state(&self) -> crate::FramerState {
+                self.rfe.framer_state()
+            }
+
+            /// Sets how long a partially received message can go without receiving more bytes
+            /// before it's discarded and reported as an invalid message, or `None` to wait
+            /// indefinitely.
+            ///
+            /// Defaults to 10 seconds.
+            pub fn set_framer_stale_timeout(&self, stale_timeout: Option<Duration>) {
+                self.rfe.set_framer_stale_timeout(stale_timeout)
+            }
+
             /// Sets the baud rate of the serial connection to the RF Explorer.
             pub fn set_baud_rate(&self, baud_rate: u32) -> crate::Result<()> {
                 let baud_rate = BaudRate::try_from(baud_rate)?;
                 self.send_command(rf_explorer::Command::SetBaudRate { baud_rate })?;
-                self.rfe
-                    .serial_port()
-                    .set_baud_rate(baud_rate.bps())
-                    .map_err(crate::Error::from)
+                self.rfe.serial_port().set_baud_rate(baud_rate.bps())?;
+                crate::common::MessageContainer::baud_rate_changed(
+                    self.rfe.messages(),
+                    baud_rate.bps(),
+                );
+                Ok(())
             }
 
             /// Sends a command to the RF Explorer.
@@ -103,6 +227,18 @@ macro_rules! impl_rf_explorer {
                     .send_command(rf_explorer::Command::DisableDumpScreen)
             }
 
+            /// Captures the RF Explorer's screen and saves it to a file as a binary PBM (portable
+            /// bitmap) image.
+            ///
+            /// The screen dump is enabled until the next `ScreenData` is received and disabled
+            /// afterwards.
+            pub fn save_screenshot(&self, path: impl AsRef<std::path::Path>) -> crate::Result<()> {
+                self.enable_dump_screen()?;
+                let screen_data = self.wait_for_next_screen_data();
+                self.disable_dump_screen()?;
+                screen_data?.save_pbm(path).map_err(crate::Error::from)
+            }
+
             /// Tells the RF Explorer to stop collecting data.
             pub fn hold(&self) -> io::Result<()> {
                 self.rfe.send_command(rf_explorer::Command::Hold)
@@ -118,6 +254,85 @@ macro_rules! impl_rf_explorer {
                 self.rfe.send_command(rf_explorer::Command::PowerOff)
             }
         }
+
+        impl rf_explorer::RfeDevice for $rf_explorer {
+            type Model = Model;
+
+            fn port_name(&self) -> &str {
+                $rf_explorer::port_name(self)
+            }
+
+            fn baud_rate(&self) -> io::Result<u32> {
+                $rf_explorer::baud_rate(self)
+            }
+
+            fn set_baud_rate(&self, baud_rate: u32) -> crate::Result<()> {
+                $rf_explorer::set_baud_rate(self, baud_rate)
+            }
+
+            fn parse_error_count(&self) -> u64 {
+                $rf_explorer::parse_error_count(self)
+            }
+
+            fn send_bytes(&self, bytes: &[u8]) -> io::Result<()> {
+                $rf_explorer::send_bytes(self, bytes)
+            }
+
+            fn serial_number(&self) -> Option<String> {
+                $rf_explorer::serial_number(self)
+            }
+
+            fn firmware_version(&self) -> String {
+                $rf_explorer::firmware_version(self)
+            }
+
+            fn setup_info(&self) -> Option<rf_explorer::SetupInfo<Model>> {
+                $rf_explorer::setup_info(self)
+            }
+
+            fn screen_data(&self) -> Option<rf_explorer::ScreenData> {
+                $rf_explorer::screen_data(self)
+            }
+
+            fn wait_for_next_screen_data(&self) -> crate::Result<rf_explorer::ScreenData> {
+                $rf_explorer::wait_for_next_screen_data(self)
+            }
+
+            fn wait_for_next_screen_data_with_timeout(
+                &self,
+                timeout: Duration,
+            ) -> crate::Result<rf_explorer::ScreenData> {
+                $rf_explorer::wait_for_next_screen_data_with_timeout(self, timeout)
+            }
+
+            fn lcd_on(&self) -> io::Result<()> {
+                $rf_explorer::lcd_on(self)
+            }
+
+            fn lcd_off(&self) -> io::Result<()> {
+                $rf_explorer::lcd_off(self)
+            }
+
+            fn enable_dump_screen(&self) -> io::Result<()> {
+                $rf_explorer::enable_dump_screen(self)
+            }
+
+            fn disable_dump_screen(&self) -> io::Result<()> {
+                $rf_explorer::disable_dump_screen(self)
+            }
+
+            fn hold(&self) -> io::Result<()> {
+                $rf_explorer::hold(self)
+            }
+
+            fn reboot(&self) -> io::Result<()> {
+                $rf_explorer::reboot(self)
+            }
+
+            fn power_off(&self) -> io::Result<()> {
+                $rf_explorer::power_off(self)
+            }
+        }
     };
 }
 
@@ -1,4 +1,5 @@
 use nom::{
+    branch::alt,
     bytes::complete::{tag, take},
     character::complete::line_ending,
     combinator::{all_consuming, map_res, opt},
@@ -10,8 +11,18 @@ pub(crate) fn parse_comma(bytes: &[u8]) -> IResult<&[u8], &[u8]> {
     tag(",")(bytes)
 }
 
+/// Consumes an optional \r\n, \n, or \r line ending and makes sure there aren't any bytes left.
 pub(crate) fn parse_opt_line_ending(bytes: &[u8]) -> IResult<&[u8], Option<&[u8]>> {
-    all_consuming(opt(line_ending))(bytes)
+    all_consuming(opt(alt((line_ending, tag("\r")))))(bytes)
+}
+
+/// Removes a single \r\n, \n, or \r line ending from the end of a message.
+pub(crate) fn strip_line_ending(bytes: &[u8]) -> &[u8] {
+    bytes
+        .strip_suffix(b"\r\n")
+        .or_else(|| bytes.strip_suffix(b"\n"))
+        .or_else(|| bytes.strip_suffix(b"\r"))
+        .unwrap_or(bytes)
 }
 
 pub(crate) fn parse_num<'a, T>(digits: u8) -> impl FnMut(&'a [u8]) -> IResult<&'a [u8], T>
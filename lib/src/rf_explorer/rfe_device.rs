@@ -0,0 +1,71 @@
+use std::{fmt::Debug, io, time::Duration};
+
+use super::{ScreenData, SetupInfo};
+
+/// Functionality shared by every kind of RF Explorer.
+///
+/// Implemented by both [`SpectrumAnalyzer`](crate::SpectrumAnalyzer) and
+/// [`SignalGenerator`](crate::SignalGenerator) so that tooling that only needs the common
+/// surface can be written once.
+pub trait RfeDevice {
+    /// The model type reported in this device's `SetupInfo`.
+    type Model: Debug + Clone + Copy + TryFrom<u8> + PartialEq + Eq + Default;
+
+    /// The name of the serial port through which the RF Explorer is connected.
+    fn port_name(&self) -> &str;
+
+    /// The baud rate of the serial connection to the RF Explorer.
+    fn baud_rate(&self) -> io::Result<u32>;
+
+    /// Sets the baud rate of the serial connection to the RF Explorer.
+    fn set_baud_rate(&self, baud_rate: u32) -> crate::Result<()>;
+
+    /// The number of messages received from the RF Explorer since connecting that could not be
+    /// parsed.
+    fn parse_error_count(&self) -> u64;
+
+    /// Sends bytes to the RF Explorer.
+    fn send_bytes(&self, bytes: &[u8]) -> io::Result<()>;
+
+    /// Returns the RF Explorer's serial number, if it exists.
+    fn serial_number(&self) -> Option<String>;
+
+    /// The firmware version of the RF Explorer.
+    fn firmware_version(&self) -> String;
+
+    /// The `SetupInfo` reported by the RF Explorer.
+    fn setup_info(&self) -> Option<SetupInfo<Self::Model>>;
+
+    /// Returns the most recent `ScreenData` captured by the RF Explorer.
+    fn screen_data(&self) -> Option<ScreenData>;
+
+    /// Waits for the RF Explorer to capture its next `ScreenData`.
+    fn wait_for_next_screen_data(&self) -> crate::Result<ScreenData>;
+
+    /// Waits up to `timeout` for the RF Explorer to capture its next `ScreenData`.
+    fn wait_for_next_screen_data_with_timeout(
+        &self,
+        timeout: Duration,
+    ) -> crate::Result<ScreenData>;
+
+    /// Turns the RF Explorer's LCD on.
+    fn lcd_on(&self) -> io::Result<()>;
+
+    /// Turns the RF Explorer's LCD off.
+    fn lcd_off(&self) -> io::Result<()>;
+
+    /// Tells the RF Explorer to start sending `ScreenData`.
+    fn enable_dump_screen(&self) -> io::Result<()>;
+
+    /// Tells the RF Explorer to stop sending `ScreenData`.
+    fn disable_dump_screen(&self) -> io::Result<()>;
+
+    /// Tells the RF Explorer to stop collecting data.
+    fn hold(&self) -> io::Result<()>;
+
+    /// Reboots the RF Explorer.
+    fn reboot(&self) -> io::Result<()>;
+
+    /// Turns the RF Explorer's power off.
+    fn power_off(&self) -> io::Result<()>;
+}
@@ -1,10 +1,17 @@
-use std::convert::TryInto;
+use std::{
+    convert::TryInto,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
 
 use chrono::{DateTime, Utc};
 use nom::{bytes::complete::tag, bytes::streaming::take, combinator::map_res};
 
 use super::parsers::*;
-use crate::common::MessageParseError;
+use crate::common::sync::{Condvar, Mutex};
+use crate::common::{LockResultExt, MessageParseError};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ScreenData {
@@ -50,6 +57,51 @@ impl ScreenData {
     pub fn timestamp(&self) -> DateTime<Utc> {
         self.timestamp
     }
+
+    /// Writes the screen as a binary PBM (portable bitmap) image, where pixels that are on are black.
+    pub fn write_pbm(&self, mut writer: impl Write) -> io::Result<()> {
+        write!(writer, "P4\n{} {}\n", Self::WIDTH_PX, Self::HEIGHT_PX)?;
+        for y in 0..Self::HEIGHT_PX {
+            let row = (0..Self::WIDTH_PX)
+                .collect::<Vec<_>>()
+                .chunks(8)
+                .map(|xs| {
+                    xs.iter().enumerate().fold(0u8, |byte, (bit, &x)| {
+                        byte | (u8::from(self.get_pixel(x, y)) << (7 - bit))
+                    })
+                })
+                .collect::<Vec<_>>();
+            writer.write_all(&row)?;
+        }
+        writer.flush()
+    }
+
+    /// Saves the screen to a file as a binary PBM (portable bitmap) image.
+    pub fn save_pbm(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write_pbm(BufWriter::new(File::create(path)?))
+    }
+}
+
+/// Waits for `ScreenData` different from the currently cached `ScreenData` to be received or for the
+/// timeout duration to elapse.
+pub(crate) fn wait_for_next_screen_data(
+    screen_data: &(Mutex<Option<ScreenData>>, Condvar),
+    timeout: Duration,
+) -> crate::Result<ScreenData> {
+    crate::common::ensure_not_reader_thread()?;
+    let (screen_data, condvar) = screen_data;
+    let screen_data = screen_data.lock().unpoisoned();
+    let previous_screen_data = screen_data.clone();
+    let (screen_data, wait_result) = condvar
+        .wait_timeout_while(screen_data, timeout, |screen_data| {
+            *screen_data == previous_screen_data || screen_data.is_none()
+        })
+        .unpoisoned();
+
+    match &*screen_data {
+        Some(screen_data) if !wait_result.timed_out() => Ok(screen_data.clone()),
+        _ => Err(crate::Error::TimedOut(timeout)),
+    }
 }
 
 impl<'a> TryFrom<&'a [u8]> for ScreenData {
@@ -82,3 +134,28 @@ impl<'a> TryFrom<&'a [u8]> for ScreenData {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_screen_data_as_pbm() {
+        let mut bytes = b"$D".to_vec();
+        let mut screen = vec![0; ScreenData::ROWS * ScreenData::COLUMNS];
+        // Turn on the top-left pixel and the pixel at (9, 1)
+        screen[0] = 0b1;
+        screen[9] = 0b10;
+        bytes.extend(screen);
+        let screen_data = ScreenData::try_from(bytes.as_slice()).unwrap();
+
+        let mut pbm = Vec::new();
+        screen_data.write_pbm(&mut pbm).unwrap();
+        let (header, pixels) = pbm.split_at(b"P4\n128 64\n".len());
+        assert_eq!(header, b"P4\n128 64\n");
+        assert_eq!(pixels.len(), 16 * 64);
+        assert_eq!(pixels[0], 0b1000_0000);
+        assert_eq!(pixels[16 + 1], 0b0100_0000);
+        assert_eq!(pixels.iter().filter(|&&byte| byte != 0).count(), 2);
+    }
+}
@@ -11,15 +11,34 @@ use crate::common::MessageParseError;
 use crate::spectrum_analyzer::Model;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) struct SetupInfo<
-    M: Debug + Clone + Copy + TryFrom<u8> + PartialEq + Eq + Default = Model,
-> {
+pub struct SetupInfo<M: Debug + Clone + Copy + TryFrom<u8> + PartialEq + Eq + Default = Model> {
     pub main_radio_model: Option<M>,
     pub expansion_radio_model: Option<M>,
     pub firmware_version: String,
 }
 
 impl<M: Debug + Copy + TryFrom<u8> + Eq + PartialEq + Default> SetupInfo<M> {
+    /// The firmware version of the main radio module.
+    ///
+    /// This is the same as `firmware_version` unless the expansion radio module reports its own
+    /// firmware version, e.g. `"01.15,02.03"`.
+    pub fn main_firmware_version(&self) -> &str {
+        self.firmware_versions().0
+    }
+
+    /// The firmware version of the expansion radio module, if it reports one separately from the
+    /// main radio module.
+    pub fn expansion_firmware_version(&self) -> Option<&str> {
+        self.firmware_versions().1
+    }
+
+    fn firmware_versions(&self) -> (&str, Option<&str>) {
+        match self.firmware_version.split_once(',') {
+            Some((main, expansion)) => (main.trim(), Some(expansion.trim())),
+            None => (self.firmware_version.as_str(), None),
+        }
+    }
+
     pub(crate) fn try_from_with_prefix<'a>(
         bytes: &'a [u8],
         prefix: &'static [u8],
@@ -1,9 +1,11 @@
-use super::{Attenuation, PowerLevel};
+use super::{Attenuation, PowerLevel, SweepDelay};
 use crate::common::Frequency;
-use std::{borrow::Cow, time::Duration};
+use crate::rf_explorer::command_fields;
+use std::borrow::Cow;
 
+/// A command that can be sent to a signal generator.
 #[derive(Debug, Copy, Clone, PartialEq)]
-pub(crate) enum Command {
+pub enum Command {
     RfPowerOn,
     RfPowerOff,
     StartAmpSweep {
@@ -12,14 +14,14 @@ pub(crate) enum Command {
         start_power_level: PowerLevel,
         stop_attenuation: Attenuation,
         stop_power_level: PowerLevel,
-        step_delay: Duration,
+        step_delay: SweepDelay,
     },
     StartAmpSweepExp {
         cw: Frequency,
         start_power_dbm: f64,
         step_power_db: f64,
         stop_power_dbm: f64,
-        step_delay: Duration,
+        step_delay: SweepDelay,
     },
     StartCw {
         cw: Frequency,
@@ -36,14 +38,14 @@ pub(crate) enum Command {
         power_level: PowerLevel,
         sweep_steps: u16,
         step: Frequency,
-        step_delay: Duration,
+        step_delay: SweepDelay,
     },
     StartFreqSweepExp {
         start: Frequency,
         power_dbm: f64,
         sweep_steps: u16,
         step: Frequency,
-        step_delay: Duration,
+        step_delay: SweepDelay,
     },
     StartTracking {
         start: Frequency,
@@ -61,6 +63,120 @@ pub(crate) enum Command {
     TrackingStep(u16),
 }
 
+impl Command {
+    /// Returns the bytes that are sent to the RF Explorer for this command.
+    pub fn encode(&self) -> Vec<u8> {
+        Cow::from(*self).into_owned()
+    }
+
+    /// Interprets bytes sent to an RF Explorer as a command.
+    ///
+    /// `None` is returned if the bytes aren't a valid signal generator command.
+    pub fn decode(bytes: &[u8]) -> Option<Command> {
+        // The length field isn't checked because some generator commands are sent with an
+        // incorrect length
+        if bytes.first() != Some(&b'#') {
+            return None;
+        }
+
+        match bytes.get(2..)? {
+            b"CP1" => return Some(Command::RfPowerOn),
+            b"CP0" => return Some(Command::RfPowerOff),
+            [b'k', high, low] => {
+                return Some(Command::TrackingStep(u16::from_be_bytes([*high, *low])))
+            }
+            _ => (),
+        }
+
+        let khz = |field: &str| field.parse().ok().map(Frequency::from_khz);
+        let millis = |field: &str| SweepDelay::from_millis(field.parse().ok()?).ok();
+        let attenuation = |field: &str| Attenuation::try_from(field.parse::<u8>().ok()?).ok();
+        let power_level = |field: &str| PowerLevel::try_from(field.parse::<u8>().ok()?).ok();
+
+        if let Some(fields) = command_fields(bytes, b"C3-A:") {
+            let [cw, start_att, start_power, stop_att, stop_power, step_delay] = fields[..] else {
+                return None;
+            };
+            Some(Command::StartAmpSweep {
+                cw: khz(cw)?,
+                start_attenuation: attenuation(start_att)?,
+                start_power_level: power_level(start_power)?,
+                stop_attenuation: attenuation(stop_att)?,
+                stop_power_level: power_level(stop_power)?,
+                step_delay: millis(step_delay)?,
+            })
+        } else if let Some(fields) = command_fields(bytes, b"C5-A:") {
+            let [cw, start_power, step_power, stop_power, step_delay] = fields[..] else {
+                return None;
+            };
+            Some(Command::StartAmpSweepExp {
+                cw: khz(cw)?,
+                start_power_dbm: start_power.parse().ok()?,
+                step_power_db: step_power.parse().ok()?,
+                stop_power_dbm: stop_power.parse().ok()?,
+                step_delay: millis(step_delay)?,
+            })
+        } else if let Some(fields) = command_fields(bytes, b"C3-F:") {
+            match fields[..] {
+                [cw, att, power] => Some(Command::StartCw {
+                    cw: khz(cw)?,
+                    attenuation: attenuation(att)?,
+                    power_level: power_level(power)?,
+                }),
+                [start, att, power, sweep_steps, step, step_delay] => {
+                    Some(Command::StartFreqSweep {
+                        start: khz(start)?,
+                        attenuation: attenuation(att)?,
+                        power_level: power_level(power)?,
+                        sweep_steps: sweep_steps.parse().ok()?,
+                        step: khz(step)?,
+                        step_delay: millis(step_delay)?,
+                    })
+                }
+                _ => None,
+            }
+        } else if let Some(fields) = command_fields(bytes, b"C5-F:") {
+            match fields[..] {
+                [cw, power] => Some(Command::StartCwExp {
+                    cw: khz(cw)?,
+                    power_dbm: power.parse().ok()?,
+                }),
+                [start, power, sweep_steps, step, step_delay] => Some(Command::StartFreqSweepExp {
+                    start: khz(start)?,
+                    power_dbm: power.parse().ok()?,
+                    sweep_steps: sweep_steps.parse().ok()?,
+                    step: khz(step)?,
+                    step_delay: millis(step_delay)?,
+                }),
+                _ => None,
+            }
+        } else if let Some(fields) = command_fields(bytes, b"C3-T:") {
+            let [start, att, power, sweep_steps, step] = fields[..] else {
+                return None;
+            };
+            Some(Command::StartTracking {
+                start: khz(start)?,
+                attenuation: attenuation(att)?,
+                power_level: power_level(power)?,
+                sweep_steps: sweep_steps.parse().ok()?,
+                step: khz(step)?,
+            })
+        } else if let Some(fields) = command_fields(bytes, b"C5-T:") {
+            let [start, power, sweep_steps, step] = fields[..] else {
+                return None;
+            };
+            Some(Command::StartTrackingExp {
+                start: khz(start)?,
+                power_dbm: power.parse().ok()?,
+                sweep_steps: sweep_steps.parse().ok()?,
+                step: khz(step)?,
+            })
+        } else {
+            None
+        }
+    }
+}
+
 impl From<Command> for Cow<'static, [u8]> {
     fn from(command: Command) -> Cow<'static, [u8]> {
         match command {
@@ -251,14 +367,14 @@ mod tests {
             start_power_level: PowerLevel::Low,
             stop_attenuation: Attenuation::Off,
             stop_power_level: PowerLevel::Highest,
-            step_delay: Duration::from_secs(1),
+            step_delay: SweepDelay::from_millis(1000).unwrap(),
         });
         assert_correct_size!(Command::StartAmpSweepExp {
             cw: Frequency::from_khz(100_000),
             start_power_dbm: -40.,
             step_power_db: 2.,
             stop_power_dbm: 0.,
-            step_delay: Duration::from_secs(1),
+            step_delay: SweepDelay::from_millis(1000).unwrap(),
         });
         assert_correct_size!(Command::StartCw {
             cw: Frequency::from_mhz(1),
@@ -275,7 +391,72 @@ mod tests {
             power_level: PowerLevel::High,
             sweep_steps: 10,
             step: Frequency::from_mhz(1),
-            step_delay: Duration::from_secs(2)
+            step_delay: SweepDelay::from_millis(2000).unwrap()
         });
     }
+
+    #[test]
+    fn decode_encoded_commands() {
+        let commands = [
+            Command::RfPowerOn,
+            Command::RfPowerOff,
+            Command::StartAmpSweep {
+                cw: Frequency::from_khz(100_000),
+                start_attenuation: Attenuation::On,
+                start_power_level: PowerLevel::Low,
+                stop_attenuation: Attenuation::Off,
+                stop_power_level: PowerLevel::Highest,
+                step_delay: SweepDelay::from_millis(1000).unwrap(),
+            },
+            Command::StartAmpSweepExp {
+                cw: Frequency::from_khz(100_000),
+                start_power_dbm: -40.,
+                step_power_db: 2.,
+                stop_power_dbm: 0.,
+                step_delay: SweepDelay::from_millis(1000).unwrap(),
+            },
+            Command::StartCw {
+                cw: Frequency::from_mhz(1),
+                attenuation: Attenuation::Off,
+                power_level: PowerLevel::Low,
+            },
+            Command::StartCwExp {
+                cw: Frequency::from_ghz(1),
+                power_dbm: 10.,
+            },
+            Command::StartFreqSweep {
+                start: Frequency::from_ghz(1),
+                attenuation: Attenuation::Off,
+                power_level: PowerLevel::High,
+                sweep_steps: 10,
+                step: Frequency::from_mhz(1),
+                step_delay: SweepDelay::from_millis(2000).unwrap(),
+            },
+            Command::StartFreqSweepExp {
+                start: Frequency::from_ghz(1),
+                power_dbm: -10.5,
+                sweep_steps: 10,
+                step: Frequency::from_mhz(1),
+                step_delay: SweepDelay::from_millis(500).unwrap(),
+            },
+            Command::StartTracking {
+                start: Frequency::from_mhz(500),
+                attenuation: Attenuation::On,
+                power_level: PowerLevel::Lowest,
+                sweep_steps: 100,
+                step: Frequency::from_khz(250),
+            },
+            Command::StartTrackingExp {
+                start: Frequency::from_mhz(500),
+                power_dbm: 5.,
+                sweep_steps: 100,
+                step: Frequency::from_khz(250),
+            },
+            Command::TrackingStep(42),
+        ];
+
+        for command in commands {
+            assert_eq!(Command::decode(&command.encode()), Some(command));
+        }
+    }
 }
@@ -1,5 +1,3 @@
-use std::time::Duration;
-
 use chrono::{DateTime, Utc};
 use nom::bytes::complete::{tag, take};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
@@ -7,7 +5,7 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 use crate::{
     common::{Frequency, MessageParseError},
     rf_explorer::parsers::*,
-    signal_generator::parsers::*,
+    signal_generator::{parsers::*, PowerSteps, SweepDelay},
 };
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, TryFromPrimitive, IntoPrimitive, Default)]
@@ -44,24 +42,34 @@ pub struct Config {
     pub step: Frequency,
     pub attenuation: Attenuation,
     pub power_level: PowerLevel,
-    pub sweep_power_steps: u16,
+    pub sweep_power_steps: PowerSteps,
     pub start_attenuation: Attenuation,
     pub start_power_level: PowerLevel,
     pub stop_attenuation: Attenuation,
     pub stop_power_level: PowerLevel,
     pub rf_power: RfPower,
-    pub sweep_delay: Duration,
+    pub sweep_delay: SweepDelay,
     pub timestamp: DateTime<Utc>,
 }
 
 impl Config {
     pub(crate) const PREFIX: &'static [u8] = b"#C3-*:";
+
+    /// The number of sweep power steps as a plain number.
+    pub fn sweep_power_steps_raw(&self) -> u16 {
+        self.sweep_power_steps.get()
+    }
+
+    /// The sweep delay in milliseconds.
+    pub fn sweep_delay_ms(&self) -> u32 {
+        self.sweep_delay.as_millis()
+    }
 }
 
 impl<'a> TryFrom<&'a [u8]> for Config {
     type Error = MessageParseError<'a>;
 
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
         // Parse the prefix of the message
         let (bytes, _) = tag(Config::PREFIX)(bytes)?;
 
@@ -96,7 +104,7 @@ impl<'a> TryFrom<&'a [u8]> for Config {
         let (bytes, _) = parse_comma(bytes)?;
 
         // Parse the sweep power steps
-        let (bytes, sweep_power_steps) = parse_num(4u8)(bytes)?;
+        let (bytes, sweep_power_steps) = parse_power_steps(bytes)?;
 
         let (bytes, _) = parse_comma(bytes)?;
 
@@ -126,7 +134,7 @@ impl<'a> TryFrom<&'a [u8]> for Config {
         let (bytes, _) = parse_comma(bytes)?;
 
         // Parse the sweep delay
-        let (bytes, sweep_delay_ms) = parse_sweep_delay_ms(bytes)?;
+        let (bytes, sweep_delay) = parse_sweep_delay(bytes)?;
 
         // Consume any \r or \r\n line endings and make sure there aren't any bytes left
         let _ = parse_opt_line_ending(bytes)?;
@@ -144,7 +152,7 @@ impl<'a> TryFrom<&'a [u8]> for Config {
             stop_attenuation,
             stop_power_level,
             rf_power,
-            sweep_delay: Duration::from_millis(u64::from(sweep_delay_ms)),
+            sweep_delay,
             timestamp: Utc::now(),
         })
     }
@@ -161,12 +169,17 @@ pub struct ConfigExp {
     pub start_power_dbm: f32,
     pub stop_power_dbm: f32,
     pub rf_power_on: bool,
-    pub sweep_delay: Duration,
+    pub sweep_delay: SweepDelay,
     pub timestamp: DateTime<Utc>,
 }
 
 impl ConfigExp {
     pub const PREFIX: &'static [u8] = b"#C5-*:";
+
+    /// The sweep delay in milliseconds.
+    pub fn sweep_delay_ms(&self) -> u32 {
+        self.sweep_delay.as_millis()
+    }
 }
 
 impl<'a> TryFrom<&'a [u8]> for ConfigExp {
@@ -222,7 +235,7 @@ impl<'a> TryFrom<&'a [u8]> for ConfigExp {
         let (bytes, _) = parse_comma(bytes)?;
 
         // Parse the sweep delay
-        let (bytes, sweep_delay_ms) = parse_sweep_delay_ms(bytes)?;
+        let (bytes, sweep_delay) = parse_sweep_delay(bytes)?;
 
         // Consume any \r or \r\n line endings and make sure there aren't any bytes left
         let _ = parse_opt_line_ending(bytes)?;
@@ -237,7 +250,7 @@ impl<'a> TryFrom<&'a [u8]> for ConfigExp {
             start_power_dbm,
             stop_power_dbm,
             rf_power_on: rf_power[0] == b'0',
-            sweep_delay: Duration::from_millis(u64::from(sweep_delay_ms)),
+            sweep_delay,
             timestamp: Utc::now(),
         })
     }
@@ -257,7 +270,7 @@ mod tests {
         assert_eq!(config.step.as_hz(), 1_000_000);
         assert_eq!(config.attenuation, Attenuation::On);
         assert_eq!(config.power_level, PowerLevel::Highest);
-        assert_eq!(config.sweep_power_steps, 0);
+        assert_eq!(config.sweep_power_steps_raw(), 0);
         assert_eq!(config.start_attenuation, Attenuation::On);
         assert_eq!(config.start_power_level, PowerLevel::Lowest);
         assert_eq!(config.stop_attenuation, Attenuation::Off);
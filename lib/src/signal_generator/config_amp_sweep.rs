@@ -1,35 +1,43 @@
-use std::time::Duration;
-
 use chrono::{DateTime, Utc};
 use nom::bytes::complete::tag;
 
 use crate::{
     common::{Frequency, MessageParseError},
     rf_explorer::parsers::*,
-    signal_generator::{parsers::*, Attenuation, PowerLevel, RfPower},
+    signal_generator::{parsers::*, Attenuation, PowerLevel, PowerSteps, RfPower, SweepDelay},
 };
 
 #[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
 pub struct ConfigAmpSweep {
     pub cw: Frequency,
-    pub sweep_power_steps: u16,
+    pub sweep_power_steps: PowerSteps,
     pub start_attenuation: Attenuation,
     pub start_power_level: PowerLevel,
     pub stop_attenuation: Attenuation,
     pub stop_power_level: PowerLevel,
     pub rf_power: RfPower,
-    pub sweep_delay: Duration,
+    pub sweep_delay: SweepDelay,
     pub timestamp: DateTime<Utc>,
 }
 
 impl ConfigAmpSweep {
     pub(crate) const PREFIX: &'static [u8] = b"#C3-A:";
+
+    /// The number of sweep power steps as a plain number.
+    pub fn sweep_power_steps_raw(&self) -> u16 {
+        self.sweep_power_steps.get()
+    }
+
+    /// The sweep delay in milliseconds.
+    pub fn sweep_delay_ms(&self) -> u32 {
+        self.sweep_delay.as_millis()
+    }
 }
 
 impl<'a> TryFrom<&'a [u8]> for ConfigAmpSweep {
     type Error = MessageParseError<'a>;
 
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
         // Parse the prefix of the message
         let (bytes, _) = tag(Self::PREFIX)(bytes)?;
 
@@ -39,7 +47,7 @@ impl<'a> TryFrom<&'a [u8]> for ConfigAmpSweep {
         let (bytes, _) = parse_comma(bytes)?;
 
         // Parse the sweep power steps
-        let (bytes, sweep_power_steps) = parse_num(4u8)(bytes)?;
+        let (bytes, sweep_power_steps) = parse_power_steps(bytes)?;
 
         let (bytes, _) = parse_comma(bytes)?;
 
@@ -69,7 +77,7 @@ impl<'a> TryFrom<&'a [u8]> for ConfigAmpSweep {
         let (bytes, _) = parse_comma(bytes)?;
 
         // Parse the sweep delay
-        let (bytes, sweep_delay_ms) = parse_sweep_delay_ms(bytes)?;
+        let (bytes, sweep_delay) = parse_sweep_delay(bytes)?;
 
         // Consume any \r or \r\n line endings and make sure there aren't any bytes left
         let _ = parse_opt_line_ending(bytes)?;
@@ -82,7 +90,7 @@ impl<'a> TryFrom<&'a [u8]> for ConfigAmpSweep {
             stop_attenuation,
             stop_power_level,
             rf_power,
-            sweep_delay: Duration::from_millis(u64::from(sweep_delay_ms)),
+            sweep_delay,
             timestamp: Utc::now(),
         })
     }
@@ -94,7 +102,7 @@ pub struct ConfigAmpSweepExp {
     start_power_dbm: f32,
     step_power_dbm: f32,
     stop_power_dbm: f32,
-    sweep_delay: Duration,
+    sweep_delay: SweepDelay,
     pub timestamp: DateTime<Utc>,
 }
 
@@ -130,7 +138,7 @@ impl<'a> TryFrom<&'a [u8]> for ConfigAmpSweepExp {
         let (bytes, _) = parse_comma(bytes)?;
 
         // Parse the sweep delay
-        let (bytes, sweep_delay_ms) = parse_sweep_delay_ms(bytes)?;
+        let (bytes, sweep_delay) = parse_sweep_delay(bytes)?;
 
         // Consume any \r or \r\n line endings and make sure there aren't any bytes left
         let _ = parse_opt_line_ending(bytes)?;
@@ -140,7 +148,7 @@ impl<'a> TryFrom<&'a [u8]> for ConfigAmpSweepExp {
             start_power_dbm,
             step_power_dbm,
             stop_power_dbm,
-            sweep_delay: Duration::from_millis(u64::from(sweep_delay_ms)),
+            sweep_delay,
             timestamp: Utc::now(),
         })
     }
@@ -155,7 +163,7 @@ mod tests {
         let bytes = b"#C3-A:0186525,0000,0,0,1,3,0,00100\r\n";
         let config_amp_sweep = ConfigAmpSweep::try_from(bytes.as_ref()).unwrap();
         assert_eq!(config_amp_sweep.cw.as_khz(), 186_525);
-        assert_eq!(config_amp_sweep.sweep_power_steps, 0);
+        assert_eq!(config_amp_sweep.sweep_power_steps_raw(), 0);
         assert_eq!(config_amp_sweep.start_attenuation, Attenuation::On);
         assert_eq!(config_amp_sweep.start_power_level, PowerLevel::Lowest);
         assert_eq!(config_amp_sweep.stop_attenuation, Attenuation::Off);
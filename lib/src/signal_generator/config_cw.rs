@@ -25,7 +25,7 @@ impl ConfigCw {
 impl<'a> TryFrom<&'a [u8]> for ConfigCw {
     type Error = MessageParseError<'a>;
 
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
         // Parse the prefix of the message
         let (bytes, _) = tag(Self::PREFIX)(bytes)?;
 
@@ -77,6 +77,13 @@ impl<'a> TryFrom<&'a [u8]> for ConfigCw {
     }
 }
 
+impl From<ConfigCw> for Frequency {
+    /// Returns the CW frequency.
+    fn from(config_cw: ConfigCw) -> Self {
+        config_cw.cw
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Default)]
 pub struct ConfigCwExp {
     pub cw: Frequency,
@@ -136,4 +143,11 @@ mod tests {
         assert_eq!(config_cw.power_level, PowerLevel::Highest);
         assert_eq!(config_cw.rf_power, RfPower::On);
     }
+
+    #[test]
+    fn frequency_from_config_cw() {
+        let bytes = b"#C3-G:0186525,0186525,0005,0001000,0,3,0\r\n";
+        let config_cw = ConfigCw::try_from(bytes.as_ref()).unwrap();
+        assert_eq!(Frequency::from(config_cw), Frequency::from_khz(186_525));
+    }
 }
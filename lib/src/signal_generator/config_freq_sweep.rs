@@ -1,12 +1,10 @@
-use std::time::Duration;
-
 use chrono::{DateTime, Utc};
 use nom::bytes::complete::tag;
 
 use crate::{
     common::{Frequency, MessageParseError},
     rf_explorer::parsers::*,
-    signal_generator::{parsers::*, Attenuation, PowerLevel, RfPower},
+    signal_generator::{parsers::*, Attenuation, PowerLevel, RfPower, SweepDelay},
 };
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
@@ -17,17 +15,30 @@ pub struct ConfigFreqSweep {
     pub attenuation: Attenuation,
     pub power_level: PowerLevel,
     pub rf_power: RfPower,
-    pub sweep_delay: Duration,
+    pub sweep_delay: SweepDelay,
     pub timestamp: DateTime<Utc>,
 }
 impl ConfigFreqSweep {
     pub(crate) const PREFIX: &'static [u8] = b"#C3-F:";
+
+    /// The sweep delay in milliseconds.
+    pub fn sweep_delay_ms(&self) -> u32 {
+        self.sweep_delay.as_millis()
+    }
+
+    /// The number of steps the frequency sweep takes from its start frequency to its stop
+    /// frequency.
+    ///
+    /// `None` is returned if the step frequency is 0, since the sweep wouldn't go anywhere.
+    pub fn step_count(&self) -> Option<u32> {
+        (self.step.as_hz() > 0).then_some(self.total_steps)
+    }
 }
 
 impl<'a> TryFrom<&'a [u8]> for ConfigFreqSweep {
     type Error = MessageParseError<'a>;
 
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
         // Parse the prefix of the message
         let (bytes, _) = tag(Self::PREFIX)(bytes)?;
 
@@ -62,7 +73,7 @@ impl<'a> TryFrom<&'a [u8]> for ConfigFreqSweep {
         let (bytes, _) = parse_comma(bytes)?;
 
         // Parse the sweep delay
-        let (bytes, sweep_delay_ms) = parse_sweep_delay_ms(bytes)?;
+        let (bytes, sweep_delay) = parse_sweep_delay(bytes)?;
 
         // Consume any \r or \r\n line endings and make sure there aren't any bytes left
         let _ = parse_opt_line_ending(bytes)?;
@@ -74,7 +85,7 @@ impl<'a> TryFrom<&'a [u8]> for ConfigFreqSweep {
             attenuation,
             power_level,
             rf_power,
-            sweep_delay: Duration::from_millis(u64::from(sweep_delay_ms)),
+            sweep_delay,
             timestamp: Utc::now(),
         })
     }
@@ -87,18 +98,23 @@ pub struct ConfigFreqSweepExp {
     pub step: Frequency,
     pub power_dbm: f32,
     pub rf_power: RfPower,
-    pub sweep_delay: Duration,
+    pub sweep_delay: SweepDelay,
     pub timestamp: DateTime<Utc>,
 }
 
 impl ConfigFreqSweepExp {
     pub const PREFIX: &'static [u8] = b"#C5-F:";
+
+    /// The sweep delay in milliseconds.
+    pub fn sweep_delay_ms(&self) -> u32 {
+        self.sweep_delay.as_millis()
+    }
 }
 
 impl<'a> TryFrom<&'a [u8]> for ConfigFreqSweepExp {
     type Error = MessageParseError<'a>;
 
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
         // Parse the prefix of the message
         let (bytes, _) = tag(Self::PREFIX)(bytes)?;
 
@@ -128,7 +144,7 @@ impl<'a> TryFrom<&'a [u8]> for ConfigFreqSweepExp {
         let (bytes, _) = parse_comma(bytes)?;
 
         // Parse the sweep delay
-        let (bytes, sweep_delay_ms) = parse_sweep_delay_ms(bytes)?;
+        let (bytes, sweep_delay) = parse_sweep_delay(bytes)?;
 
         // Consume any \r or \r\n line endings and make sure there aren't any bytes left
         let _ = parse_opt_line_ending(bytes)?;
@@ -139,7 +155,7 @@ impl<'a> TryFrom<&'a [u8]> for ConfigFreqSweepExp {
             step: Frequency::from_khz(step_khz),
             power_dbm,
             rf_power,
-            sweep_delay: Duration::from_millis(u64::from(sweep_delay_ms)),
+            sweep_delay,
             timestamp: Utc::now(),
         })
     }
@@ -160,5 +176,13 @@ mod tests {
         assert_eq!(config_freq_sweep.power_level, PowerLevel::Highest);
         assert_eq!(config_freq_sweep.rf_power, RfPower::On);
         assert_eq!(config_freq_sweep.sweep_delay.as_millis(), 100);
+        assert_eq!(config_freq_sweep.step_count(), Some(5));
+    }
+
+    #[test]
+    fn zero_step_has_no_step_count() {
+        let bytes = b"#C3-F:0186525,0005,0000000,0,3,0,00100";
+        let config_freq_sweep = ConfigFreqSweep::try_from(bytes.as_ref()).unwrap();
+        assert_eq!(config_freq_sweep.step_count(), None);
     }
 }
@@ -5,8 +5,9 @@ use super::{
 use crate::common::MessageParseError;
 use crate::rf_explorer::{ScreenData, SerialNumber, SetupInfo};
 
+/// A message received from a signal generator.
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) enum Message {
+pub enum Message {
     Config(Config),
     ConfigAmpSweep(ConfigAmpSweep),
     ConfigCw(ConfigCw),
@@ -21,6 +22,29 @@ pub(crate) enum Message {
     Temperature(Temperature),
 }
 
+impl Message {
+    /// Returns the prefix that identifies this type of message, e.g. `b"#C3-G:"` for a
+    /// `ConfigCw`.
+    ///
+    /// This is useful for identifying a message's type at runtime, e.g. when logging.
+    pub fn prefix(&self) -> &'static [u8] {
+        match self {
+            Message::Config(_) => Config::PREFIX,
+            Message::ConfigAmpSweep(_) => ConfigAmpSweep::PREFIX,
+            Message::ConfigCw(_) => ConfigCw::PREFIX,
+            Message::ConfigFreqSweep(_) => ConfigFreqSweep::PREFIX,
+            Message::ConfigExp(_) => ConfigExp::PREFIX,
+            Message::ConfigAmpSweepExp(_) => ConfigAmpSweepExp::PREFIX,
+            Message::ConfigCwExp(_) => ConfigCwExp::PREFIX,
+            Message::ConfigFreqSweepExp(_) => ConfigFreqSweepExp::PREFIX,
+            Message::ScreenData(_) => ScreenData::PREFIX,
+            Message::SerialNumber(_) => SerialNumber::PREFIX,
+            Message::SetupInfo(_) => SetupInfo::<Model>::PREFIX,
+            Message::Temperature(_) => Temperature::PREFIX,
+        }
+    }
+}
+
 impl<'a> TryFrom<&'a [u8]> for Message {
     type Error = MessageParseError<'a>;
 
@@ -59,3 +83,54 @@ impl<'a> TryFrom<&'a [u8]> for Message {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_matches_parsed_message() {
+        let mut screen_data = b"$D".to_vec();
+        screen_data.resize(2 + 8 * 128, 0);
+        let messages: [&[u8]; 12] = [
+            b"#C3-*:0510000,0186525,0005,0001000,0,3,0000,0,0,1,3,0,00100\r\n",
+            b"#C3-A:0186525,0000,0,0,1,3,0,00100\r\n",
+            b"#C3-G:0186525,0186525,0005,0001000,0,3,0\r\n",
+            b"#C3-F:0186525,0005,0001000,0,3,0,00100\r\n",
+            b"#C5-*:0510000,0186525,0005,0001000,-0010,00001,-0020,-0010,0,00100\r\n",
+            b"#C5-A:0186525,-0020,00001,-0010,00100\r\n",
+            b"#C5-G:0186525,-0010,0\r\n",
+            b"#C5-F:0186525,0005,0001000,-0010,0,00100\r\n",
+            &screen_data,
+            b"#Sn0SME38SI2X7NGR48\r\n",
+            b"#C3-M:060,255,01.15\r\n",
+            b"#T:3\r\n",
+        ];
+
+        // Listing every variant makes this test fail to compile when one is added
+        let variant_index = |message: &Message| match message {
+            Message::Config(_) => 0,
+            Message::ConfigAmpSweep(_) => 1,
+            Message::ConfigCw(_) => 2,
+            Message::ConfigFreqSweep(_) => 3,
+            Message::ConfigExp(_) => 4,
+            Message::ConfigAmpSweepExp(_) => 5,
+            Message::ConfigCwExp(_) => 6,
+            Message::ConfigFreqSweepExp(_) => 7,
+            Message::ScreenData(_) => 8,
+            Message::SerialNumber(_) => 9,
+            Message::SetupInfo(_) => 10,
+            Message::Temperature(_) => 11,
+        };
+        for (index, bytes) in messages.into_iter().enumerate() {
+            let message = Message::try_from(bytes).unwrap();
+            assert_eq!(variant_index(&message), index);
+            assert!(
+                bytes.starts_with(message.prefix()),
+                "{} doesn't start with {}",
+                bytes.escape_ascii(),
+                message.prefix().escape_ascii()
+            );
+        }
+    }
+}
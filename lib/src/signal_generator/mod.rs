@@ -5,17 +5,28 @@ mod config_cw;
 mod config_freq_sweep;
 mod message;
 mod model;
+mod operating_mode;
+mod output_calibration;
 mod parsers;
+mod power_steps;
 mod rf_explorer;
 mod setup_info;
+mod sweep_delay;
+mod sweep_progress;
 mod temperature;
 
-pub(crate) use command::Command;
+pub use command::Command;
 pub use config::{Attenuation, Config, ConfigExp, PowerLevel, RfPower};
 pub use config_amp_sweep::{ConfigAmpSweep, ConfigAmpSweepExp};
 pub use config_cw::{ConfigCw, ConfigCwExp};
 pub use config_freq_sweep::{ConfigFreqSweep, ConfigFreqSweepExp};
-pub(crate) use message::Message;
+pub use message::Message;
 pub use model::Model;
+pub use operating_mode::OperatingMode;
+pub use output_calibration::{OutputCalibration, OutputPowerEstimate};
+pub use power_steps::PowerSteps;
 pub use rf_explorer::SignalGenerator;
+pub use sweep_delay::SweepDelay;
+pub use sweep_progress::SweepProgress;
+pub(crate) use sweep_progress::SweepSchedule;
 pub use temperature::Temperature;
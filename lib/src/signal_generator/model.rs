@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{fmt::Display, ops::RangeInclusive};
 
 use num_enum::TryFromPrimitive;
 
@@ -13,6 +13,7 @@ pub enum Model {
 }
 
 impl Model {
+    /// The minimum frequency the model can generate.
     pub fn min_freq(&self) -> Frequency {
         match self {
             Self::Rfe6Gen => 23_400_000,
@@ -21,6 +22,7 @@ impl Model {
         .into()
     }
 
+    /// The maximum frequency the model can generate.
     pub fn max_freq(&self) -> Frequency {
         match self {
             Self::Rfe6Gen => 6_000_000_000,
@@ -28,6 +30,11 @@ impl Model {
         }
         .into()
     }
+
+    /// The range of frequencies the model can generate.
+    pub fn frequency_range(&self) -> RangeInclusive<Frequency> {
+        self.min_freq()..=self.max_freq()
+    }
 }
 
 impl Display for Model {
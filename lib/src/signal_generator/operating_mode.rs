@@ -0,0 +1,45 @@
+use std::fmt::Display;
+
+/// The mode a signal generator was last started in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum OperatingMode {
+    Cw,
+    AmpSweep,
+    FreqSweep,
+    Tracking,
+}
+
+impl OperatingMode {
+    /// Returns `true` if starting `next` while in this mode would disrupt this mode.
+    ///
+    /// Only tracking mode conflicts with other modes, since a spectrum analyzer relies on the
+    /// signal generator staying in step with it.
+    pub(crate) fn conflicts_with(&self, next: OperatingMode) -> bool {
+        *self == OperatingMode::Tracking && next != OperatingMode::Tracking
+    }
+}
+
+impl Display for OperatingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode = match self {
+            OperatingMode::Cw => "CW",
+            OperatingMode::AmpSweep => "amplitude sweep",
+            OperatingMode::FreqSweep => "frequency sweep",
+            OperatingMode::Tracking => "tracking",
+        };
+        write!(f, "{mode}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_tracking_conflicts() {
+        assert!(OperatingMode::Tracking.conflicts_with(OperatingMode::FreqSweep));
+        assert!(OperatingMode::Tracking.conflicts_with(OperatingMode::Cw));
+        assert!(!OperatingMode::Tracking.conflicts_with(OperatingMode::Tracking));
+        assert!(!OperatingMode::Cw.conflicts_with(OperatingMode::FreqSweep));
+    }
+}
@@ -0,0 +1,109 @@
+use crate::Frequency;
+
+/// A table of output power corrections measured at different frequencies.
+///
+/// Each correction is the difference, in dB, between the measured output power and the power
+/// the signal generator was set to. Corrections between two frequencies in the table are
+/// linearly interpolated.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutputCalibration {
+    points: Vec<(Frequency, f64)>,
+}
+
+/// An estimate of the signal generator's actual output power.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OutputPowerEstimate {
+    pub power_dbm: f64,
+    /// Whether the frequency was outside the calibration table, in which case the correction
+    /// of the nearest frequency in the table was used.
+    pub is_extrapolated: bool,
+}
+
+impl OutputCalibration {
+    /// Creates a calibration table from (frequency, correction in dB) pairs.
+    pub fn new(points: impl IntoIterator<Item = (Frequency, f64)>) -> Self {
+        let mut points: Vec<_> = points.into_iter().collect();
+        points.sort_by_key(|(freq, _)| *freq);
+        points.dedup_by_key(|(freq, _)| *freq);
+        OutputCalibration { points }
+    }
+
+    /// Returns the (frequency, correction in dB) pairs in the table, sorted by frequency.
+    pub fn points(&self) -> &[(Frequency, f64)] {
+        &self.points
+    }
+
+    /// Returns the correction at the given frequency and whether it was extrapolated.
+    ///
+    /// An empty table has no correction.
+    pub fn correction_db(&self, freq: Frequency) -> (f64, bool) {
+        let (Some(first), Some(last)) = (self.points.first(), self.points.last()) else {
+            return (0., false);
+        };
+
+        if freq < first.0 {
+            return (first.1, true);
+        } else if freq > last.0 {
+            return (last.1, true);
+        }
+
+        let upper_index = self
+            .points
+            .partition_point(|(point_freq, _)| *point_freq < freq);
+        let (upper_freq, upper_correction) = self.points[upper_index];
+        if upper_freq == freq || upper_index == 0 {
+            return (upper_correction, false);
+        }
+
+        let (lower_freq, lower_correction) = self.points[upper_index - 1];
+        let fraction =
+            (freq - lower_freq).as_hz() as f64 / (upper_freq - lower_freq).as_hz() as f64;
+        (
+            lower_correction + fraction * (upper_correction - lower_correction),
+            false,
+        )
+    }
+
+    /// Estimates the actual output power when the signal generator is set to `power_dbm`.
+    pub fn estimate(&self, freq: Frequency, power_dbm: f64) -> OutputPowerEstimate {
+        let (correction_db, is_extrapolated) = self.correction_db(freq);
+        OutputPowerEstimate {
+            power_dbm: power_dbm + correction_db,
+            is_extrapolated,
+        }
+    }
+
+    /// Returns the power the signal generator should be set to for its output to be `power_dbm`.
+    pub fn power_setting_dbm(&self, freq: Frequency, power_dbm: f64) -> f64 {
+        power_dbm - self.correction_db(freq).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolate_and_extrapolate_corrections() {
+        let calibration = OutputCalibration::new([
+            (Frequency::from_mhz(2_000), -2.),
+            (Frequency::from_mhz(1_000), -1.),
+        ]);
+        assert_eq!(
+            calibration.correction_db(Frequency::from_mhz(1_500)),
+            (-1.5, false)
+        );
+        assert_eq!(
+            calibration.correction_db(Frequency::from_mhz(1_000)),
+            (-1., false)
+        );
+        assert_eq!(
+            calibration.correction_db(Frequency::from_mhz(500)),
+            (-1., true)
+        );
+        assert_eq!(
+            calibration.correction_db(Frequency::from_mhz(3_000)),
+            (-2., true)
+        );
+    }
+}
@@ -2,7 +2,7 @@ use std::convert::TryFrom;
 
 use nom::{combinator::map_res, IResult};
 
-use super::{Attenuation, PowerLevel, RfPower};
+use super::{Attenuation, PowerLevel, PowerSteps, RfPower, SweepDelay};
 use crate::rf_explorer::parsers::*;
 
 pub(super) fn parse_attenuation(bytes: &[u8]) -> IResult<&[u8], Attenuation> {
@@ -13,8 +13,12 @@ pub(super) fn parse_power_level(bytes: &[u8]) -> IResult<&[u8], PowerLevel> {
     map_res(parse_num::<u8>(1u8), PowerLevel::try_from)(bytes)
 }
 
-pub(super) fn parse_sweep_delay_ms(bytes: &[u8]) -> IResult<&[u8], u16> {
-    parse_num(5u8)(bytes)
+pub(super) fn parse_sweep_delay(bytes: &[u8]) -> IResult<&[u8], SweepDelay> {
+    map_res(parse_num::<u32>(5u8), SweepDelay::from_millis)(bytes)
+}
+
+pub(super) fn parse_power_steps(bytes: &[u8]) -> IResult<&[u8], PowerSteps> {
+    map_res(parse_num::<u16>(4u8), PowerSteps::new)(bytes)
 }
 
 pub(super) fn parse_rf_power(bytes: &[u8]) -> IResult<&[u8], RfPower> {
@@ -0,0 +1,44 @@
+use crate::{Error, Result};
+
+/// The number of power steps in a signal generator amplitude sweep.
+///
+/// The RF Explorer reports up to 9,999 steps.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PowerSteps(u16);
+
+/// cbindgen:ignore
+impl PowerSteps {
+    pub const MAX: PowerSteps = PowerSteps(9_999);
+
+    /// Returns `PowerSteps` with the given number of steps.
+    ///
+    /// An `InvalidInput` error is returned if there are more steps than `PowerSteps::MAX`.
+    pub fn new(steps: u16) -> Result<Self> {
+        if steps > Self::MAX.0 {
+            return Err(Error::InvalidInput(format!(
+                "The number of power steps must be at most {} but was {steps}",
+                Self::MAX.0
+            )));
+        }
+
+        Ok(PowerSteps(steps))
+    }
+
+    pub fn get(&self) -> u16 {
+        self.0
+    }
+}
+
+impl TryFrom<u16> for PowerSteps {
+    type Error = Error;
+
+    fn try_from(steps: u16) -> Result<Self> {
+        PowerSteps::new(steps)
+    }
+}
+
+impl From<PowerSteps> for u16 {
+    fn from(steps: PowerSteps) -> Self {
+        steps.0
+    }
+}
@@ -1,19 +1,30 @@
 use std::{
     fmt::Debug,
     io,
-    sync::{Condvar, Mutex},
-    time::Duration,
+    ops::RangeInclusive,
+    sync::Weak,
+    thread,
+    time::{Duration, Instant},
 };
 
+use chrono::{DateTime, Utc};
+
 use super::{
     Attenuation, Config, ConfigAmpSweep, ConfigAmpSweepExp, ConfigCw, ConfigCwExp, ConfigExp,
-    ConfigFreqSweep, ConfigFreqSweepExp, Model, PowerLevel, Temperature,
+    ConfigFreqSweep, ConfigFreqSweepExp, Model, OperatingMode, OutputCalibration,
+    OutputPowerEstimate, PowerLevel, RfPower, SweepDelay, SweepProgress, SweepSchedule,
+    Temperature,
+};
+use crate::common::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc, Condvar, Mutex,
 };
+use crate::common::{ensure_not_reader_thread, LockResultExt};
 use crate::rf_explorer::{
-    impl_rf_explorer, Callback, ScreenData, SerialNumber, SetupInfo, NEXT_SCREEN_DATA_TIMEOUT,
-    RECEIVE_INITIAL_DEVICE_INFO_TIMEOUT,
+    call_callback, impl_rf_explorer, Callback, ScreenData, SerialNumber, SetupInfo,
+    COMMAND_RESPONSE_TIMEOUT, NEXT_SCREEN_DATA_TIMEOUT, RECEIVE_INITIAL_DEVICE_INFO_TIMEOUT,
 };
-use crate::{ConnectionError, ConnectionResult, Device, Frequency, Result};
+use crate::{ConnectionError, ConnectionResult, Device, DeviceType, Frequency, Result};
 
 #[derive(Debug)]
 pub struct SignalGenerator {
@@ -26,11 +37,12 @@ impl SignalGenerator {
     /// Returns the RF Explorer's serial number, if it exists.
     pub fn serial_number(&self) -> Option<String> {
         // Return the serial number if we've already received it
-        if let Some(ref serial_number) = *self.messages().serial_number.0.lock().unwrap() {
+        if let Some(ref serial_number) = *self.messages().serial_number.0.lock().unpoisoned() {
             return Some(serial_number.to_string());
         }
 
         // If we haven't already received the serial number, request it from the RF Explorer
+        ensure_not_reader_thread().ok()?;
         self.send_command(crate::rf_explorer::Command::RequestSerialNumber)
             .ok()?;
 
@@ -39,13 +51,13 @@ impl SignalGenerator {
         tracing::trace!("Waiting to receive SerialNumber from RF Explorer");
         let _ = cvar
             .wait_timeout_while(
-                lock.lock().unwrap(),
+                lock.lock().unpoisoned(),
                 std::time::Duration::from_secs(2),
                 |serial_number| serial_number.is_none(),
             )
-            .unwrap();
+            .unpoisoned();
 
-        (*self.messages().serial_number.0.lock().unwrap())
+        (*self.messages().serial_number.0.lock().unpoisoned())
             .as_ref()
             .map(|sn| sn.to_string())
     }
@@ -55,70 +67,74 @@ impl SignalGenerator {
             .setup_info
             .0
             .lock()
-            .unwrap()
+            .unpoisoned()
             .as_ref()
             .map(|setup_info| setup_info.firmware_version.clone())
             .unwrap_or_default()
     }
 
+    /// The `SetupInfo` reported by the RF Explorer.
+    pub fn setup_info(&self) -> Option<SetupInfo<Model>> {
+        self.messages().setup_info.0.lock().unpoisoned().clone()
+    }
+
     pub fn config(&self) -> Option<Config> {
-        *self.messages().config.0.lock().unwrap()
+        *self.messages().config.0.lock().unpoisoned()
     }
 
     pub fn config_expansion(&self) -> Option<ConfigExp> {
-        *self.messages().config_exp.0.lock().unwrap()
+        *self.messages().config_exp.0.lock().unpoisoned()
     }
 
     pub fn config_amp_sweep(&self) -> Option<ConfigAmpSweep> {
-        *self.messages().config_amp_sweep.0.lock().unwrap()
+        *self.messages().config_amp_sweep.0.lock().unpoisoned()
     }
 
     pub fn config_amp_sweep_expansion(&self) -> Option<ConfigAmpSweepExp> {
-        *self.messages().config_amp_sweep_exp.0.lock().unwrap()
+        *self.messages().config_amp_sweep_exp.0.lock().unpoisoned()
     }
 
     pub fn config_cw(&self) -> Option<ConfigCw> {
-        *self.messages().config_cw.0.lock().unwrap()
+        *self.messages().config_cw.0.lock().unpoisoned()
     }
 
     pub fn config_cw_expansion(&self) -> Option<ConfigCwExp> {
-        *self.messages().config_cw_exp.0.lock().unwrap()
+        *self.messages().config_cw_exp.0.lock().unpoisoned()
     }
 
     pub fn config_freq_sweep(&self) -> Option<ConfigFreqSweep> {
-        *self.messages().config_freq_sweep.0.lock().unwrap()
+        *self.messages().config_freq_sweep.0.lock().unpoisoned()
+    }
+
+    /// The number of steps in the frequency sweep described by the most recent `ConfigFreqSweep`.
+    ///
+    /// `None` is returned if a `ConfigFreqSweep` hasn't been received or its step frequency is 0.
+    pub fn freq_sweep_step_count(&self) -> Option<u32> {
+        self.config_freq_sweep()
+            .and_then(|config_freq_sweep| config_freq_sweep.step_count())
     }
 
     pub fn config_freq_sweep_expansion(&self) -> Option<ConfigFreqSweepExp> {
-        *self.messages().config_freq_sweep_exp.0.lock().unwrap()
+        *self.messages().config_freq_sweep_exp.0.lock().unpoisoned()
     }
 
     /// Returns the most recent `ScreenData` captured by the RF Explorer.
     pub fn screen_data(&self) -> Option<ScreenData> {
-        self.messages().screen_data.0.lock().unwrap().clone()
+        self.messages().screen_data.0.lock().unpoisoned().clone()
     }
 
+    /// Waits for the RF Explorer to capture its next `ScreenData`.
     pub fn wait_for_next_screen_data(&self) -> Result<ScreenData> {
         self.wait_for_next_screen_data_with_timeout(NEXT_SCREEN_DATA_TIMEOUT)
     }
 
+    /// Waits for the RF Explorer to capture its next `ScreenData` or for the timeout duration to elapse.
     pub fn wait_for_next_screen_data_with_timeout(&self, timeout: Duration) -> Result<ScreenData> {
-        let previous_screen_data = self.screen_data();
-        let (screen_data, condvar) = &self.messages().screen_data;
-        let (screen_data, wait_result) = condvar
-            .wait_timeout_while(screen_data.lock().unwrap(), timeout, |screen_data| {
-                *screen_data == previous_screen_data || screen_data.is_none()
-            })
-            .unwrap();
-
-        match &*screen_data {
-            Some(screen_data) if !wait_result.timed_out() => Ok(screen_data.clone()),
-            _ => Err(crate::Error::TimedOut(timeout)),
-        }
+        rf_explorer::wait_for_next_screen_data(&self.messages().screen_data, timeout)
     }
 
     pub fn temperature(&self) -> Option<Temperature> {
-        *self.messages().temperature.0.lock().unwrap()
+        *self.messages().temperature.0.lock().unpoisoned()
     }
 
     /// Returns the main radio's model.
@@ -127,7 +143,7 @@ impl SignalGenerator {
             .setup_info
             .0
             .lock()
-            .unwrap()
+            .unpoisoned()
             .as_ref()
             .unwrap()
             .main_radio_model
@@ -139,7 +155,7 @@ impl SignalGenerator {
             .setup_info
             .0
             .lock()
-            .unwrap()
+            .unpoisoned()
             .as_ref()
             .unwrap()
             .expansion_radio_model
@@ -158,11 +174,14 @@ impl SignalGenerator {
         }
     }
 
+    /// The range of frequencies the active radio can generate.
+    pub fn active_frequency_range(&self) -> RangeInclusive<Frequency> {
+        self.active_radio_model().frequency_range()
+    }
+
     /// The inactive radio's model.
     pub fn inactive_radio_model(&self) -> Option<Model> {
-        let Some(exp_model) = self.expansion_radio_model() else {
-            return None;
-        };
+        let exp_model = self.expansion_radio_model()?;
 
         if self.config_expansion().is_some() {
             self.main_radio_model()
@@ -179,34 +198,43 @@ impl SignalGenerator {
         start_power_level: PowerLevel,
         stop_attenuation: Attenuation,
         stop_power_level: PowerLevel,
-        step_delay: Duration,
-    ) -> io::Result<()> {
-        self.send_command(super::Command::StartAmpSweep {
-            cw: cw.into(),
-            start_attenuation,
-            start_power_level,
-            stop_attenuation,
-            stop_power_level,
-            step_delay,
-        })
+        step_delay: SweepDelay,
+    ) -> Result<()> {
+        self.start_operating_mode(
+            OperatingMode::AmpSweep,
+            super::Command::StartAmpSweep {
+                cw: cw.into(),
+                start_attenuation,
+                start_power_level,
+                stop_attenuation,
+                stop_power_level,
+                step_delay,
+            },
+        )
     }
 
     /// Starts the signal generator's amplitude sweep mode using the expansion module.
+    ///
+    /// The start and stop powers are adjusted by the output calibration, if one is set.
     pub fn start_amp_sweep_exp(
         &self,
         cw: impl Into<Frequency>,
         start_power_dbm: f64,
         step_power_db: f64,
         stop_power_dbm: f64,
-        step_delay: Duration,
-    ) -> io::Result<()> {
-        self.send_command(super::Command::StartAmpSweepExp {
-            cw: cw.into(),
-            start_power_dbm,
-            step_power_db,
-            stop_power_dbm,
-            step_delay,
-        })
+        step_delay: SweepDelay,
+    ) -> Result<()> {
+        let cw = cw.into();
+        self.start_operating_mode(
+            OperatingMode::AmpSweep,
+            super::Command::StartAmpSweepExp {
+                cw,
+                start_power_dbm: self.power_setting_dbm(cw, start_power_dbm),
+                step_power_db,
+                stop_power_dbm: self.power_setting_dbm(cw, stop_power_dbm),
+                step_delay,
+            },
+        )
     }
 
     /// Starts the signal generator's CW mode.
@@ -215,20 +243,29 @@ impl SignalGenerator {
         cw: impl Into<Frequency>,
         attenuation: Attenuation,
         power_level: PowerLevel,
-    ) -> io::Result<()> {
-        self.send_command(super::Command::StartCw {
-            cw: cw.into(),
-            attenuation,
-            power_level,
-        })
+    ) -> Result<()> {
+        self.start_operating_mode(
+            OperatingMode::Cw,
+            super::Command::StartCw {
+                cw: cw.into(),
+                attenuation,
+                power_level,
+            },
+        )
     }
 
     /// Starts the signal generator's CW mode using the expansion module.
-    pub fn start_cw_exp(&self, cw: impl Into<Frequency>, power_dbm: f64) -> io::Result<()> {
-        self.send_command(super::Command::StartCwExp {
-            cw: cw.into(),
-            power_dbm,
-        })
+    ///
+    /// The power is adjusted by the output calibration, if one is set.
+    pub fn start_cw_exp(&self, cw: impl Into<Frequency>, power_dbm: f64) -> Result<()> {
+        let cw = cw.into();
+        self.start_operating_mode(
+            OperatingMode::Cw,
+            super::Command::StartCwExp {
+                cw,
+                power_dbm: self.power_setting_dbm(cw, power_dbm),
+            },
+        )
     }
 
     /// Starts the signal generator's frequency sweep mode.
@@ -239,16 +276,19 @@ impl SignalGenerator {
         power_level: PowerLevel,
         sweep_steps: u16,
         step_hz: u64,
-        step_delay: Duration,
-    ) -> io::Result<()> {
-        self.send_command(super::Command::StartFreqSweep {
-            start: start.into(),
-            attenuation,
-            power_level,
-            sweep_steps,
-            step: Frequency::from_hz(step_hz),
-            step_delay,
-        })
+        step_delay: SweepDelay,
+    ) -> Result<()> {
+        self.start_operating_mode(
+            OperatingMode::FreqSweep,
+            super::Command::StartFreqSweep {
+                start: start.into(),
+                attenuation,
+                power_level,
+                sweep_steps,
+                step: Frequency::from_hz(step_hz),
+                step_delay,
+            },
+        )
     }
 
     /// Starts the signal generator's frequency sweep mode using the expansion module.
@@ -258,15 +298,18 @@ impl SignalGenerator {
         power_dbm: f64,
         sweep_steps: u16,
         step: impl Into<Frequency>,
-        step_delay: Duration,
-    ) -> io::Result<()> {
-        self.send_command(super::Command::StartFreqSweepExp {
-            start: start.into(),
-            power_dbm,
-            sweep_steps,
-            step: step.into(),
-            step_delay,
-        })
+        step_delay: SweepDelay,
+    ) -> Result<()> {
+        self.start_operating_mode(
+            OperatingMode::FreqSweep,
+            super::Command::StartFreqSweepExp {
+                start: start.into(),
+                power_dbm,
+                sweep_steps,
+                step: step.into(),
+                step_delay,
+            },
+        )
     }
 
     /// Starts the signal generator's tracking mode.
@@ -277,14 +320,17 @@ impl SignalGenerator {
         power_level: PowerLevel,
         sweep_steps: u16,
         step: impl Into<Frequency>,
-    ) -> io::Result<()> {
-        self.send_command(super::Command::StartTracking {
-            start: start.into(),
-            attenuation,
-            power_level,
-            sweep_steps,
-            step: step.into(),
-        })
+    ) -> Result<()> {
+        self.start_operating_mode(
+            OperatingMode::Tracking,
+            super::Command::StartTracking {
+                start: start.into(),
+                attenuation,
+                power_level,
+                sweep_steps,
+                step: step.into(),
+            },
+        )
     }
 
     /// Starts the signal generator's tracking mode using the expansion module.
@@ -294,13 +340,126 @@ impl SignalGenerator {
         power_dbm: f64,
         sweep_steps: u16,
         step: impl Into<Frequency>,
-    ) -> io::Result<()> {
-        self.send_command(super::Command::StartTrackingExp {
-            start: start.into(),
-            power_dbm,
-            sweep_steps,
-            step: step.into(),
-        })
+    ) -> Result<()> {
+        self.start_operating_mode(
+            OperatingMode::Tracking,
+            super::Command::StartTrackingExp {
+                start: start.into(),
+                power_dbm,
+                sweep_steps,
+                step: step.into(),
+            },
+        )
+    }
+
+    /// The mode the signal generator was last started in, or `None` if it hasn't been started or
+    /// RF power has been turned off since.
+    pub fn operating_mode(&self) -> Option<OperatingMode> {
+        *self.messages().operating_mode.lock().unpoisoned()
+    }
+
+    /// Stops the current operating mode by turning off RF power, so that any other mode can be
+    /// started.
+    pub fn stop_current(&self) -> Result<()> {
+        self.rf_power_off()
+    }
+
+    /// Stops the current operating mode without waiting for the signal generator to report that
+    /// RF power is off.
+    ///
+    /// Unlike `stop_current`, the operating mode and any in-progress sweep are forgotten even if
+    /// the RF power off command can't be sent, so this can be used to recover when the signal
+    /// generator stops responding in the middle of a sweep or tracking.
+    pub fn stop_current_force(&self) -> Result<()> {
+        self.messages().set_sweep_schedule(None);
+        *self.messages().operating_mode.lock().unpoisoned() = None;
+        self.send_command(super::Command::RfPowerOff)?;
+        Ok(())
+    }
+
+    /// Sends the command that starts an operating mode, unless it would disrupt the current
+    /// operating mode.
+    fn start_operating_mode(&self, mode: OperatingMode, command: super::Command) -> Result<()> {
+        let mut operating_mode = self.messages().operating_mode.lock().unpoisoned();
+        if let Some(active_mode) = operating_mode.filter(|active| active.conflicts_with(mode)) {
+            return Err(crate::Error::InvalidOperation(format!(
+                "The signal generator is in {active_mode} mode, call stop_current or stop_current_force before starting {mode} mode"
+            )));
+        }
+        self.send_command(command)?;
+        *operating_mode = Some(mode);
+        Ok(())
+    }
+
+    /// Sets the output calibration used by the expansion module's CW and amplitude sweep modes.
+    pub fn set_output_calibration(&self, calibration: OutputCalibration) {
+        *self.messages().output_calibration.lock().unpoisoned() = Some(calibration);
+    }
+
+    /// Removes the output calibration.
+    pub fn remove_output_calibration(&self) {
+        *self.messages().output_calibration.lock().unpoisoned() = None;
+    }
+
+    /// Returns the estimated output power of the expansion module's CW mode, corrected by the
+    /// output calibration if one is set.
+    pub fn output_power_estimate(&self) -> Option<OutputPowerEstimate> {
+        let config = self.config_cw_expansion()?;
+        let power_dbm = f64::from(config.power_dbm);
+        Some(
+            match *self.messages().output_calibration.lock().unpoisoned() {
+                Some(ref calibration) => calibration.estimate(config.cw, power_dbm),
+                None => OutputPowerEstimate {
+                    power_dbm,
+                    is_extrapolated: false,
+                },
+            },
+        )
+    }
+
+    fn power_setting_dbm(&self, freq: Frequency, power_dbm: f64) -> f64 {
+        match *self.messages().output_calibration.lock().unpoisoned() {
+            Some(ref calibration) => calibration.power_setting_dbm(freq, power_dbm),
+            None => power_dbm,
+        }
+    }
+
+    /// Returns the expected progress of the current frequency sweep.
+    ///
+    /// `None` is returned if a frequency sweep isn't running.
+    pub fn sweep_progress(&self) -> Option<SweepProgress> {
+        self.messages()
+            .sweep_schedule
+            .0
+            .lock()
+            .unpoisoned()
+            .map(|schedule| schedule.progress_at(Instant::now()))
+    }
+
+    /// Sets the callback that is executed every `step_interval` steps of a frequency sweep.
+    pub fn set_sweep_progress_callback(
+        &self,
+        step_interval: u32,
+        cb: impl FnMut(SweepProgress) + Send + 'static,
+    ) {
+        *self.messages().sweep_progress_callback.lock().unpoisoned() =
+            Some((step_interval.max(1), Arc::new(Mutex::new(cb))));
+        let generation = self
+            .messages()
+            .sweep_progress_generation
+            .fetch_add(1, Ordering::SeqCst)
+            + 1;
+        let messages = self.rfe.messages_weak();
+        thread::spawn(move || run_sweep_progress_callback(messages, generation));
+    }
+
+    /// Removes the callback that is executed during a frequency sweep.
+    pub fn remove_sweep_progress_callback(&self) {
+        *self.messages().sweep_progress_callback.lock().unpoisoned() = None;
+        self.messages()
+            .sweep_progress_generation
+            .fetch_add(1, Ordering::SeqCst);
+        self.messages().sweep_schedule.1.notify_all();
     }
 
     /// Jumps to a new frequency using the tracking step frequency.
@@ -310,32 +469,40 @@ impl SignalGenerator {
 
     /// Sets the callback that is executed when the signal generator receives a `Config`.
     pub fn set_config_callback(&self, cb: impl FnMut(Config) + Send + 'static) {
-        *self.messages().config_callback.lock().unwrap() = Some(Box::new(cb));
+        *self.messages().config_callback.lock().unpoisoned() = Some(Box::new(cb));
     }
 
     /// Removes the callback that is executed when the signal generator receives a `Config`.
     pub fn remove_config_callback(&self) {
-        *self.messages().config_callback.lock().unwrap() = None;
+        *self.messages().config_callback.lock().unpoisoned() = None;
     }
 
     /// Sets the callback that is executed when the signal generator receives a `ConfigExp`.
     pub fn set_config_exp_callback(&self, cb: impl FnMut(ConfigExp) + Send + 'static) {
-        *self.messages().config_exp_callback.lock().unwrap() = Some(Box::new(cb));
+        *self.messages().config_exp_callback.lock().unpoisoned() = Some(Box::new(cb));
     }
 
     /// Removes the callback that is executed when the signal generator receives a `ConfigExp`.
     pub fn remove_config_exp_callback(&self) {
-        *self.messages().config_exp_callback.lock().unwrap() = None;
+        *self.messages().config_exp_callback.lock().unpoisoned() = None;
     }
 
     /// Sets the callback that is executed when the signal generator receives a `ConfigAmpSweep`.
     pub fn set_config_amp_sweep_callback(&self, cb: impl FnMut(ConfigAmpSweep) + Send + 'static) {
-        *self.messages().config_amp_sweep_callback.lock().unwrap() = Some(Box::new(cb));
+        *self
+            .messages()
+            .config_amp_sweep_callback
+            .lock()
+            .unpoisoned() = Some(Box::new(cb));
     }
 
     /// Removes the callback that is executed when the signal generator receives a `ConfigAmpSweep`.
     pub fn remove_config_amp_sweep_callback(&self) {
-        *self.messages().config_amp_sweep_callback.lock().unwrap() = None;
+        *self
+            .messages()
+            .config_amp_sweep_callback
+            .lock()
+            .unpoisoned() = None;
     }
 
     /// Sets the callback that is executed when the signal generator receives a `ConfigAmpSweepExp`.
@@ -347,7 +514,7 @@ impl SignalGenerator {
             .messages()
             .config_amp_sweep_exp_callback
             .lock()
-            .unwrap() = Some(Box::new(cb));
+            .unpoisoned() = Some(Box::new(cb));
     }
 
     /// Removes the callback that is executed when the signal generator receives a `ConfigAmpSweepExp`.
@@ -356,37 +523,45 @@ impl SignalGenerator {
             .messages()
             .config_amp_sweep_exp_callback
             .lock()
-            .unwrap() = None;
+            .unpoisoned() = None;
     }
 
     /// Sets the callback that is executed when the signal generator receives a `ConfigCw`.
     pub fn set_config_cw_callback(&self, cb: impl FnMut(ConfigCw) + Send + 'static) {
-        *self.messages().config_cw_callback.lock().unwrap() = Some(Box::new(cb));
+        *self.messages().config_cw_callback.lock().unpoisoned() = Some(Box::new(cb));
     }
 
     /// Removes the callback that is executed when the signal generator receives a `ConfigCw`.
     pub fn remove_config_cw_callback(&self) {
-        *self.messages().config_cw_callback.lock().unwrap() = None;
+        *self.messages().config_cw_callback.lock().unpoisoned() = None;
     }
 
     /// Sets the callback that is executed when the signal generator receives a `ConfigCwExp`.
     pub fn set_config_cw_exp_callback(&self, cb: impl FnMut(ConfigCwExp) + Send + 'static) {
-        *self.messages().config_cw_exp_callback.lock().unwrap() = Some(Box::new(cb));
+        *self.messages().config_cw_exp_callback.lock().unpoisoned() = Some(Box::new(cb));
     }
 
     /// Removes the callback that is executed when the signal generator receives a `ConfigCwExp`.
     pub fn remove_config_cw_exp_callback(&self) {
-        *self.messages().config_cw_exp_callback.lock().unwrap() = None;
+        *self.messages().config_cw_exp_callback.lock().unpoisoned() = None;
     }
 
     /// Sets the callback that is executed when the signal generator receives a `ConfigFreqSweep`.
     pub fn set_config_freq_sweep_callback(&self, cb: impl FnMut(ConfigFreqSweep) + Send + 'static) {
-        *self.messages().config_freq_sweep_callback.lock().unwrap() = Some(Box::new(cb));
+        *self
+            .messages()
+            .config_freq_sweep_callback
+            .lock()
+            .unpoisoned() = Some(Box::new(cb));
     }
 
     /// Removes the callback that is executed when the signal generator receives a `ConfigFreqSweep`.
     pub fn remove_config_freq_sweep_callback(&self) {
-        *self.messages().config_freq_sweep_callback.lock().unwrap() = None;
+        *self
+            .messages()
+            .config_freq_sweep_callback
+            .lock()
+            .unpoisoned() = None;
     }
 
     /// Sets the callback that is executed when the signal generator receives a `ConfigFreqSweepExp`.
@@ -398,7 +573,7 @@ impl SignalGenerator {
             .messages()
             .config_freq_sweep_exp_callback
             .lock()
-            .unwrap() = Some(Box::new(cb));
+            .unpoisoned() = Some(Box::new(cb));
     }
 
     /// Removes the callback that is executed when the signal generator receives a `ConfigFreqSweepExp`.
@@ -407,17 +582,138 @@ impl SignalGenerator {
             .messages()
             .config_freq_sweep_exp_callback
             .lock()
-            .unwrap() = None;
+            .unpoisoned() = None;
     }
 
     /// Turns on RF power with the current power and frequency configuration.
-    pub fn rf_power_on(&self) -> io::Result<()> {
-        self.send_command(super::Command::RfPowerOn)
+    ///
+    /// Returns once the signal generator reports that RF power is on.
+    pub fn rf_power_on(&self) -> Result<()> {
+        self.send_command(super::Command::RfPowerOn)?;
+        self.wait_for_rf_power(RfPower::On)
     }
 
     /// Turns off RF power.
-    pub fn rf_power_off(&self) -> io::Result<()> {
-        self.send_command(super::Command::RfPowerOff)
+    ///
+    /// Returns once the signal generator reports that RF power is off.
+    pub fn rf_power_off(&self) -> Result<()> {
+        self.send_command(super::Command::RfPowerOff)?;
+        self.messages().set_sweep_schedule(None);
+        *self.messages().operating_mode.lock().unpoisoned() = None;
+        self.wait_for_rf_power(RfPower::Off)
+    }
+
+    /// Returns the most recently reported RF power state and the time it was received.
+    pub fn rf_power_state_with_timestamp(&self) -> Option<(RfPower, DateTime<Utc>)> {
+        *self.messages().rf_power.0.lock().unpoisoned()
+    }
+
+    /// Requests the current config and waits for it to report the given RF power state.
+    fn wait_for_rf_power(&self, rf_power: RfPower) -> Result<()> {
+        ensure_not_reader_thread()?;
+        let requested_at = Utc::now();
+        self.send_command(crate::rf_explorer::Command::RequestConfig)?;
+
+        tracing::trace!("Waiting to receive RF power state {:?}", rf_power);
+        let (lock, condvar) = &self.messages().rf_power;
+        let (state, wait_result) = condvar
+            .wait_timeout_while(
+                lock.lock().unpoisoned(),
+                COMMAND_RESPONSE_TIMEOUT,
+                |state| {
+                    !matches!(state, Some((state, timestamp)) if *state == rf_power && *timestamp >= requested_at)
+                },
+            ).unpoisoned();
+
+        if !wait_result.timed_out() {
+            return Ok(());
+        }
+
+        let last_reported = match *state {
+            Some((state, timestamp)) if timestamp >= requested_at => format!("reported {state:?}"),
+            _ => "didn't report its RF power".to_string(),
+        };
+        tracing::warn!(
+            "Timed out waiting for RF power state {:?}, the signal generator {}",
+            rf_power,
+            last_reported
+        );
+        Err(crate::Error::TimedOut(COMMAND_RESPONSE_TIMEOUT))
+    }
+}
+
+type RfPowerState = (RfPower, DateTime<Utc>);
+type SweepProgressCallback = Option<(u32, Arc<Mutex<dyn FnMut(SweepProgress) + Send>>)>;
+
+/// The longest the sweep progress thread waits before checking if it should stop.
+const SWEEP_PROGRESS_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Calls the sweep progress callback until it's replaced or removed, or the device is dropped.
+fn run_sweep_progress_callback(messages: Weak<MessageContainer>, generation: u64) {
+    loop {
+        let Some(messages) = messages.upgrade() else {
+            return;
+        };
+        if messages.sweep_progress_generation.load(Ordering::SeqCst) != generation {
+            return;
+        }
+
+        let Some(step_interval) = messages
+            .sweep_progress_callback
+            .lock()
+            .unpoisoned()
+            .as_ref()
+            .map(|(step_interval, _)| u64::from(*step_interval))
+        else {
+            return;
+        };
+
+        let (lock, condvar) = &messages.sweep_schedule;
+        let schedule_guard = lock.lock().unpoisoned();
+        let Some(schedule) = *schedule_guard else {
+            let _ = condvar
+                .wait_timeout(schedule_guard, SWEEP_PROGRESS_POLL_INTERVAL)
+                .unpoisoned();
+            continue;
+        };
+        if schedule.step_delay().is_zero() {
+            let _ = condvar
+                .wait_timeout(schedule_guard, SWEEP_PROGRESS_POLL_INTERVAL)
+                .unpoisoned();
+            continue;
+        }
+
+        let now = Instant::now();
+        let next_step = (schedule.elapsed_steps(now) / step_interval + 1) * step_interval;
+        let Some(next_step_at) = schedule.instant_of_step(next_step) else {
+            return;
+        };
+        let timeout = next_step_at
+            .saturating_duration_since(now)
+            .min(SWEEP_PROGRESS_POLL_INTERVAL);
+        let (schedule_guard, _) = condvar.wait_timeout(schedule_guard, timeout).unpoisoned();
+
+        // Only report progress if the sweep is still running and the step has been reached
+        if *schedule_guard != Some(schedule) || Instant::now() < next_step_at {
+            continue;
+        }
+        drop(schedule_guard);
+
+        if messages.sweep_progress_generation.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        // The callback is taken out of its lock before it's called so that it can replace or
+        // remove itself
+        let cb = messages
+            .sweep_progress_callback
+            .lock()
+            .unpoisoned()
+            .as_ref()
+            .map(|(_, cb)| Arc::clone(cb));
+        if let Some(cb) = cb {
+            let mut cb = cb.lock().unpoisoned();
+            let _ = call_callback("SweepProgress", || cb(schedule.progress_at(next_step_at)));
+        }
     }
 }
 
@@ -439,124 +735,218 @@ struct MessageContainer {
     pub(crate) config_freq_sweep_callback: Mutex<Callback<ConfigFreqSweep>>,
     pub(crate) config_freq_sweep_exp: (Mutex<Option<ConfigFreqSweepExp>>, Condvar),
     pub(crate) config_freq_sweep_exp_callback: Mutex<Callback<ConfigFreqSweepExp>>,
+    pub(crate) rf_power: (Mutex<Option<RfPowerState>>, Condvar),
     pub(crate) screen_data: (Mutex<Option<ScreenData>>, Condvar),
     pub(crate) temperature: (Mutex<Option<Temperature>>, Condvar),
     pub(crate) setup_info: (Mutex<Option<SetupInfo<Model>>>, Condvar),
+    pub(crate) is_other_device_type: AtomicBool,
     pub(crate) serial_number: (Mutex<Option<SerialNumber>>, Condvar),
+    pub(crate) output_calibration: Mutex<Option<OutputCalibration>>,
+    pub(crate) sweep_schedule: (Mutex<Option<SweepSchedule>>, Condvar),
+    pub(crate) sweep_progress_callback: Mutex<SweepProgressCallback>,
+    pub(crate) sweep_progress_generation: AtomicU64,
+    pub(crate) operating_mode: Mutex<Option<OperatingMode>>,
+}
+
+impl MessageContainer {
+    fn cache_rf_power(&self, message: &super::Message) {
+        use super::Message;
+        let (rf_power, timestamp) = match message {
+            Message::Config(config) => (config.rf_power, config.timestamp),
+            Message::ConfigAmpSweep(config) => (config.rf_power, config.timestamp),
+            Message::ConfigCw(config) => (config.rf_power, config.timestamp),
+            Message::ConfigFreqSweep(config) => (config.rf_power, config.timestamp),
+            Message::ConfigExp(config) if config.rf_power_on => (RfPower::On, config.timestamp),
+            Message::ConfigExp(config) => (RfPower::Off, config.timestamp),
+            Message::ConfigCwExp(config) => (config.rf_power, config.timestamp),
+            Message::ConfigFreqSweepExp(config) => (config.rf_power, config.timestamp),
+            _ => return,
+        };
+        *self.rf_power.0.lock().unpoisoned() = Some((rf_power, timestamp));
+        self.rf_power.1.notify_all();
+    }
+
+    fn cache_sweep_schedule(&self, message: &super::Message) {
+        use super::Message;
+        let schedule = match message {
+            Message::ConfigFreqSweep(config) if config.rf_power == RfPower::On => {
+                Some(SweepSchedule::from_config(config, Instant::now()))
+            }
+            Message::ConfigFreqSweepExp(config) if config.rf_power == RfPower::On => {
+                Some(SweepSchedule::from_config_exp(config, Instant::now()))
+            }
+            // Any other mode's config, or RF power turning off, means a sweep isn't running
+            Message::ConfigFreqSweep(_)
+            | Message::ConfigFreqSweepExp(_)
+            | Message::ConfigCw(_)
+            | Message::ConfigCwExp(_)
+            | Message::ConfigAmpSweep(_)
+            | Message::ConfigAmpSweepExp(_) => None,
+            Message::Config(config) if config.rf_power == RfPower::Off => None,
+            Message::ConfigExp(config) if !config.rf_power_on => None,
+            _ => return,
+        };
+
+        // Keep the original start time if the same sweep's config is received again
+        if let (Some(current), Some(schedule)) =
+            (*self.sweep_schedule.0.lock().unpoisoned(), schedule)
+        {
+            if current.is_same_sweep(&schedule) {
+                return;
+            }
+        }
+        self.set_sweep_schedule(schedule);
+    }
+
+    fn set_sweep_schedule(&self, schedule: Option<SweepSchedule>) {
+        *self.sweep_schedule.0.lock().unpoisoned() = schedule;
+        self.sweep_schedule.1.notify_all();
+    }
 }
 
 impl crate::common::MessageContainer for MessageContainer {
     type Message = super::Message;
+    const DEVICE_TYPE: DeviceType = DeviceType::SignalGenerator;
 
     fn cache_message(&self, message: Self::Message) {
+        tracing::trace!(
+            prefix = String::from_utf8_lossy(message.prefix()).as_ref(),
+            "Caching message"
+        );
+        self.cache_rf_power(&message);
+        self.cache_sweep_schedule(&message);
         match message {
             Self::Message::Config(config) => {
-                *self.config.0.lock().unwrap() = Some(config);
-                self.config.1.notify_one();
-                if let Some(ref mut cb) = *self.config_callback.lock().unwrap() {
-                    cb(config);
+                *self.config.0.lock().unpoisoned() = Some(config);
+                self.config.1.notify_all();
+                if let Some(ref mut cb) = *self.config_callback.lock().unpoisoned() {
+                    let _ = call_callback("Config", || cb(config));
                 }
             }
             Self::Message::ConfigAmpSweep(config) => {
-                *self.config_amp_sweep.0.lock().unwrap() = Some(config);
-                self.config_amp_sweep.1.notify_one();
-                if let Some(ref mut cb) = *self.config_amp_sweep_callback.lock().unwrap() {
-                    cb(config);
+                *self.config_amp_sweep.0.lock().unpoisoned() = Some(config);
+                self.config_amp_sweep.1.notify_all();
+                if let Some(ref mut cb) = *self.config_amp_sweep_callback.lock().unpoisoned() {
+                    let _ = call_callback("ConfigAmpSweep", || cb(config));
                 }
             }
             Self::Message::ConfigCw(config) => {
-                *self.config_cw.0.lock().unwrap() = Some(config);
-                self.config_cw.1.notify_one();
-                if let Some(ref mut cb) = *self.config_cw_callback.lock().unwrap() {
-                    cb(config);
+                *self.config_cw.0.lock().unpoisoned() = Some(config);
+                self.config_cw.1.notify_all();
+                if let Some(ref mut cb) = *self.config_cw_callback.lock().unpoisoned() {
+                    let _ = call_callback("ConfigCw", || cb(config));
                 }
             }
             Self::Message::ConfigFreqSweep(config) => {
-                *self.config_freq_sweep.0.lock().unwrap() = Some(config);
-                self.config_freq_sweep.1.notify_one();
-                if let Some(ref mut cb) = *self.config_freq_sweep_callback.lock().unwrap() {
-                    cb(config);
+                *self.config_freq_sweep.0.lock().unpoisoned() = Some(config);
+                self.config_freq_sweep.1.notify_all();
+                if let Some(ref mut cb) = *self.config_freq_sweep_callback.lock().unpoisoned() {
+                    let _ = call_callback("ConfigFreqSweep", || cb(config));
                 }
             }
             Self::Message::ConfigExp(config) => {
-                *self.config_exp.0.lock().unwrap() = Some(config);
-                self.config_exp.1.notify_one();
-                if let Some(ref mut cb) = *self.config_exp_callback.lock().unwrap() {
-                    cb(config);
+                *self.config_exp.0.lock().unpoisoned() = Some(config);
+                self.config_exp.1.notify_all();
+                if let Some(ref mut cb) = *self.config_exp_callback.lock().unpoisoned() {
+                    let _ = call_callback("ConfigExp", || cb(config));
                 }
             }
             Self::Message::ConfigAmpSweepExp(config) => {
-                *self.config_amp_sweep_exp.0.lock().unwrap() = Some(config);
-                self.config_amp_sweep.1.notify_one();
-                if let Some(ref mut cb) = *self.config_amp_sweep_exp_callback.lock().unwrap() {
-                    cb(config);
+                *self.config_amp_sweep_exp.0.lock().unpoisoned() = Some(config);
+                self.config_amp_sweep_exp.1.notify_all();
+                if let Some(ref mut cb) = *self.config_amp_sweep_exp_callback.lock().unpoisoned() {
+                    let _ = call_callback("ConfigAmpSweepExp", || cb(config));
                 }
             }
             Self::Message::ConfigCwExp(config) => {
-                *self.config_cw_exp.0.lock().unwrap() = Some(config);
-                self.config_cw_exp.1.notify_one();
-                if let Some(ref mut cb) = *self.config_cw_exp_callback.lock().unwrap() {
-                    cb(config);
+                *self.config_cw_exp.0.lock().unpoisoned() = Some(config);
+                self.config_cw_exp.1.notify_all();
+                if let Some(ref mut cb) = *self.config_cw_exp_callback.lock().unpoisoned() {
+                    let _ = call_callback("ConfigCwExp", || cb(config));
                 }
             }
             Self::Message::ConfigFreqSweepExp(config) => {
-                *self.config_freq_sweep_exp.0.lock().unwrap() = Some(config);
-                self.config_freq_sweep_exp.1.notify_one();
-                if let Some(ref mut cb) = *self.config_freq_sweep_exp_callback.lock().unwrap() {
-                    cb(config);
+                *self.config_freq_sweep_exp.0.lock().unpoisoned() = Some(config);
+                self.config_freq_sweep_exp.1.notify_all();
+                if let Some(ref mut cb) = *self.config_freq_sweep_exp_callback.lock().unpoisoned() {
+                    let _ = call_callback("ConfigFreqSweepExp", || cb(config));
                 }
             }
             Self::Message::ScreenData(screen_data) => {
-                *self.screen_data.0.lock().unwrap() = Some(screen_data);
-                self.screen_data.1.notify_one();
+                *self.screen_data.0.lock().unpoisoned() = Some(screen_data);
+                self.screen_data.1.notify_all();
             }
             Self::Message::SerialNumber(serial_number) => {
-                *self.serial_number.0.lock().unwrap() = Some(serial_number);
-                self.serial_number.1.notify_one();
+                *self.serial_number.0.lock().unpoisoned() = Some(serial_number);
+                self.serial_number.1.notify_all();
             }
             Self::Message::SetupInfo(setup_info) => {
-                *self.setup_info.0.lock().unwrap() = Some(setup_info);
-                self.setup_info.1.notify_one();
+                *self.setup_info.0.lock().unpoisoned() = Some(setup_info);
+                self.setup_info.1.notify_all();
             }
             Self::Message::Temperature(temperature) => {
-                *self.temperature.0.lock().unwrap() = Some(temperature);
-                self.temperature.1.notify_one();
+                *self.temperature.0.lock().unpoisoned() = Some(temperature);
+                self.temperature.1.notify_all();
             }
         }
     }
 
+    fn other_device_type_found(&self) {
+        self.is_other_device_type.store(true, Ordering::Relaxed);
+        // The locks are taken before notifying so that a waiter can't miss the notification
+        // between checking the flag and waiting
+        drop(self.config.0.lock().unpoisoned());
+        self.config.1.notify_all();
+        drop(self.setup_info.0.lock().unpoisoned());
+        self.setup_info.1.notify_all();
+    }
+
     fn wait_for_device_info(&self) -> ConnectionResult<()> {
         let (config_lock, config_cvar) = &self.config;
         let (setup_info_lock, setup_info_cvar) = &self.setup_info;
 
         // Check to see if we've already received a Config and SetupInfo
-        if config_lock.lock().unwrap().is_some() && setup_info_lock.lock().unwrap().is_some() {
+        if config_lock.lock().unpoisoned().is_some()
+            && setup_info_lock.lock().unpoisoned().is_some()
+        {
             return Ok(());
         }
 
         // Wait to see if we receive a Config and SetupInfo before timing out
-        if config_cvar
+        let config_received = config_cvar
             .wait_timeout_while(
-                config_lock.lock().unwrap(),
+                config_lock.lock().unpoisoned(),
                 RECEIVE_INITIAL_DEVICE_INFO_TIMEOUT,
-                |config| config.is_none(),
+                |config| config.is_none() && !self.is_other_device_type.load(Ordering::Relaxed),
             )
-            .unwrap()
+            .unpoisoned()
             .0
-            .is_some()
-            && setup_info_cvar
+            .is_some();
+
+        // Don't wait for the SetupInfo if the Config never arrived since the device isn't responding
+        let setup_info_received = if config_received {
+            setup_info_cvar
                 .wait_timeout_while(
-                    setup_info_lock.lock().unwrap(),
+                    setup_info_lock.lock().unpoisoned(),
                     RECEIVE_INITIAL_DEVICE_INFO_TIMEOUT,
-                    |setup_info| setup_info.is_none(),
+                    |setup_info| {
+                        setup_info.is_none() && !self.is_other_device_type.load(Ordering::Relaxed)
+                    },
                 )
-                .unwrap()
+                .unpoisoned()
                 .0
                 .is_some()
-        {
+        } else {
+            setup_info_lock.lock().unpoisoned().is_some()
+        };
+
+        if config_received && setup_info_received {
             Ok(())
         } else {
-            Err(ConnectionError::DeviceInfoNotReceived)
+            Err(ConnectionError::InitializationTimeout {
+                missing_config: !config_received,
+                missing_setup_info: !setup_info_received,
+            })
         }
     }
 }
@@ -564,27 +954,210 @@ impl crate::common::MessageContainer for MessageContainer {
 impl Debug for MessageContainer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("MessageContainer")
-            .field("config", &self.config.0.lock().unwrap())
-            .field("config_exp", &self.config_exp.0.lock().unwrap())
-            .field("config_cw", &self.config_cw.0.lock().unwrap())
-            .field("config_cw_exp", &self.config_cw_exp.0.lock().unwrap())
-            .field("config_amp_sweep", &self.config_amp_sweep.0.lock().unwrap())
+            .field("config", &self.config.0.lock().unpoisoned())
+            .field("config_exp", &self.config_exp.0.lock().unpoisoned())
+            .field("config_cw", &self.config_cw.0.lock().unpoisoned())
+            .field("config_cw_exp", &self.config_cw_exp.0.lock().unpoisoned())
+            .field(
+                "config_amp_sweep",
+                &self.config_amp_sweep.0.lock().unpoisoned(),
+            )
             .field(
                 "config_amp_sweep_exp",
-                &self.config_amp_sweep_exp.0.lock().unwrap(),
+                &self.config_amp_sweep_exp.0.lock().unpoisoned(),
             )
             .field(
                 "config_freq_sweep",
-                &self.config_freq_sweep.0.lock().unwrap(),
+                &self.config_freq_sweep.0.lock().unpoisoned(),
             )
             .field(
                 "config_freq_sweep_exp",
-                &self.config_freq_sweep_exp.0.lock().unwrap(),
+                &self.config_freq_sweep_exp.0.lock().unpoisoned(),
             )
-            .field("screen_data", &self.screen_data.0.lock().unwrap())
-            .field("temperature", &self.temperature.0.lock().unwrap())
-            .field("setup_info", &self.setup_info.0.lock().unwrap())
-            .field("serial_number", &self.serial_number.0.lock().unwrap())
+            .field("screen_data", &self.screen_data.0.lock().unpoisoned())
+            .field("temperature", &self.temperature.0.lock().unpoisoned())
+            .field("setup_info", &self.setup_info.0.lock().unpoisoned())
+            .field("serial_number", &self.serial_number.0.lock().unpoisoned())
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_support::ScriptedStream;
+    use crate::common::MessageContainer as _;
+    use crate::signal_generator::Message;
+
+    #[test]
+    fn connect_with_name_of_missing_port() {
+        let name = "/dev/rfe-signal-generator-that-does-not-exist";
+        assert!(matches!(
+            SignalGenerator::connect_with_name_and_baud_rate(name, 500_000),
+            Err(ConnectionError::UsbSerialDeviceNotFound(port_name)) if port_name == name
+        ));
+    }
+
+    #[test]
+    fn connect_with_stream_and_receive_device_info() {
+        let stream = ScriptedStream::new([
+            &b"#C3-M:060,255,01.15\r\n"[..],
+            b"#C3-*:0510000,0186525,0005,0001000,0,3,0000,0,0,1,3,0,00100\r\n",
+        ]);
+        let rfe = SignalGenerator::connect_with_stream(stream.clone()).unwrap();
+
+        assert_eq!(rfe.main_radio_model(), Some(Model::Rfe6Gen));
+        assert_eq!(rfe.expansion_radio_model(), None);
+        assert_eq!(rfe.firmware_version(), "01.15");
+        assert_eq!(
+            rfe.config().map(|config| config.start),
+            Some(Frequency::from_khz(510_000))
+        );
+        assert!(stream.written().starts_with(&[b'#', 4, b'C', b'0']));
+    }
+
+    #[test]
+    fn starting_another_mode_while_tracking_is_an_error() {
+        let stream = ScriptedStream::new([
+            &b"#C3-M:060,255,01.15\r\n"[..],
+            b"#C3-*:0510000,0186525,0005,0001000,0,3,0000,0,0,1,3,0,00100\r\n",
+        ]);
+        let rfe = SignalGenerator::connect_with_stream(stream).unwrap();
+
+        rfe.start_tracking(
+            Frequency::from_mhz(100),
+            Attenuation::On,
+            PowerLevel::Lowest,
+            10,
+            Frequency::from_khz(100),
+        )
+        .unwrap();
+        assert!(matches!(
+            rfe.start_cw(
+                Frequency::from_mhz(100),
+                Attenuation::On,
+                PowerLevel::Lowest
+            ),
+            Err(crate::Error::InvalidOperation(_))
+        ));
+        assert_eq!(rfe.operating_mode(), Some(OperatingMode::Tracking));
+    }
+
+    #[test]
+    fn force_stop_without_acknowledgement() {
+        // The signal generator never reports its RF power turning off
+        let stream = ScriptedStream::new([
+            &b"#C3-M:060,255,01.15\r\n"[..],
+            b"#C3-*:0510000,0186525,0005,0001000,0,3,0000,0,0,1,3,0,00100\r\n",
+        ]);
+        let rfe = SignalGenerator::connect_with_stream(stream.clone()).unwrap();
+        rfe.start_freq_sweep_exp(
+            Frequency::from_mhz(100),
+            -10.,
+            10,
+            Frequency::from_khz(100),
+            SweepDelay::MAX,
+        )
+        .unwrap();
+        rfe.messages()
+            .set_sweep_schedule(Some(SweepSchedule::from_config_exp(
+                &ConfigFreqSweepExp::default(),
+                Instant::now(),
+            )));
+        assert_eq!(rfe.operating_mode(), Some(OperatingMode::FreqSweep));
+        assert!(rfe.sweep_progress().is_some());
+
+        rfe.stop_current_force().unwrap();
+        assert_eq!(rfe.operating_mode(), None);
+        assert_eq!(rfe.sweep_progress(), None);
+        assert!(stream
+            .written()
+            .ends_with(&super::super::Command::RfPowerOff.encode()));
+    }
+
+    #[test]
+    fn cache_rf_power_from_config_exp() {
+        let messages = MessageContainer::default();
+        messages.cache_message(Message::ConfigExp(ConfigExp {
+            rf_power_on: true,
+            ..Default::default()
+        }));
+        assert_eq!(
+            messages
+                .rf_power
+                .0
+                .lock()
+                .unpoisoned()
+                .map(|(rf_power, _)| rf_power),
+            Some(RfPower::On)
+        );
+    }
+
+    #[test]
+    fn report_sweep_progress_until_callback_removed() {
+        let messages = Arc::new(MessageContainer::default());
+        let (sender, receiver) = std::sync::mpsc::channel();
+        *messages.sweep_progress_callback.lock().unpoisoned() = Some((
+            2,
+            Arc::new(Mutex::new(move |progress| sender.send(progress).unwrap())),
+        ));
+        messages
+            .sweep_progress_generation
+            .store(1, Ordering::SeqCst);
+        let weak_messages = Arc::downgrade(&messages);
+        let handle = thread::spawn(move || run_sweep_progress_callback(weak_messages, 1));
+
+        messages.cache_message(Message::ConfigFreqSweep(ConfigFreqSweep {
+            start: Frequency::from_mhz(100),
+            step: Frequency::from_mhz(1),
+            total_steps: 100,
+            rf_power: RfPower::On,
+            sweep_delay: SweepDelay::from_millis(5).unwrap(),
+            ..Default::default()
+        }));
+        let progress = receiver.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(progress.step_index % 2, 0);
+
+        messages
+            .sweep_progress_generation
+            .store(2, Ordering::SeqCst);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn sweep_progress_callback_can_remove_itself() {
+        let messages = Arc::new(MessageContainer::default());
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let weak_messages = Arc::downgrade(&messages);
+        *messages.sweep_progress_callback.lock().unpoisoned() = Some((
+            1,
+            Arc::new(Mutex::new(move |progress| {
+                if let Some(messages) = weak_messages.upgrade() {
+                    *messages.sweep_progress_callback.lock().unpoisoned() = None;
+                }
+                sender.send(progress).unwrap();
+            })),
+        ));
+        messages
+            .sweep_progress_generation
+            .store(1, Ordering::SeqCst);
+        let weak_messages = Arc::downgrade(&messages);
+        let handle = thread::spawn(move || run_sweep_progress_callback(weak_messages, 1));
+
+        messages.cache_message(Message::ConfigFreqSweep(ConfigFreqSweep {
+            start: Frequency::from_mhz(100),
+            step: Frequency::from_mhz(1),
+            total_steps: 100,
+            rf_power: RfPower::On,
+            sweep_delay: SweepDelay::from_millis(5).unwrap(),
+            ..Default::default()
+        }));
+        assert!(receiver.recv_timeout(Duration::from_secs(1)).is_ok());
+        handle.join().unwrap();
+        assert!(messages
+            .sweep_progress_callback
+            .lock()
+            .unpoisoned()
+            .is_none());
+    }
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use crate::{Error, Result};
+
+/// The delay between the steps of a signal generator sweep.
+///
+/// The RF Explorer accepts delays with millisecond precision up to 99,999 ms.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SweepDelay(Duration);
+
+/// cbindgen:ignore
+impl SweepDelay {
+    pub const MAX: SweepDelay = SweepDelay(Duration::from_millis(99_999));
+
+    /// Returns a `SweepDelay` with the given duration, truncated to whole milliseconds.
+    ///
+    /// An `InvalidInput` error is returned if the duration is longer than `SweepDelay::MAX`.
+    pub fn new(delay: Duration) -> Result<Self> {
+        if delay > Self::MAX.0 {
+            return Err(Error::InvalidInput(format!(
+                "The sweep delay must be at most {} ms but was {} ms",
+                Self::MAX.as_millis(),
+                delay.as_millis()
+            )));
+        }
+
+        Ok(SweepDelay(Duration::from_millis(delay.as_millis() as u64)))
+    }
+
+    /// Returns a `SweepDelay` of the given number of milliseconds.
+    ///
+    /// An `InvalidInput` error is returned if the delay is longer than `SweepDelay::MAX`.
+    pub fn from_millis(millis: u32) -> Result<Self> {
+        Self::new(Duration::from_millis(u64::from(millis)))
+    }
+
+    pub fn as_duration(&self) -> Duration {
+        self.0
+    }
+
+    pub fn as_millis(&self) -> u32 {
+        // The delay is at most 99,999 ms, so it always fits in a u32
+        self.0.as_millis() as u32
+    }
+}
+
+impl TryFrom<Duration> for SweepDelay {
+    type Error = Error;
+
+    fn try_from(delay: Duration) -> Result<Self> {
+        SweepDelay::new(delay)
+    }
+}
+
+impl From<SweepDelay> for Duration {
+    fn from(delay: SweepDelay) -> Self {
+        delay.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_to_whole_millis() {
+        let delay = SweepDelay::new(Duration::from_micros(1_500)).unwrap();
+        assert_eq!(delay.as_duration(), Duration::from_millis(1));
+        assert_eq!(delay.as_millis(), 1);
+    }
+
+    #[test]
+    fn reject_delays_longer_than_max() {
+        assert_eq!(SweepDelay::from_millis(99_999).unwrap(), SweepDelay::MAX);
+        assert!(SweepDelay::from_millis(100_000).is_err());
+        assert!(SweepDelay::try_from(Duration::from_secs(255)).is_err());
+    }
+}
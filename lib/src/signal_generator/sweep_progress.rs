@@ -0,0 +1,116 @@
+use std::time::{Duration, Instant};
+
+use super::{ConfigFreqSweep, ConfigFreqSweepExp};
+use crate::Frequency;
+
+/// The expected progress of a signal generator's frequency sweep.
+///
+/// The RF Explorer doesn't report the progress of a sweep, so this is computed from the sweep's
+/// configuration and the time elapsed since it started.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SweepProgress {
+    /// The index of the current step, where 0 is the start frequency.
+    pub step_index: u32,
+    pub total_steps: u32,
+    pub freq: Frequency,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) struct SweepSchedule {
+    start: Frequency,
+    step: Frequency,
+    total_steps: u32,
+    step_delay: Duration,
+    started_at: Instant,
+}
+
+impl SweepSchedule {
+    pub(crate) fn from_config(config: &ConfigFreqSweep, started_at: Instant) -> Self {
+        SweepSchedule {
+            start: config.start,
+            step: config.step,
+            total_steps: config.total_steps,
+            step_delay: config.sweep_delay.as_duration(),
+            started_at,
+        }
+    }
+
+    pub(crate) fn from_config_exp(config: &ConfigFreqSweepExp, started_at: Instant) -> Self {
+        SweepSchedule {
+            start: config.start,
+            step: config.step,
+            total_steps: config.total_steps,
+            step_delay: config.sweep_delay.as_duration(),
+            started_at,
+        }
+    }
+
+    /// Returns true if both schedules describe the same sweep, regardless of when they started.
+    pub(crate) fn is_same_sweep(&self, other: &SweepSchedule) -> bool {
+        self.start == other.start
+            && self.step == other.step
+            && self.total_steps == other.total_steps
+            && self.step_delay == other.step_delay
+    }
+
+    pub(crate) fn step_delay(&self) -> Duration {
+        self.step_delay
+    }
+
+    /// Returns the number of steps taken since the sweep started, including repeats of the sweep.
+    pub(crate) fn elapsed_steps(&self, now: Instant) -> u64 {
+        if self.step_delay.is_zero() {
+            return 0;
+        }
+
+        let elapsed = now.saturating_duration_since(self.started_at);
+        u64::try_from(elapsed.as_nanos() / self.step_delay.as_nanos()).unwrap_or(u64::MAX)
+    }
+
+    /// Returns the time at which the given number of steps will have been taken.
+    pub(crate) fn instant_of_step(&self, elapsed_steps: u64) -> Option<Instant> {
+        let nanos = u64::try_from(self.step_delay.as_nanos())
+            .ok()?
+            .checked_mul(elapsed_steps)?;
+        self.started_at.checked_add(Duration::from_nanos(nanos))
+    }
+
+    pub(crate) fn progress_at(&self, now: Instant) -> SweepProgress {
+        // The sweep visits the start frequency plus one frequency per step, then repeats
+        let step_index = (self.elapsed_steps(now) % (u64::from(self.total_steps) + 1)) as u32;
+        SweepProgress {
+            step_index,
+            total_steps: self.total_steps,
+            freq: self.start + self.step * u64::from(step_index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal_generator::SweepDelay;
+
+    #[test]
+    fn progress_wraps_after_last_step() {
+        let started_at = Instant::now();
+        let schedule = SweepSchedule::from_config(
+            &ConfigFreqSweep {
+                start: Frequency::from_mhz(100),
+                step: Frequency::from_mhz(1),
+                total_steps: 10,
+                sweep_delay: SweepDelay::from_millis(100).unwrap(),
+                ..Default::default()
+            },
+            started_at,
+        );
+
+        let progress = schedule.progress_at(started_at + Duration::from_millis(350));
+        assert_eq!(progress.step_index, 3);
+        assert_eq!(progress.freq, Frequency::from_mhz(103));
+
+        let progress = schedule.progress_at(started_at + Duration::from_millis(1_150));
+        assert_eq!(progress.step_index, 0);
+        assert_eq!(progress.freq, Frequency::from_mhz(100));
+    }
+}
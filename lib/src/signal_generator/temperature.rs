@@ -37,7 +37,7 @@ impl Temperature {
 impl<'a> TryFrom<&'a [u8]> for Temperature {
     type Error = MessageParseError<'a>;
 
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
         // Parse the prefix of the message
         let (bytes, _) = tag(Temperature::PREFIX)(bytes)?;
 
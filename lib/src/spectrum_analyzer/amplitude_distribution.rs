@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+
+use super::{Config, Sweep};
+use crate::Frequency;
+
+/// The point in each sweep whose amplitudes are accumulated by an `AmplitudeDistribution`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DistributionPoint {
+    /// The point at the given index in each sweep.
+    Index(usize),
+    /// The point closest to the given frequency in each sweep.
+    Frequency(Frequency),
+}
+
+/// Accumulates the distribution of the amplitudes measured at a single point of a series of
+/// sweeps.
+///
+/// Amplitudes are counted in buckets of `bucket_width_db`, so histograms, CCDFs, and percentiles
+/// are only as precise as the bucket width. The distribution is cleared whenever the `Config` the
+/// sweeps were measured with changes, so amplitudes measured at different frequencies are never
+/// mixed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmplitudeDistribution {
+    point: DistributionPoint,
+    bucket_width_db: f32,
+    buckets: BTreeMap<i32, u64>,
+    count: u64,
+    config: Option<Config>,
+}
+
+impl AmplitudeDistribution {
+    /// Creates an empty distribution of the amplitudes at `point` with buckets `bucket_width_db`
+    /// wide.
+    ///
+    /// Bucket widths that aren't positive are replaced with a width of 1 dB.
+    pub fn new(point: DistributionPoint, bucket_width_db: f32) -> Self {
+        AmplitudeDistribution {
+            point,
+            bucket_width_db: if bucket_width_db > 0. {
+                bucket_width_db
+            } else {
+                1.
+            },
+            buckets: BTreeMap::new(),
+            count: 0,
+            config: None,
+        }
+    }
+
+    /// Adds the amplitude of the sweep's point to the distribution.
+    ///
+    /// `config` should be the `Config` the sweep was measured with. The distribution is cleared
+    /// first if it differs from the `Config` of the previous sweep. Sweeps that don't contain the
+    /// point are ignored.
+    pub fn push(&mut self, config: &Config, sweep: &Sweep) {
+        if self
+            .config
+            .as_ref()
+            .is_some_and(|previous| !previous.eq_ignoring_timestamp(config))
+        {
+            self.clear();
+        }
+        self.config = Some(config.clone());
+
+        let Some(amp_dbm) = self
+            .index(config)
+            .and_then(|index| sweep.amplitudes_dbm().get(index))
+            .filter(|amp_dbm| amp_dbm.is_finite())
+        else {
+            return;
+        };
+        let bucket = (amp_dbm / self.bucket_width_db).floor() as i32;
+        *self.buckets.entry(bucket).or_default() += 1;
+        self.count += 1;
+    }
+
+    /// Discards every accumulated amplitude.
+    pub fn clear(&mut self) {
+        self.buckets.clear();
+        self.count = 0;
+    }
+
+    /// The number of amplitudes accumulated.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the lower edge (in dBm) and number of amplitudes of each non-empty bucket, ordered
+    /// from the lowest amplitude to the highest.
+    pub fn histogram(&self) -> Vec<(f32, u64)> {
+        self.buckets
+            .iter()
+            .map(|(bucket, count)| (self.lower_edge_dbm(*bucket), *count))
+            .collect()
+    }
+
+    /// Returns the lower edge (in dBm) of each non-empty bucket along with the fraction of
+    /// amplitudes at or above it, ordered from the lowest amplitude to the highest.
+    pub fn ccdf(&self) -> Vec<(f32, f64)> {
+        let mut remaining = self.count;
+        self.buckets
+            .iter()
+            .map(|(bucket, count)| {
+                let fraction = remaining as f64 / self.count as f64;
+                remaining -= count;
+                (self.lower_edge_dbm(*bucket), fraction)
+            })
+            .collect()
+    }
+
+    /// Returns the amplitude (in dBm) that `percentile` percent of the accumulated amplitudes are
+    /// at or below, rounded up to the upper edge of its bucket.
+    ///
+    /// `None` is returned if no amplitudes have been accumulated.
+    pub fn percentile(&self, percentile: f32) -> Option<f32> {
+        let target = (f64::from(percentile.clamp(0., 100.)) / 100. * self.count as f64).ceil();
+        let mut cumulative = 0;
+        self.buckets.iter().find_map(|(bucket, count)| {
+            cumulative += count;
+            (cumulative as f64 >= target).then(|| self.lower_edge_dbm(*bucket + 1))
+        })
+    }
+
+    fn index(&self, config: &Config) -> Option<usize> {
+        match self.point {
+            DistributionPoint::Index(index) => Some(index),
+            DistributionPoint::Frequency(freq) => {
+                let step_hz = config.step_size.as_hz();
+                if freq < config.start_freq || step_hz == 0 {
+                    return None;
+                }
+                let offset_hz = (freq - config.start_freq).as_hz();
+                usize::try_from((offset_hz + step_hz / 2) / step_hz).ok()
+            }
+        }
+    }
+
+    fn lower_edge_dbm(&self, bucket: i32) -> f32 {
+        bucket as f32 * self.bucket_width_db
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_support::sweep;
+
+    fn config(start_mhz: u64) -> Config {
+        Config {
+            start_freq: Frequency::from_mhz(start_mhz),
+            step_size: Frequency::from_mhz(1),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn histogram_ccdf_and_percentiles() {
+        let mut distribution =
+            AmplitudeDistribution::new(DistributionPoint::Frequency(Frequency::from_mhz(101)), 5.);
+        for amp_dbm in [-82., -81., -78., -61.] {
+            distribution.push(&config(100), &sweep(&[-100., amp_dbm, -100.]));
+        }
+
+        assert_eq!(distribution.count(), 4);
+        assert_eq!(distribution.histogram(), [(-85., 2), (-80., 1), (-65., 1)]);
+        assert_eq!(distribution.ccdf(), [(-85., 1.), (-80., 0.5), (-65., 0.25)]);
+        assert_eq!(distribution.percentile(50.), Some(-80.));
+        assert_eq!(distribution.percentile(100.), Some(-60.));
+    }
+
+    #[test]
+    fn clear_when_config_changes() {
+        let mut distribution = AmplitudeDistribution::new(DistributionPoint::Index(0), 1.);
+        distribution.push(&config(100), &sweep(&[-50.]));
+        distribution.push(&config(100), &sweep(&[-50.]));
+        assert_eq!(distribution.count(), 2);
+
+        distribution.push(&config(200), &sweep(&[-50.]));
+        assert_eq!(distribution.count(), 1);
+        assert_eq!(
+            AmplitudeDistribution::new(DistributionPoint::Index(0), 1.).percentile(50.),
+            None
+        );
+    }
+}
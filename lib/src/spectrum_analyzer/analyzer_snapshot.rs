@@ -0,0 +1,11 @@
+use super::{Config, DspMode, InputStage, Sweep, TrackingStatus};
+
+/// The state cached by a spectrum analyzer at a single point in time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnalyzerSnapshot {
+    pub config: Option<Config>,
+    pub sweep: Option<Sweep>,
+    pub dsp_mode: Option<DspMode>,
+    pub input_stage: Option<InputStage>,
+    pub tracking_status: Option<TrackingStatus>,
+}
@@ -0,0 +1,182 @@
+use super::{InputStage, Sweep};
+
+/// The thresholds used to automatically switch a spectrum analyzer's input stage.
+///
+/// The gap between `lower_peak_dbm` and `upper_peak_dbm` provides hysteresis, so a signal whose
+/// peak sits near one threshold doesn't cause the input stage to switch back and forth.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AutoRangingPolicy {
+    /// Switch to an input stage with less gain when a sweep's peak amplitude is at or above this
+    /// level.
+    pub upper_peak_dbm: f32,
+    /// Switch to an input stage with more gain when a sweep's peak amplitude is below this level.
+    pub lower_peak_dbm: f32,
+    /// The number of sweeps to ignore after switching while the new input stage settles.
+    pub settle_sweeps: u32,
+}
+
+impl Default for AutoRangingPolicy {
+    fn default() -> Self {
+        AutoRangingPolicy {
+            upper_peak_dbm: -30.0,
+            lower_peak_dbm: -80.0,
+            settle_sweeps: 2,
+        }
+    }
+}
+
+/// An input stage switch made by automatic input ranging.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct InputStageSwitch {
+    pub from: InputStage,
+    pub to: InputStage,
+    /// The peak amplitude of the sweep that caused the switch.
+    pub peak_dbm: f32,
+}
+
+/// Statistics about automatic input ranging since it was enabled.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct AutoRangingStats {
+    pub sweeps_observed: u64,
+    pub switches: u64,
+    pub last_switch: Option<InputStageSwitch>,
+}
+
+/// The input stages automatic input ranging switches between, ordered from the most gain to the
+/// least.
+const INPUT_STAGES: [InputStage; 3] = [
+    InputStage::Lna25dB,
+    InputStage::Direct,
+    InputStage::Attenuator30dB,
+];
+
+/// Decides when to switch input stages based on the peak amplitude of each sweep.
+#[derive(Debug, Clone)]
+pub(crate) struct AutoRanger {
+    policy: AutoRangingPolicy,
+    sweeps_to_settle: u32,
+    pub(crate) stats: AutoRangingStats,
+}
+
+impl AutoRanger {
+    pub(crate) fn new(policy: AutoRangingPolicy) -> Self {
+        AutoRanger {
+            policy,
+            sweeps_to_settle: 0,
+            stats: AutoRangingStats::default(),
+        }
+    }
+
+    /// Returns the switch to make, if any, after a sweep was measured with the given input stage.
+    pub(crate) fn observe(
+        &mut self,
+        sweep: &Sweep,
+        input_stage: InputStage,
+    ) -> Option<InputStageSwitch> {
+        self.stats.sweeps_observed += 1;
+        if self.sweeps_to_settle > 0 {
+            self.sweeps_to_settle -= 1;
+            return None;
+        }
+
+        let peak_dbm = sweep
+            .amplitudes_dbm()
+            .iter()
+            .copied()
+            .filter(|amp_dbm| !amp_dbm.is_nan())
+            .reduce(f32::max)?;
+
+        let position = match input_stage {
+            InputStage::Lna25dB | InputStage::Lna12dB => 0,
+            InputStage::Direct => 1,
+            InputStage::Attenuator30dB | InputStage::Attenuator60dB => 2,
+        };
+        let to = if peak_dbm >= self.policy.upper_peak_dbm {
+            INPUT_STAGES.get(position + 1)
+        } else if peak_dbm < self.policy.lower_peak_dbm {
+            position
+                .checked_sub(1)
+                .map(|position| &INPUT_STAGES[position])
+        } else {
+            None
+        };
+
+        let switch = InputStageSwitch {
+            from: input_stage,
+            to: *to?,
+            peak_dbm,
+        };
+        self.sweeps_to_settle = self.policy.settle_sweeps;
+        self.stats.switches += 1;
+        self.stats.last_switch = Some(switch);
+        Some(switch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_support::sweep;
+
+    fn sweep_with_peak(peak_dbm: f32) -> Sweep {
+        sweep(&[-110.0, peak_dbm, -105.0])
+    }
+
+    #[test]
+    fn switch_to_less_gain_when_peak_is_high() {
+        let mut ranger = AutoRanger::new(AutoRangingPolicy {
+            settle_sweeps: 0,
+            ..AutoRangingPolicy::default()
+        });
+        let switch = ranger
+            .observe(&sweep_with_peak(-20.0), InputStage::Lna25dB)
+            .unwrap();
+        assert_eq!(switch.to, InputStage::Direct);
+        let switch = ranger
+            .observe(&sweep_with_peak(-20.0), InputStage::Direct)
+            .unwrap();
+        assert_eq!(switch.to, InputStage::Attenuator30dB);
+        assert_eq!(
+            ranger.observe(&sweep_with_peak(-20.0), InputStage::Attenuator30dB),
+            None
+        );
+        assert_eq!(ranger.stats.switches, 2);
+        assert_eq!(ranger.stats.sweeps_observed, 3);
+    }
+
+    #[test]
+    fn stay_on_input_stage_between_thresholds() {
+        let mut ranger = AutoRanger::new(AutoRangingPolicy::default());
+        assert_eq!(
+            ranger.observe(&sweep_with_peak(-50.0), InputStage::Direct),
+            None
+        );
+        let switch = ranger
+            .observe(&sweep_with_peak(-90.0), InputStage::Direct)
+            .unwrap();
+        assert_eq!(switch.to, InputStage::Lna25dB);
+        assert_eq!(ranger.stats.last_switch, Some(switch));
+    }
+
+    #[test]
+    fn ignore_sweeps_while_settling() {
+        let mut ranger = AutoRanger::new(AutoRangingPolicy::default());
+        assert!(ranger
+            .observe(&sweep_with_peak(-20.0), InputStage::Direct)
+            .is_some());
+        assert_eq!(
+            ranger.observe(&sweep_with_peak(-20.0), InputStage::Attenuator30dB),
+            None
+        );
+        assert_eq!(
+            ranger.observe(&sweep_with_peak(-20.0), InputStage::Attenuator30dB),
+            None
+        );
+        assert_eq!(
+            ranger
+                .observe(&sweep_with_peak(-90.0), InputStage::Attenuator30dB)
+                .map(|switch| switch.to),
+            Some(InputStage::Direct)
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use std::sync::OnceLock;
+
+use super::{Config, Sweep};
+use crate::Frequency;
+
+/// A set of channels with evenly sized bandwidths, numbered starting at 1.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ChannelPlan<'a> {
+    /// The center frequency of each channel, where the first entry is channel 1.
+    pub center_frequencies: &'a [Frequency],
+    pub channel_bandwidth: Frequency,
+}
+
+impl<'a> ChannelPlan<'a> {
+    pub fn new(center_frequencies: &'a [Frequency], channel_bandwidth: Frequency) -> Self {
+        ChannelPlan {
+            center_frequencies,
+            channel_bandwidth,
+        }
+    }
+
+    /// Returns the center frequency of the given channel, or `None` if the channel isn't part of
+    /// the plan.
+    pub fn center_frequency(&self, channel: u32) -> Option<Frequency> {
+        let index = usize::try_from(channel.checked_sub(1)?).ok()?;
+        self.center_frequencies.get(index).copied()
+    }
+
+    /// The number of channels in the plan.
+    pub fn channel_count(&self) -> u32 {
+        u32::try_from(self.center_frequencies.len()).unwrap_or(u32::MAX)
+    }
+
+    /// Returns each channel's number along with the fraction of the sweep's points within the
+    /// channel whose amplitude is above `threshold_dbm`.
+    ///
+    /// Channels that don't contain any of the sweep's points have an occupancy of 0.
+    pub(crate) fn occupancy(
+        &self,
+        threshold_dbm: f32,
+        config: &Config,
+        sweep: &Sweep,
+    ) -> Vec<(u32, f32)> {
+        let start_hz = config.start_freq.as_hz();
+        let step_hz = config.step_size.as_hz();
+        let half_bandwidth_hz = self.channel_bandwidth.as_hz() / 2;
+        (1..)
+            .zip(self.center_frequencies)
+            .map(|(channel, center)| {
+                let channel_range_hz = center.as_hz().saturating_sub(half_bandwidth_hz)
+                    ..=center.as_hz() + half_bandwidth_hz;
+                let (occupied, total) = sweep
+                    .iter_with_index()
+                    .filter(|(index, _)| {
+                        channel_range_hz.contains(&(start_hz + *index as u64 * step_hz))
+                    })
+                    .fold((0u32, 0u32), |(occupied, total), (_, amp_dbm)| {
+                        (occupied + u32::from(amp_dbm > threshold_dbm), total + 1)
+                    });
+                let occupancy = if total > 0 {
+                    occupied as f32 / total as f32
+                } else {
+                    0.
+                };
+                (channel, occupancy)
+            })
+            .collect()
+    }
+}
+
+impl ChannelPlan<'static> {
+    /// The 16 PMR446 channels between 446.00625 MHz and 446.19375 MHz with 12.5 kHz spacing.
+    pub fn pmr446() -> Self {
+        static CENTER_FREQUENCIES: OnceLock<Vec<Frequency>> = OnceLock::new();
+        let center_frequencies = CENTER_FREQUENCIES
+            .get_or_init(|| evenly_spaced(Frequency::from_hz(446_006_250), 12_500, 16));
+        ChannelPlan::new(center_frequencies, Frequency::from_hz(12_500))
+    }
+
+    /// The 69 LPD433 channels between 433.075 MHz and 434.775 MHz with 25 kHz spacing.
+    pub fn lpd433() -> Self {
+        static CENTER_FREQUENCIES: OnceLock<Vec<Frequency>> = OnceLock::new();
+        let center_frequencies = CENTER_FREQUENCIES
+            .get_or_init(|| evenly_spaced(Frequency::from_khz(433_075), 25_000, 69));
+        ChannelPlan::new(center_frequencies, Frequency::from_khz(25))
+    }
+
+    /// The 14 2.4 GHz Wi-Fi channels with 20 MHz bandwidths.
+    ///
+    /// Channels 1 through 13 are spaced 5 MHz apart starting at 2412 MHz and channel 14 is at
+    /// 2484 MHz.
+    pub fn wifi_2_4_ghz() -> Self {
+        static CENTER_FREQUENCIES: OnceLock<Vec<Frequency>> = OnceLock::new();
+        let center_frequencies = CENTER_FREQUENCIES.get_or_init(|| {
+            let mut center_frequencies = evenly_spaced(Frequency::from_mhz(2412), 5_000_000, 13);
+            center_frequencies.push(Frequency::from_mhz(2484));
+            center_frequencies
+        });
+        ChannelPlan::new(center_frequencies, Frequency::from_mhz(20))
+    }
+}
+
+fn evenly_spaced(first: Frequency, spacing_hz: u64, count: u64) -> Vec<Frequency> {
+    (0..count)
+        .map(|i| Frequency::from_hz(first.as_hz() + i * spacing_hz))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channels_are_numbered_from_one() {
+        let plan = ChannelPlan::pmr446();
+        assert_eq!(plan.channel_count(), 16);
+        assert_eq!(plan.center_frequency(0), None);
+        assert_eq!(
+            plan.center_frequency(1),
+            Some(Frequency::from_hz(446_006_250))
+        );
+        assert_eq!(
+            plan.center_frequency(16),
+            Some(Frequency::from_hz(446_193_750))
+        );
+        assert_eq!(plan.center_frequency(17), None);
+    }
+
+    #[test]
+    fn wifi_channel_14_is_not_evenly_spaced() {
+        let plan = ChannelPlan::wifi_2_4_ghz();
+        assert_eq!(plan.center_frequency(13), Some(Frequency::from_mhz(2472)));
+        assert_eq!(plan.center_frequency(14), Some(Frequency::from_mhz(2484)));
+    }
+
+    #[test]
+    fn occupancy_counts_points_above_threshold_in_each_channel() {
+        // Points every 10 kHz from 446 MHz to 446.2 MHz
+        let config = Config::try_from(
+            b"#C2-F:0446000,0010000,-030,-118,0021,0,000,0240000,0960000,0100000,00200,0000,000"
+                .as_slice(),
+        )
+        .unwrap();
+        let mut amplitudes_dbm = vec![-100.; 21];
+        amplitudes_dbm[0] = -40.;
+        amplitudes_dbm[1] = -40.;
+        let sweep = Sweep::from_config_and_amplitudes(&config, amplitudes_dbm).unwrap();
+        let center_frequencies = [Frequency::from_hz(446_005_000), Frequency::from_mhz(500)];
+        let plan = ChannelPlan::new(&center_frequencies, Frequency::from_khz(10));
+
+        assert_eq!(plan.occupancy(-50., &config, &sweep), [(1, 1.), (2, 0.)]);
+        assert_eq!(plan.occupancy(-30., &config, &sweep), [(1, 0.), (2, 0.)]);
+    }
+}
@@ -2,9 +2,11 @@ use std::borrow::Cow;
 
 use super::{CalcMode, DspMode, InputStage, WifiBand};
 use crate::common::Frequency;
+use crate::rf_explorer::command_fields;
 
+/// A command that can be sent to a spectrum analyzer.
 #[derive(Debug, Copy, Clone, PartialEq)]
-pub(crate) enum Command {
+pub enum Command {
     SetConfig {
         start: Frequency,
         stop: Frequency,
@@ -28,6 +30,68 @@ pub(crate) enum Command {
     SetSweepPointsLarge(u16),
 }
 
+impl Command {
+    /// Returns the bytes that are sent to the RF Explorer for this command.
+    pub fn encode(&self) -> Vec<u8> {
+        Cow::from(*self).into_owned()
+    }
+
+    /// Interprets bytes sent to an RF Explorer as a command.
+    ///
+    /// `None` is returned if the bytes aren't a valid spectrum analyzer command.
+    pub fn decode(bytes: &[u8]) -> Option<Command> {
+        // Every command starts with '#' followed by the length of the entire command
+        if bytes.first() != Some(&b'#') || usize::from(*bytes.get(1)?) != bytes.len() {
+            return None;
+        }
+
+        let command = match &bytes[2..] {
+            [b'C', b'M', 0] => Command::SwitchModuleMain,
+            [b'C', b'M', 1] => Command::SwitchModuleExp,
+            [b'C', b'W', 0] => Command::StopWifiAnalyzer,
+            [b'C', b'W', wifi_band] => {
+                Command::StartWifiAnalyzer(WifiBand::try_from(*wifi_band).ok()?)
+            }
+            [b'C', b'+', calc_mode] => Command::SetCalcMode(CalcMode::try_from(*calc_mode).ok()?),
+            [b'k', high, low] => Command::TrackingStep(u16::from_be_bytes([*high, *low])),
+            [b'C', b'p', dsp_mode] => Command::SetDsp(DspMode::try_from(*dsp_mode).ok()?),
+            [b'C', b'O', offset_db] => Command::SetOffsetDB(*offset_db as i8),
+            [b'a', input_stage] => Command::SetInputStage(InputStage::try_from(*input_stage).ok()?),
+            [b'C', b'J', sweep_points] => {
+                Command::SetSweepPointsExt((u16::from(*sweep_points) + 1) * 16)
+            }
+            [b'C', b'j', high, low] => {
+                Command::SetSweepPointsLarge(u16::from_be_bytes([*high, *low]))
+            }
+            _ => {
+                if let Some(fields) = command_fields(bytes, b"C2-F:") {
+                    let [start_khz, stop_khz, max_amp_dbm, min_amp_dbm] = fields[..] else {
+                        return None;
+                    };
+                    Command::SetConfig {
+                        start: Frequency::from_khz(start_khz.parse().ok()?),
+                        stop: Frequency::from_khz(stop_khz.parse().ok()?),
+                        min_amp_dbm: min_amp_dbm.parse().ok()?,
+                        max_amp_dbm: max_amp_dbm.parse().ok()?,
+                    }
+                } else if let Some(fields) = command_fields(bytes, b"C3-K:") {
+                    let [start_khz, step_khz] = fields[..] else {
+                        return None;
+                    };
+                    Command::StartTracking {
+                        start: Frequency::from_khz(start_khz.parse().ok()?),
+                        step: Frequency::from_khz(step_khz.parse().ok()?),
+                    }
+                } else {
+                    return None;
+                }
+            }
+        };
+
+        Some(command)
+    }
+}
+
 impl From<Command> for Cow<'static, [u8]> {
     fn from(command: Command) -> Cow<'static, [u8]> {
         match command {
@@ -134,4 +198,40 @@ mod tests {
         assert_correct_size!(Command::SetSweepPointsExt(1024));
         assert_correct_size!(Command::SetSweepPointsLarge(8192));
     }
+
+    #[test]
+    fn decode_encoded_commands() {
+        let commands = [
+            Command::SetConfig {
+                start: Frequency::from_khz(90_000),
+                stop: Frequency::from_khz(110_000),
+                min_amp_dbm: -120,
+                max_amp_dbm: -40,
+            },
+            Command::SwitchModuleMain,
+            Command::SwitchModuleExp,
+            Command::StartTracking {
+                start: Frequency::from_khz(100_000),
+                step: Frequency::from_khz(1_000),
+            },
+            Command::StartWifiAnalyzer(WifiBand::FiveGhz),
+            Command::StopWifiAnalyzer,
+            Command::SetCalcMode(CalcMode::MaxHold),
+            Command::TrackingStep(300),
+            Command::SetDsp(DspMode::Fast),
+            Command::SetOffsetDB(-20),
+            Command::SetInputStage(InputStage::Lna25dB),
+            Command::SetSweepPointsExt(1024),
+            Command::SetSweepPointsLarge(8192),
+        ];
+
+        for command in commands {
+            assert_eq!(Command::decode(&command.encode()), Some(command));
+        }
+    }
+
+    #[test]
+    fn reject_command_with_incorrect_size() {
+        assert_eq!(Command::decode(&[b'#', 6, b'C', b'M', 0]), None);
+    }
 }
@@ -30,6 +30,20 @@ pub enum Mode {
     Unknown = 255,
 }
 
+impl Mode {
+    /// The modes a spectrum analyzer can be switched to from this mode with
+    /// `SpectrumAnalyzer::set_mode`.
+    pub(crate) fn settable_modes(&self) -> &'static [Mode] {
+        match self {
+            Mode::SpectrumAnalyzer => &[Mode::WifiAnalyzer],
+            Mode::WifiAnalyzer | Mode::AnalyzerTracking | Mode::RfSniffer => {
+                &[Mode::SpectrumAnalyzer]
+            }
+            _ => &[],
+        }
+    }
+}
+
 impl Display for Mode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mode = match self {
@@ -49,6 +63,7 @@ impl Display for Mode {
 }
 
 #[derive(Debug, Copy, Clone, TryFromPrimitive, IntoPrimitive, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum CalcMode {
     #[default]
@@ -61,6 +76,17 @@ pub enum CalcMode {
     Unknown = 255,
 }
 
+impl CalcMode {
+    /// Returns `true` if the RF Explorer combines several measurements into each sweep it sends in
+    /// this mode, rather than sending each measurement as it's made.
+    pub fn is_processed(&self) -> bool {
+        matches!(
+            self,
+            CalcMode::Max | CalcMode::Avg | CalcMode::MaxHold | CalcMode::MaxHistorical
+        )
+    }
+}
+
 impl Display for CalcMode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let calc_mode = match self {
@@ -77,7 +103,7 @@ impl Display for CalcMode {
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
-pub(crate) struct Config {
+pub struct Config {
     pub start_freq: Frequency,
     pub step_size: Frequency,
     pub stop_freq: Frequency,
@@ -100,6 +126,19 @@ pub(crate) struct Config {
 impl Config {
     pub(crate) const PREFIX: &'static [u8] = b"#C2-F:";
 
+    /// The frequency spacing (in Hz) between adjacent points of a sweep.
+    ///
+    /// This is the span divided by one less than the number of sweep points, which is distinct from
+    /// the RBW since the RBW depends on the filter rather than the point spacing. Configs with fewer
+    /// than two sweep points return the whole span.
+    pub fn frequency_resolution_hz(&self) -> u64 {
+        let span_hz = self
+            .stop_freq
+            .as_hz()
+            .saturating_sub(self.start_freq.as_hz());
+        span_hz / u64::from(self.sweep_len.saturating_sub(1).max(1))
+    }
+
     #[tracing::instrument(skip(self), ret, fields(self.start_freq = ?self.start_freq, self.stop_freq = ?self.stop_freq, self.min_amp_dbm = ?self.min_amp_dbm, self.max_amp_dbm = ?self.max_amp_dbm))]
     pub(crate) fn contains_start_stop_amp_range(
         &self,
@@ -113,12 +152,22 @@ impl Config {
             && self.min_amp_dbm == min_amp_dbm
             && self.max_amp_dbm == max_amp_dbm
     }
+
+    /// Returns `true` if both configs report the same settings, regardless of when they were
+    /// received.
+    pub(crate) fn eq_ignoring_timestamp(&self, other: &Config) -> bool {
+        *self
+            == Config {
+                timestamp: self.timestamp,
+                ..other.clone()
+            }
+    }
 }
 
 impl<'a> TryFrom<&'a [u8]> for Config {
     type Error = MessageParseError<'a>;
 
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
         // Parse the prefix of the message
         let (bytes, _) = tag(Config::PREFIX)(bytes)?;
 
@@ -225,6 +274,25 @@ impl<'a> TryFrom<&'a [u8]> for Config {
 mod tests {
     use super::*;
 
+    #[test]
+    fn frequency_resolution_is_point_spacing() {
+        let config = Config {
+            start_freq: Frequency::from_mhz(100),
+            stop_freq: Frequency::from_mhz(200),
+            sweep_len: 101,
+            ..Config::default()
+        };
+        assert_eq!(config.frequency_resolution_hz(), 1_000_000);
+        assert_eq!(
+            Config {
+                sweep_len: 1,
+                ..config
+            }
+            .frequency_resolution_hz(),
+            100_000_000
+        );
+    }
+
     #[test]
     fn parse_6g_combo_config() {
         let bytes =
@@ -290,4 +358,62 @@ mod tests {
             b"#C2-F:XX96000,0090072,-010,-120,0112,0,000,0000050,0960000,0959950,00110,0000,000";
         assert!(Config::try_from(bytes.as_ref()).is_err());
     }
+
+    #[test]
+    fn compare_configs_ignoring_timestamp() {
+        let bytes =
+            b"#C2-F:5249000,0196428,-030,-118,0112,0,000,4850000,6100000,0600000,00200,0000,000";
+        let config = Config::try_from(bytes.as_ref()).unwrap();
+        let later_config = Config {
+            timestamp: config.timestamp + chrono::Duration::seconds(1),
+            ..config.clone()
+        };
+        assert!(config.eq_ignoring_timestamp(&later_config));
+
+        let changed_config = Config {
+            sweep_len: 224,
+            ..later_config
+        };
+        assert!(!config.eq_ignoring_timestamp(&changed_config));
+    }
+
+    #[test]
+    fn only_analyzer_modes_are_settable() {
+        assert_eq!(
+            Mode::SpectrumAnalyzer.settable_modes(),
+            [Mode::WifiAnalyzer]
+        );
+        assert_eq!(
+            Mode::AnalyzerTracking.settable_modes(),
+            [Mode::SpectrumAnalyzer]
+        );
+        assert!(Mode::CwTransmitter.settable_modes().is_empty());
+    }
+
+    #[test]
+    fn report_offset_of_invalid_config_field() {
+        let bytes =
+            b"#C2-F:5249000,01x6428,-030,-118,0112,0,000,4850000,6100000,0600000,00200,0000,000";
+        let error = Config::try_from(bytes.as_ref()).unwrap_err();
+        assert!(matches!(
+            error,
+            MessageParseError::Invalid {
+                expected: "a number",
+                ..
+            }
+        ));
+        assert_eq!(error.offset(bytes), Some(14));
+
+        let bytes =
+            b"#C2-F:5249000;0196428,-030,-118,0112,0,000,4850000,6100000,0600000,00200,0000,000";
+        let error = Config::try_from(bytes.as_ref()).unwrap_err();
+        assert!(matches!(
+            error,
+            MessageParseError::Invalid {
+                expected: "a message prefix or separator",
+                ..
+            }
+        ));
+        assert_eq!(error.offset(bytes), Some(13));
+    }
 }
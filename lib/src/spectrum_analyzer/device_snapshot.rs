@@ -0,0 +1,21 @@
+use super::{CalcMode, DspMode, InputStage, RadioModule};
+use crate::Frequency;
+
+/// The settings of a spectrum analyzer at a point in time.
+///
+/// A `DeviceSnapshot` is created with `SpectrumAnalyzer::device_snapshot()` and can be applied to a
+/// spectrum analyzer with `SpectrumAnalyzer::restore()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceSnapshot {
+    pub radio_module: RadioModule,
+    pub start: Frequency,
+    pub stop: Frequency,
+    pub min_amp_dbm: i16,
+    pub max_amp_dbm: i16,
+    pub sweep_len: u16,
+    pub calc_mode: Option<CalcMode>,
+    pub dsp_mode: Option<DspMode>,
+    pub input_stage: Option<InputStage>,
+    pub amp_offset_db: Option<i8>,
+}
@@ -7,6 +7,7 @@ use crate::common::MessageParseError;
 use crate::rf_explorer::parsers::*;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, TryFromPrimitive, IntoPrimitive, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum DspMode {
     #[default]
@@ -0,0 +1,87 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use super::Mode;
+
+/// The result of a spectrum analyzer health check.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Health {
+    /// How long ago the most recent message was received, or `None` if no message has been
+    /// received.
+    pub last_message_age: Option<Duration>,
+    /// The number of sweeps received per second, or `None` if too few sweeps have been received to
+    /// measure it.
+    pub sweep_rate: Option<f64>,
+    /// The mode reported in the most recent `Config`.
+    pub mode: Option<Mode>,
+    /// Whether the RF Explorer has sent a message recently or answered a request during the check.
+    pub connected: bool,
+    /// Whether a `Config` has been received and its settings are consistent.
+    pub config_valid: bool,
+}
+
+impl Health {
+    /// Returns `true` if the RF Explorer is connected, has a valid `Config`, and is sweeping at
+    /// least `min_sweep_rate` times per second.
+    pub fn is_healthy(&self, min_sweep_rate: f64) -> bool {
+        self.connected
+            && self.config_valid
+            && self
+                .sweep_rate
+                .is_some_and(|sweep_rate| sweep_rate >= min_sweep_rate)
+    }
+}
+
+/// Measures the rate at which sweeps are received from the arrival times of the most recent ones.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SweepRate {
+    arrivals: VecDeque<Instant>,
+}
+
+impl SweepRate {
+    const MAX_ARRIVALS: usize = 16;
+
+    pub(crate) fn record(&mut self, received_at: Instant) {
+        if self.arrivals.len() == Self::MAX_ARRIVALS {
+            self.arrivals.pop_front();
+        }
+        self.arrivals.push_back(received_at);
+    }
+
+    /// The number of sweeps received per second up until `now`.
+    ///
+    /// Measuring up until `now` rather than the most recent sweep makes the rate fall when sweeps
+    /// stop arriving.
+    pub(crate) fn per_second(&self, now: Instant) -> Option<f64> {
+        let oldest = *self.arrivals.front()?;
+        let elapsed = now.checked_duration_since(oldest)?.as_secs_f64();
+        (self.arrivals.len() > 1 && elapsed > 0.)
+            .then(|| (self.arrivals.len() - 1) as f64 / elapsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sweep_rate_falls_when_sweeps_stop() {
+        let start = Instant::now();
+        let mut sweep_rate = SweepRate::default();
+        assert_eq!(sweep_rate.per_second(start), None);
+
+        for i in 0..20 {
+            sweep_rate.record(start + Duration::from_millis(100 * i));
+        }
+        let last = start + Duration::from_millis(1900);
+        assert!((sweep_rate.per_second(last).unwrap() - 10.).abs() < 1e-9);
+        assert!(
+            sweep_rate
+                .per_second(last + Duration::from_secs(3))
+                .unwrap()
+                < 5.
+        );
+    }
+}
@@ -2,8 +2,9 @@ use super::{Config, DspMode, InputStage, Model, Sweep, TrackingStatus};
 use crate::common::MessageParseError;
 use crate::rf_explorer::{ScreenData, SerialNumber, SetupInfo};
 
+/// A message received from a spectrum analyzer.
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) enum Message {
+pub enum Message {
     Config(Config),
     DspMode(DspMode),
     InputStage(InputStage),
@@ -14,6 +15,26 @@ pub(crate) enum Message {
     TrackingStatus(TrackingStatus),
 }
 
+impl Message {
+    /// Returns the prefix that identifies this type of message, e.g. `b"#C2-F:"` for a `Config`.
+    ///
+    /// This is useful for identifying a message's type at runtime, e.g. when logging. Sweeps can be
+    /// received in one of three formats, but the format isn't kept after parsing, so the standard
+    /// sweep prefix is returned for all sweeps.
+    pub fn prefix(&self) -> &'static [u8] {
+        match self {
+            Message::Config(_) => Config::PREFIX,
+            Message::DspMode(_) => DspMode::PREFIX,
+            Message::InputStage(_) => InputStage::PREFIX,
+            Message::ScreenData(_) => ScreenData::PREFIX,
+            Message::SerialNumber(_) => SerialNumber::PREFIX,
+            Message::SetupInfo(_) => SetupInfo::<Model>::PREFIX,
+            Message::Sweep(_) => Sweep::STANDARD_PREFIX,
+            Message::TrackingStatus(_) => TrackingStatus::PREFIX,
+        }
+    }
+}
+
 impl<'a> TryFrom<&'a [u8]> for Message {
     type Error = MessageParseError<'a>;
 
@@ -31,10 +52,7 @@ impl<'a> TryFrom<&'a [u8]> for Message {
             Ok(Message::SerialNumber(SerialNumber::try_from(bytes)?))
         } else if bytes.starts_with(SetupInfo::<Model>::PREFIX) {
             Ok(Message::SetupInfo(SetupInfo::<Model>::try_from(bytes)?))
-        } else if bytes.starts_with(Sweep::STANDARD_PREFIX)
-            || bytes.starts_with(Sweep::EXT_PREFIX)
-            || bytes.starts_with(Sweep::LARGE_PREFIX)
-        {
+        } else if Sweep::has_prefix(bytes) {
             Ok(Message::Sweep(Sweep::try_from(bytes)?))
         } else if bytes.starts_with(TrackingStatus::PREFIX) {
             Ok(Message::TrackingStatus(TrackingStatus::try_from(bytes)?))
@@ -43,3 +61,46 @@ impl<'a> TryFrom<&'a [u8]> for Message {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_matches_parsed_message() {
+        let mut screen_data = b"$D".to_vec();
+        screen_data.resize(2 + 8 * 128, 0);
+        let messages: [&[u8]; 8] = [
+            b"#C2-F:5249000,0196428,-030,-118,0112,1,000,4850000,6100000,0600000,00200,0000,000\r\n",
+            b"DSP:0\r\n",
+            b"#a1\r\n",
+            &screen_data,
+            b"#Sn0SME38SI2X7NGR48\r\n",
+            b"#C2-M:006,005,01.12B26\r\n",
+            b"$S\x03\x10\x20\x30\r\n",
+            b"#K\x01\r\n",
+        ];
+
+        // Listing every variant makes this test fail to compile when one is added
+        let variant_index = |message: &Message| match message {
+            Message::Config(_) => 0,
+            Message::DspMode(_) => 1,
+            Message::InputStage(_) => 2,
+            Message::ScreenData(_) => 3,
+            Message::SerialNumber(_) => 4,
+            Message::SetupInfo(_) => 5,
+            Message::Sweep(_) => 6,
+            Message::TrackingStatus(_) => 7,
+        };
+        for (index, bytes) in messages.into_iter().enumerate() {
+            let message = Message::try_from(bytes).unwrap();
+            assert_eq!(variant_index(&message), index);
+            assert!(
+                bytes.starts_with(message.prefix()),
+                "{} doesn't start with {}",
+                bytes.escape_ascii(),
+                message.prefix().escape_ascii()
+            );
+        }
+    }
+}
@@ -0,0 +1,17 @@
+/// Tracks which messages a caller of the `try_next_*` methods has already seen.
+///
+/// Each caller should use its own cursor. A new cursor hasn't seen any messages, so the first call
+/// to a `try_next_*` method returns the most recent message if one has been received.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct MessageCursor {
+    pub(crate) config_seq: u64,
+    pub(crate) sweep_seq: u64,
+    pub(crate) screen_data_seq: u64,
+}
+
+impl MessageCursor {
+    /// Creates a cursor that hasn't seen any messages.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
@@ -0,0 +1,71 @@
+use std::{collections::VecDeque, time::Instant};
+
+use super::Message;
+
+/// A record of the messages received from a spectrum analyzer.
+///
+/// Once the log holds `capacity` messages, the oldest message is discarded for each new one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MessageLog {
+    capacity: usize,
+    events: VecDeque<(Message, Instant)>,
+}
+
+impl MessageLog {
+    /// Creates an empty log that holds up to `capacity` messages.
+    pub fn new(capacity: usize) -> Self {
+        MessageLog {
+            capacity,
+            events: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Records a message along with the time it was received.
+    pub fn push(&mut self, message: Message, received_at: Instant) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+
+        self.events.push_back((message, received_at));
+    }
+
+    /// Returns an iterator over the recorded messages from oldest to newest.
+    pub fn events(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &(Message, Instant)> + ExactSizeIterator + '_ {
+        self.events.iter()
+    }
+
+    /// Returns the maximum number of messages the log holds.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spectrum_analyzer::DspMode;
+
+    #[test]
+    fn discard_oldest_message_when_full() {
+        let mut log = MessageLog::new(2);
+        let now = Instant::now();
+        log.push(Message::DspMode(DspMode::Auto), now);
+        log.push(Message::DspMode(DspMode::Filter), now);
+        log.push(Message::DspMode(DspMode::Fast), now);
+
+        let dsp_modes: Vec<_> = log.events().map(|(message, _)| message).collect();
+        assert_eq!(
+            dsp_modes,
+            [
+                &Message::DspMode(DspMode::Filter),
+                &Message::DspMode(DspMode::Fast)
+            ]
+        );
+    }
+}
@@ -1,24 +1,53 @@
+mod amplitude_distribution;
+mod analyzer_snapshot;
+mod auto_input_ranging;
+mod channel_plan;
 mod command;
 mod config;
+mod device_snapshot;
 mod dsp_mode;
+mod health;
 mod input_stage;
 mod message;
+mod message_cursor;
+mod message_log;
 mod model;
 mod parsers;
+mod radio_module;
 mod rf_explorer;
 mod setup_info;
 mod sweep;
+mod sweep_binary;
+mod sweep_comparison;
+mod sweep_gap_detector;
+mod sweep_history;
+mod sweep_len_mismatch_policy;
+mod tracking_iterator;
 mod tracking_status;
 mod wifi_band;
+mod wifi_region;
 
-pub(crate) use command::Command;
-pub(crate) use config::Config;
-pub use config::{CalcMode, Mode};
+pub use amplitude_distribution::{AmplitudeDistribution, DistributionPoint};
+pub use analyzer_snapshot::AnalyzerSnapshot;
+pub use auto_input_ranging::{AutoRangingPolicy, AutoRangingStats, InputStageSwitch};
+pub use channel_plan::ChannelPlan;
+pub use command::Command;
+pub use config::{CalcMode, Config, Mode};
+pub use device_snapshot::DeviceSnapshot;
 pub use dsp_mode::DspMode;
+pub use health::Health;
 pub use input_stage::InputStage;
-pub(crate) use message::Message;
+pub use message::Message;
+pub use message_cursor::MessageCursor;
+pub use message_log::MessageLog;
 pub use model::Model;
+pub use radio_module::RadioModule;
 pub use rf_explorer::SpectrumAnalyzer;
-pub(crate) use sweep::Sweep;
+pub use sweep::Sweep;
+pub use sweep_comparison::{BinDelta, SweepComparison};
+pub use sweep_gap_detector::{SweepGap, SweepGapDetector};
+pub use sweep_len_mismatch_policy::SweepLenMismatchPolicy;
+pub use tracking_iterator::TrackingIterator;
 pub use tracking_status::TrackingStatus;
 pub use wifi_band::WifiBand;
+pub use wifi_region::WifiRegion;
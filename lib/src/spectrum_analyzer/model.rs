@@ -1,7 +1,8 @@
-use std::fmt::Display;
+use std::{fmt::Display, ops::RangeInclusive};
 
 use num_enum::TryFromPrimitive;
 
+use super::WifiBand;
 use crate::Frequency;
 
 #[derive(Debug, Copy, Clone, TryFromPrimitive, Eq, PartialEq, Default)]
@@ -56,6 +57,7 @@ impl Model {
         )
     }
 
+    /// The minimum frequency the model can measure.
     pub fn min_freq(&self) -> Frequency {
         match self {
             Model::Rfe433M => 430_000_000,
@@ -76,6 +78,7 @@ impl Model {
         .into()
     }
 
+    /// The maximum frequency the model can measure.
     pub fn max_freq(&self) -> Frequency {
         match self {
             Model::Rfe433M => 440_000_000,
@@ -94,6 +97,7 @@ impl Model {
         .into()
     }
 
+    /// The minimum span the model supports.
     pub fn min_span(&self) -> Frequency {
         match self {
             Model::Rfe433M
@@ -117,6 +121,7 @@ impl Model {
         .into()
     }
 
+    /// The maximum span the model supports.
     pub fn max_span(&self) -> Frequency {
         match self {
             Model::Rfe433M | Model::Rfe868M | Model::Rfe915M => 10_000_000,
@@ -132,6 +137,34 @@ impl Model {
         }
         .into()
     }
+
+    /// The range of frequencies the model can measure.
+    pub fn frequency_range(&self) -> RangeInclusive<Frequency> {
+        self.min_freq()..=self.max_freq()
+    }
+
+    /// Returns `true` if the model's Wi-Fi analyzer can measure the given band.
+    pub fn supports_wifi_band(&self, wifi_band: WifiBand) -> bool {
+        let band = wifi_band.frequency_range();
+        self.has_wifi_analyzer()
+            && self.frequency_range().contains(band.start())
+            && self.frequency_range().contains(band.end())
+    }
+
+    /// The range of spans the model supports.
+    pub fn span_range(&self) -> RangeInclusive<Frequency> {
+        self.min_span()..=self.max_span()
+    }
+
+    /// The approximate resolution bandwidth (RBW) of a sweep with the given span and number of
+    /// points, which is the span divided by the number of points.
+    ///
+    /// The RF Explorer chooses the actual RBW itself, and newer firmware reports it in
+    /// `Config::rbw`, which should be preferred when it's available. The approximation is the same
+    /// for every model.
+    pub fn approx_rbw_hz(span: Frequency, sweep_points: u16) -> Frequency {
+        span / u64::from(sweep_points.max(1))
+    }
 }
 
 impl Display for Model {
@@ -160,3 +193,28 @@ impl Display for Model {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_wifi_band_support() {
+        assert!(Model::Rfe24G.supports_wifi_band(WifiBand::TwoPointFourGhz));
+        assert!(!Model::Rfe24G.supports_wifi_band(WifiBand::FiveGhz));
+        assert!(Model::Rfe6G.supports_wifi_band(WifiBand::FiveGhz));
+        assert!(!Model::RfeWSub1G.supports_wifi_band(WifiBand::TwoPointFourGhz));
+    }
+
+    #[test]
+    fn approximate_rbw_from_span_and_points() {
+        assert_eq!(
+            Model::approx_rbw_hz(Frequency::from_mhz(112), 112),
+            Frequency::from_mhz(1)
+        );
+        assert_eq!(
+            Model::approx_rbw_hz(Frequency::from_mhz(10), 0),
+            Frequency::from_mhz(10)
+        );
+    }
+}
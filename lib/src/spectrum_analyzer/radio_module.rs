@@ -0,0 +1,19 @@
+use std::fmt::Display;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RadioModule {
+    #[default]
+    Main,
+    Expansion,
+}
+
+impl Display for RadioModule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let radio_module = match self {
+            RadioModule::Main => "Main",
+            RadioModule::Expansion => "Expansion",
+        };
+        write!(f, "{radio_module}")
+    }
+}
@@ -2,20 +2,30 @@ use std::{
     fmt::Debug,
     io,
     ops::RangeInclusive,
-    sync::{Condvar, Mutex, MutexGuard, WaitTimeoutResult},
-    time::Duration,
+    sync::Weak,
+    thread,
+    time::{Duration, Instant},
 };
 
 use tracing::{error, info, trace, warn};
 
 use super::{
-    CalcMode, Command, Config, DspMode, InputStage, Mode, Model, Sweep, TrackingStatus, WifiBand,
+    auto_input_ranging::AutoRanger, health::SweepRate, sweep_history::SweepHistory,
+    AnalyzerSnapshot, AutoRangingPolicy, AutoRangingStats, CalcMode, ChannelPlan, Command, Config,
+    DeviceSnapshot, DspMode, Health, InputStage, InputStageSwitch, Message, MessageCursor,
+    MessageLog, Mode, Model, RadioModule, Sweep, SweepLenMismatchPolicy, TrackingIterator,
+    TrackingStatus, WifiBand, WifiRegion,
 };
+use crate::common::sync::{
+    atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering},
+    Condvar, Mutex, MutexGuard, WaitTimeoutResult,
+};
+use crate::common::{ensure_not_reader_thread, LockResultExt, SerialPort};
 use crate::rf_explorer::{
-    impl_rf_explorer, ScreenData, SerialNumber, SetupInfo, COMMAND_RESPONSE_TIMEOUT,
-    NEXT_SCREEN_DATA_TIMEOUT, RECEIVE_INITIAL_DEVICE_INFO_TIMEOUT,
+    call_callback, impl_rf_explorer, Callback, ScreenData, SerialNumber, SetupInfo,
+    COMMAND_RESPONSE_TIMEOUT, NEXT_SCREEN_DATA_TIMEOUT, RECEIVE_INITIAL_DEVICE_INFO_TIMEOUT,
 };
-use crate::{ConnectionError, ConnectionResult, Device, Error, Frequency, Result};
+use crate::{ConnectionError, ConnectionResult, Device, DeviceType, Error, Frequency, Result};
 
 #[derive(Debug)]
 pub struct SpectrumAnalyzer {
@@ -26,53 +36,170 @@ impl_rf_explorer!(SpectrumAnalyzer, MessageContainer);
 
 impl SpectrumAnalyzer {
     const MIN_MAX_AMP_RANGE_DBM: RangeInclusive<i16> = -120..=35;
+    // The narrowest amplitude range the firmware displays without adjusting it
+    const MIN_AMP_RANGE_DB: i16 = 10;
     const MIN_SWEEP_LEN: u16 = 112;
-    const NEXT_SWEEP_TIMEOUT: Duration = Duration::from_secs(2);
+    const MAX_SWEEP_LEN: u16 = 65_520;
+    pub(crate) const NEXT_SWEEP_TIMEOUT: Duration = Duration::from_secs(2);
+    const RESET_RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+    const RESET_TIMEOUT: Duration = Duration::from_secs(10);
+    // A health check only requests a Config if no message has been received for this long
+    const HEALTH_CHECK_SILENCE: Duration = Duration::from_secs(2);
+
+    /// Connects to the RF Explorer with the given serial number.
+    ///
+    /// Every available RF Explorer is connected to in turn until one reports a matching serial
+    /// number. The others are disconnected.
+    #[tracing::instrument(target = "rfe::spectrum_analyzer")]
+    pub fn connect_with_serial_number(serial_number: &SerialNumber) -> Option<Self> {
+        Device::connect_all(Cow::from(crate::rf_explorer::Command::RequestConfig))
+            .filter_map(|rfe| rfe.ok())
+            .map(|rfe| SpectrumAnalyzer { rfe })
+            .find(|rfe| {
+                rfe.wait_for_serial_number()
+                    .is_ok_and(|found| found == *serial_number)
+            })
+    }
+
+    /// Reboots the RF Explorer and reconnects to it once it has restarted.
+    ///
+    /// The RF Explorer is reconnected to using the same port name and baud rate, retrying until
+    /// it responds or 10 seconds have elapsed. Callbacks and cached messages don't carry over to
+    /// the new connection. If the RF Explorer can't be reconnected to, an error is returned and
+    /// every later command fails since the port has been closed.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn reset(&mut self) -> io::Result<()> {
+        let port_name = self.port_name().to_string();
+        let baud_rate = self.baud_rate()?;
+        self.reboot()?;
+        // The port must be closed before it can be opened again once the RF Explorer restarts
+        self.rfe.close();
+
+        let deadline = Instant::now() + Self::RESET_TIMEOUT;
+        loop {
+            thread::sleep(
+                Self::RESET_RECONNECT_INTERVAL
+                    .min(deadline.saturating_duration_since(Instant::now())),
+            );
+            match Self::connect_with_name_and_baud_rate(&port_name, baud_rate) {
+                Ok(rfe) => {
+                    *self = rfe;
+                    return Ok(());
+                }
+                Err(err) if Instant::now() >= deadline => return Err(io::Error::other(err)),
+                Err(err) => trace!("Failed to reconnect after reset: {err}"),
+            }
+        }
+    }
 
     /// The serial number of the RF Explorer, if it exists.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn serial_number(&self) -> Option<String> {
+        self.wait_for_serial_number()
+            .ok()
+            .map(|serial_number| serial_number.to_string())
+    }
+
+    /// Waits for the RF Explorer to report its `SerialNumber`, requesting it if it hasn't been
+    /// received yet.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn wait_for_serial_number(&self) -> Result<SerialNumber> {
+        self.wait_for_serial_number_with_timeout(COMMAND_RESPONSE_TIMEOUT)
+    }
+
+    /// Waits for the RF Explorer to report its `SerialNumber`, requesting it if it hasn't been
+    /// received yet, or for the timeout duration to elapse.
+    ///
+    /// The cached `SerialNumber` is returned immediately if it has already been received.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn wait_for_serial_number_with_timeout(&self, timeout: Duration) -> Result<SerialNumber> {
         // Return the serial number if we've already received it
-        if let Some(ref serial_number) = *self.messages().serial_number.0.lock().unwrap() {
-            return Some(serial_number.to_string());
+        if let Some(ref serial_number) = *self.messages().serial_number.0.lock().unpoisoned() {
+            return Ok(serial_number.clone());
         }
 
         // If we haven't already received the serial number, request it from the RF Explorer
-        self.send_command(crate::rf_explorer::Command::RequestSerialNumber)
-            .ok()?;
+        ensure_not_reader_thread()?;
+        self.send_command(crate::rf_explorer::Command::RequestSerialNumber)?;
 
-        // Wait 2 seconds for the RF Explorer to send its serial number
         let (lock, cvar) = &self.messages().serial_number;
-        tracing::trace!("Waiting to receive SerialNumber from RF Explorer");
-        let _ = cvar
-            .wait_timeout_while(
-                lock.lock().unwrap(),
-                std::time::Duration::from_secs(2),
-                |serial_number| serial_number.is_none(),
-            )
-            .unwrap();
+        trace!("Waiting to receive SerialNumber from RF Explorer");
+        let (serial_number, _) = cvar
+            .wait_timeout_while(lock.lock().unpoisoned(), timeout, |serial_number| {
+                serial_number.is_none()
+            })
+            .unpoisoned();
 
-        (*self.messages().serial_number.0.lock().unwrap())
-            .as_ref()
-            .map(|sn| sn.to_string())
+        serial_number.clone().ok_or(Error::TimedOut(timeout))
     }
 
     /// The firmware version of the RF Explorer.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn firmware_version(&self) -> String {
         self.messages()
             .setup_info
             .0
             .lock()
-            .unwrap()
+            .unpoisoned()
             .as_ref()
             .map(|setup_info| setup_info.firmware_version.clone())
             .unwrap_or_default()
     }
 
-    fn config(&self) -> MutexGuard<Option<Config>> {
-        self.messages().config.0.lock().unwrap()
+    /// The `SetupInfo` reported by the RF Explorer.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn setup_info(&self) -> Option<SetupInfo<Model>> {
+        self.messages().setup_info.0.lock().unpoisoned().clone()
+    }
+
+    /// Waits for the RF Explorer to report its `SetupInfo`, requesting it if it hasn't been
+    /// received yet.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn wait_for_setup_info(&self) -> Result<SetupInfo<Model>> {
+        self.wait_for_setup_info_with_timeout(COMMAND_RESPONSE_TIMEOUT)
+    }
+
+    /// Waits for the RF Explorer to report its `SetupInfo`, requesting it if it hasn't been
+    /// received yet, or for the timeout duration to elapse.
+    ///
+    /// The cached `SetupInfo` is returned immediately if it has already been received.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn wait_for_setup_info_with_timeout(&self, timeout: Duration) -> Result<SetupInfo<Model>> {
+        if let Some(setup_info) = self.setup_info() {
+            return Ok(setup_info);
+        }
+
+        // The RF Explorer sends its SetupInfo along with its Config
+        ensure_not_reader_thread()?;
+        self.send_command(crate::rf_explorer::Command::RequestConfig)?;
+
+        let (lock, condvar) = &self.messages().setup_info;
+        trace!("Waiting to receive 'SetupInfo'");
+        let (setup_info, _) = condvar
+            .wait_timeout_while(lock.lock().unpoisoned(), timeout, |setup_info| {
+                setup_info.is_none()
+            })
+            .unpoisoned();
+
+        setup_info.clone().ok_or(Error::TimedOut(timeout))
+    }
+
+    fn config(&self) -> MutexGuard<'_, Option<Config>> {
+        self.messages().config.0.lock().unpoisoned()
+    }
+
+    /// Calls the closure with the most recent `Config` received from the RF Explorer without
+    /// cloning it.
+    ///
+    /// The config's lock is held while the closure runs, so the closure should return quickly
+    /// since new configs can't be received until it does.
+    #[tracing::instrument(skip(self, f), target = "rfe::spectrum_analyzer")]
+    pub fn with_config<R>(&self, f: impl FnOnce(Option<&Config>) -> R) -> R {
+        f(self.config().as_ref())
     }
 
     /// The start frequency of the RF Explorer's sweeps.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn start_freq(&self) -> Frequency {
         self.config()
             .as_ref()
@@ -81,6 +208,7 @@ impl SpectrumAnalyzer {
     }
 
     /// The step size of the RF Explorer's sweeps.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn step_size(&self) -> Frequency {
         self.config()
             .as_ref()
@@ -89,6 +217,7 @@ impl SpectrumAnalyzer {
     }
 
     /// The stop frequency of the RF Explorer's sweeps.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn stop_freq(&self) -> Frequency {
         self.config()
             .as_ref()
@@ -97,6 +226,7 @@ impl SpectrumAnalyzer {
     }
 
     /// The center frequency of the RF Explorer's sweeps.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn center_freq(&self) -> Frequency {
         self.config()
             .as_ref()
@@ -105,6 +235,7 @@ impl SpectrumAnalyzer {
     }
 
     /// The span of the RF Explorer's sweeps.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn span(&self) -> Frequency {
         self.config()
             .as_ref()
@@ -112,7 +243,31 @@ impl SpectrumAnalyzer {
             .unwrap_or_default()
     }
 
+    /// The center frequency of the RF Explorer's sweeps in Hz.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn center_frequency_hz(&self) -> u64 {
+        self.center_freq().as_hz()
+    }
+
+    /// The span of the RF Explorer's sweeps in Hz.
+    ///
+    /// This is `span` as a plain number of Hz.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn span_hz(&self) -> u64 {
+        self.span().as_hz()
+    }
+
+    /// The difference between the stop and start frequencies of the RF Explorer's sweeps in Hz.
+    ///
+    /// The span is always the difference between the stop and start frequencies, so this is the
+    /// same as `span_hz`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn sweep_span_hz(&self) -> u64 {
+        self.span_hz()
+    }
+
     /// The minimum supported frequency of the RF Explorer.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn min_freq(&self) -> Frequency {
         self.config()
             .as_ref()
@@ -121,6 +276,7 @@ impl SpectrumAnalyzer {
     }
 
     /// The maximum supported frequency of the RF Explorer.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn max_freq(&self) -> Frequency {
         self.config()
             .as_ref()
@@ -129,6 +285,7 @@ impl SpectrumAnalyzer {
     }
 
     /// The maximum supported span of the RF Explorer.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn max_span(&self) -> Frequency {
         self.config()
             .as_ref()
@@ -137,6 +294,7 @@ impl SpectrumAnalyzer {
     }
 
     /// The resolution bandwidth of the RF Explorer.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn rbw(&self) -> Option<Frequency> {
         self.config()
             .as_ref()
@@ -145,6 +303,7 @@ impl SpectrumAnalyzer {
     }
 
     /// The minimum amplitude of sweeps displayed on the RF Explorer's screen.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn min_amp_dbm(&self) -> i16 {
         self.config()
             .as_ref()
@@ -153,6 +312,7 @@ impl SpectrumAnalyzer {
     }
 
     /// The maximum amplitude of sweeps displayed on the RF Explorer's screen.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn max_amp_dbm(&self) -> i16 {
         self.config()
             .as_ref()
@@ -161,6 +321,7 @@ impl SpectrumAnalyzer {
     }
 
     /// The amplitude offset of sweeps displayed on the RF Explorer's screen.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn amp_offset_db(&self) -> Option<i8> {
         self.config()
             .as_ref()
@@ -169,6 +330,7 @@ impl SpectrumAnalyzer {
     }
 
     /// The number of amplitudes in the RF Explorer's sweeps.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn sweep_len(&self) -> u16 {
         self.config()
             .as_ref()
@@ -184,6 +346,7 @@ impl SpectrumAnalyzer {
     }
 
     /// The current `Mode` of the RF Explorer.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn mode(&self) -> Mode {
         self.config()
             .as_ref()
@@ -192,6 +355,7 @@ impl SpectrumAnalyzer {
     }
 
     /// The current `CalcMode` of the RF Explorer.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn calc_mode(&self) -> Option<CalcMode> {
         self.config()
             .as_ref()
@@ -200,26 +364,47 @@ impl SpectrumAnalyzer {
     }
 
     /// The amplitudes of the most recent sweep measured by the RF Explorer.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn sweep(&self) -> Option<Vec<f32>> {
         self.rfe
             .messages()
             .sweep
             .0
             .lock()
-            .unwrap()
+            .unpoisoned()
             .as_ref()
             .map(|sweep| sweep.amplitudes_dbm.clone())
     }
 
+    /// The most recent `Sweep` measured by the RF Explorer.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn latest_sweep(&self) -> Option<Sweep> {
+        self.messages().sweep.0.lock().unpoisoned().clone()
+    }
+
+    /// Calls the closure with the most recent `Sweep` measured by the RF Explorer without cloning it.
+    ///
+    /// The sweep's lock is held while the closure runs, so the closure should return quickly since
+    /// new sweeps can't be received until it does.
+    #[tracing::instrument(skip(self, f), target = "rfe::spectrum_analyzer")]
+    pub fn with_latest_sweep<R>(&self, f: impl FnOnce(Option<&Sweep>) -> R) -> R {
+        f(self.messages().sweep.0.lock().unpoisoned().as_ref())
+    }
+
     /// Fills the buffer with the amplitudes of the most recent sweep and returns the length of the sweep.
+    #[tracing::instrument(skip(self, buf), target = "rfe::spectrum_analyzer")]
     pub fn fill_buf_with_sweep(&self, buf: &mut [f32]) -> Result<usize> {
-        let sweep = self.messages().sweep.0.lock().unwrap();
+        let sweep = self.messages().sweep.0.lock().unpoisoned();
         let Some(sweep) = sweep.as_ref() else {
             return Err(Error::InvalidOperation(
                 "No sweeps have been measured by the RF Explorer".to_string(),
             ));
         };
 
+        Self::copy_sweep_to_buf(sweep, buf)
+    }
+
+    fn copy_sweep_to_buf(sweep: &Sweep, buf: &mut [f32]) -> Result<usize> {
         let sweep_len = sweep.amplitudes_dbm.len();
         if buf.len() >= sweep_len {
             buf[0..sweep_len].copy_from_slice(sweep.amplitudes_dbm.as_slice());
@@ -232,142 +417,349 @@ impl SpectrumAnalyzer {
     }
 
     /// Waits for the RF Explorer to measure the next sweep.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn wait_for_next_sweep(&self) -> Result<Vec<f32>> {
         self.wait_for_next_sweep_with_timeout(Self::NEXT_SWEEP_TIMEOUT)
     }
 
     /// Waits for the RF Explorer to measure the next sweep and fills the buffer with its amplitudes.
+    #[tracing::instrument(skip(self, buf), target = "rfe::spectrum_analyzer")]
     pub fn wait_for_next_sweep_and_fill_buf(&self, buf: &mut [f32]) -> Result<usize> {
         self.wait_for_next_sweep_with_timeout_and_fill_buf(Self::NEXT_SWEEP_TIMEOUT, buf)
     }
 
     /// Waits for the RF Explorer to measure the next sweep or for the timeout duration to elapse.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn wait_for_next_sweep_with_timeout(&self, timeout: Duration) -> Result<Vec<f32>> {
-        let previous_sweep_timestamp = self
+        self.wait_for_next_sweep_with_timeout_internal(timeout)
+            .map(|sweep| sweep.amplitudes_dbm)
+    }
+
+    fn wait_for_next_sweep_with_timeout_internal(&self, timeout: Duration) -> Result<Sweep> {
+        ensure_not_reader_thread()?;
+        self.check_sweep_expected()?;
+
+        let previous_sweep_captured_at = self
             .rfe
             .messages()
             .sweep
             .0
             .lock()
-            .unwrap()
+            .unpoisoned()
             .as_ref()
-            .map(|sweep| sweep.timestamp);
+            .map(|sweep| sweep.captured_at);
 
         let (sweep, cond_var) = &self.messages().sweep;
-        // Wait until the timestamp of the previous sweep and the next sweep are different
+        // Wait until the capture time of the previous sweep and the next sweep are different
         let (sweep, wait_result) = cond_var
-            .wait_timeout_while(sweep.lock().unwrap(), timeout, |sweep| {
-                sweep.as_ref().map(|sweep| sweep.timestamp) == previous_sweep_timestamp
+            .wait_timeout_while(sweep.lock().unpoisoned(), timeout, |sweep| {
+                sweep.as_ref().map(|sweep| sweep.captured_at) == previous_sweep_captured_at
                     || sweep.is_none()
             })
-            .unwrap();
+            .unpoisoned();
 
         match &*sweep {
-            Some(sweep) if !wait_result.timed_out() => Ok(sweep.amplitudes_dbm.clone()),
+            Some(sweep) if !wait_result.timed_out() => Ok(sweep.clone()),
             _ => Err(Error::TimedOut(timeout)),
         }
     }
 
+    /// Returns an error if the RF Explorer won't send a sweep without being asked for one.
+    ///
+    /// In tracking mode, the RF Explorer only measures a sweep after each call to `tracking_step`.
+    fn check_sweep_expected(&self) -> Result<()> {
+        if self.mode() == Mode::AnalyzerTracking
+            && !self
+                .messages()
+                .tracking_step_pending
+                .load(Ordering::Relaxed)
+        {
+            return Err(Error::InvalidOperation(
+                "The RF Explorer is in tracking mode and only measures a sweep after each tracking step, call tracking_step or use start_tracking_stepped".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Waits for the RF Explorer to measure the next sweep, or for the timeout duration to elapse,
     /// and fills the buffer with its amplitudes.
+    #[tracing::instrument(skip(self, buf), target = "rfe::spectrum_analyzer")]
     pub fn wait_for_next_sweep_with_timeout_and_fill_buf(
         &self,
         timeout: Duration,
         buf: &mut [f32],
     ) -> Result<usize> {
-        let previous_sweep_timestamp = self
+        ensure_not_reader_thread()?;
+        self.check_sweep_expected()?;
+
+        let previous_sweep_captured_at = self
             .rfe
             .messages()
             .sweep
             .0
             .lock()
-            .unwrap()
+            .unpoisoned()
             .as_ref()
-            .map(|sweep| sweep.timestamp);
+            .map(|sweep| sweep.captured_at);
 
         let (sweep, cond_var) = &self.messages().sweep;
-        // Wait until the timestamp of the previous sweep and the next sweep are different
-        let (_, wait_result) = cond_var
-            .wait_timeout_while(sweep.lock().unwrap(), timeout, |sweep| {
-                sweep.as_ref().map(|sweep| sweep.timestamp) == previous_sweep_timestamp
+        // Wait until the capture time of the previous sweep and the next sweep are different
+        let (sweep, wait_result) = cond_var
+            .wait_timeout_while(sweep.lock().unpoisoned(), timeout, |sweep| {
+                sweep.as_ref().map(|sweep| sweep.captured_at) == previous_sweep_captured_at
                     || sweep.is_none()
             })
-            .unwrap();
+            .unpoisoned();
 
-        if !wait_result.timed_out() {
-            self.fill_buf_with_sweep(buf)
+        // The sweep is copied from the guard that's already held since the lock isn't reentrant
+        match &*sweep {
+            Some(sweep) if !wait_result.timed_out() => Self::copy_sweep_to_buf(sweep, buf),
+            _ => Err(Error::TimedOut(timeout)),
+        }
+    }
+
+    /// Checks that the RF Explorer is responsive, its `Config` is consistent, and sweeps are being
+    /// received.
+    ///
+    /// A `Config` is only requested if no message has been received for 2 seconds, in which case
+    /// the RF Explorer is considered disconnected if it doesn't respond within the timeout.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn health_check(&self, timeout: Duration) -> Result<Health> {
+        let last_message_age = |messages: &MessageContainer| {
+            messages
+                .last_message_at
+                .lock()
+                .unpoisoned()
+                .map(|last_message_at| last_message_at.elapsed())
+        };
+
+        let connected = if last_message_age(self.messages())
+            .is_some_and(|age| age <= Self::HEALTH_CHECK_SILENCE)
+        {
+            true
         } else {
-            Err(Error::TimedOut(timeout))
+            ensure_not_reader_thread()?;
+            let config_seq = self.messages().config_seq.load(Ordering::Relaxed);
+            self.send_command(crate::rf_explorer::Command::RequestConfig)?;
+            let (lock, condvar) = &self.messages().config;
+            let (_config, wait_result) = condvar
+                .wait_timeout_while(lock.lock().unpoisoned(), timeout, |_| {
+                    self.messages().config_seq.load(Ordering::Relaxed) == config_seq
+                })
+                .unpoisoned();
+            !wait_result.timed_out()
+        };
+
+        let config = self.config().clone();
+        Ok(Health {
+            last_message_age: last_message_age(self.messages()),
+            sweep_rate: self
+                .messages()
+                .sweep_rate
+                .lock()
+                .unpoisoned()
+                .per_second(Instant::now()),
+            mode: config.as_ref().map(|config| config.mode),
+            connected,
+            config_valid: config.is_some_and(|config| {
+                config.start_freq <= config.stop_freq
+                    && config.min_amp_dbm < config.max_amp_dbm
+                    && config.sweep_len > 0
+            }),
+        })
+    }
+
+    /// Returns a receiver that is notified each time the RF Explorer sends a `Config`.
+    ///
+    /// The receiver's current value is the most recently received `Config`, or `None` if no
+    /// `Config` has been received yet.
+    #[cfg(feature = "tokio")]
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn config_watcher(&self) -> tokio::sync::watch::Receiver<Option<Config>> {
+        self.messages().config_watch.subscribe()
+    }
+
+    /// Returns a receiver that is notified each time the RF Explorer sends a `Sweep`.
+    ///
+    /// The receiver's current value is the most recently received `Sweep`, or `None` if no `Sweep`
+    /// has been received yet.
+    #[cfg(feature = "tokio")]
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn sweep_watcher(&self) -> tokio::sync::watch::Receiver<Option<Sweep>> {
+        self.messages().sweep_watch.subscribe()
+    }
+
+    /// Waits for the RF Explorer to send a `Config` that differs from the one cached when this is
+    /// called, or for the timeout duration to elapse.
+    ///
+    /// `Config`s that only differ by their timestamp are ignored, so this can be used to detect
+    /// settings changed on the RF Explorer's front panel or to confirm that a command was accepted.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn wait_for_next_config(&self, timeout: Duration) -> Result<Config> {
+        ensure_not_reader_thread()?;
+        let previous_config = self.config().clone();
+
+        let (config, condvar) = &self.messages().config;
+        let (config, wait_result) = condvar
+            .wait_timeout_while(config.lock().unpoisoned(), timeout, |config| {
+                match (config.as_ref(), previous_config.as_ref()) {
+                    (Some(config), Some(previous_config)) => {
+                        config.eq_ignoring_timestamp(previous_config)
+                    }
+                    (config, _) => config.is_none(),
+                }
+            })
+            .unpoisoned();
+
+        match &*config {
+            Some(config) if !wait_result.timed_out() => Ok(config.clone()),
+            _ => Err(Error::TimedOut(timeout)),
         }
     }
 
+    /// Waits for the RF Explorer to send its next message of any type, or for the timeout duration
+    /// to elapse.
+    ///
+    /// This is useful for debugging how the RF Explorer responds to commands.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn wait_for_any_message(&self, timeout: Duration) -> Result<Message> {
+        ensure_not_reader_thread()?;
+        wait_for_any_message(self.messages(), timeout)
+    }
+
     /// Returns the most recent `ScreenData` captured by the RF Explorer.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn screen_data(&self) -> Option<ScreenData> {
-        self.messages().screen_data.0.lock().unwrap().clone()
+        self.messages().screen_data.0.lock().unpoisoned().clone()
+    }
+
+    /// Calls the closure with the most recent `ScreenData` captured by the RF Explorer without
+    /// cloning it.
+    ///
+    /// The screen data's lock is held while the closure runs, so the closure should return quickly
+    /// since new screen data can't be received until it does.
+    #[tracing::instrument(skip(self, f), target = "rfe::spectrum_analyzer")]
+    pub fn with_screen_data<R>(&self, f: impl FnOnce(Option<&ScreenData>) -> R) -> R {
+        f(self.messages().screen_data.0.lock().unpoisoned().as_ref())
+    }
+
+    /// Returns the most recent `Config` if it's newer than the last one seen by the cursor.
+    ///
+    /// This never blocks waiting for a new `Config`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn try_next_config(&self, cursor: &mut MessageCursor) -> Option<Config> {
+        let config = self.config();
+        try_next(&config, &self.messages().config_seq, &mut cursor.config_seq)
+    }
+
+    /// Returns the most recent `Sweep` if it's newer than the last one seen by the cursor.
+    ///
+    /// This never blocks waiting for a new `Sweep`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn try_next_sweep(&self, cursor: &mut MessageCursor) -> Option<Sweep> {
+        let sweep = self.messages().sweep.0.lock().unpoisoned();
+        try_next(&sweep, &self.messages().sweep_seq, &mut cursor.sweep_seq)
+    }
+
+    /// Returns the most recent `ScreenData` if it's newer than the last one seen by the cursor.
+    ///
+    /// This never blocks waiting for new `ScreenData`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn try_next_screen_data(&self, cursor: &mut MessageCursor) -> Option<ScreenData> {
+        let screen_data = self.messages().screen_data.0.lock().unpoisoned();
+        try_next(
+            &screen_data,
+            &self.messages().screen_data_seq,
+            &mut cursor.screen_data_seq,
+        )
     }
 
     /// Waits for the RF Explorer to capture its next `ScreenData`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn wait_for_next_screen_data(&self) -> Result<ScreenData> {
         self.wait_for_next_screen_data_with_timeout(NEXT_SCREEN_DATA_TIMEOUT)
     }
 
     /// Waits for the RF Explorer to capture its next `ScreenData` or for the timeout duration to elapse.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn wait_for_next_screen_data_with_timeout(&self, timeout: Duration) -> Result<ScreenData> {
-        let previous_screen_data = self.screen_data();
-
-        let (screen_data, condvar) = &self.messages().screen_data;
-        let (screen_data, wait_result) = condvar
-            .wait_timeout_while(screen_data.lock().unwrap(), timeout, |screen_data| {
-                *screen_data == previous_screen_data || screen_data.is_none()
-            })
-            .unwrap();
-
-        match &*screen_data {
-            Some(screen_data) if !wait_result.timed_out() => Ok(screen_data.clone()),
-            _ => Err(Error::TimedOut(timeout)),
-        }
+        rf_explorer::wait_for_next_screen_data(&self.messages().screen_data, timeout)
     }
 
     /// Returns the RF Explorer's DSP mode.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn dsp_mode(&self) -> Option<DspMode> {
-        *self.messages().dsp_mode.0.lock().unwrap()
+        *self.messages().dsp_mode.0.lock().unpoisoned()
     }
 
     /// Returns the status of tracking mode (enabled or disabled).
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn tracking_status(&self) -> Option<TrackingStatus> {
-        *self.messages().tracking_status.0.lock().unwrap()
+        *self.messages().tracking_status.0.lock().unpoisoned()
     }
 
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn input_stage(&self) -> Option<InputStage> {
-        *self.messages().input_stage.0.lock().unwrap()
+        *self.messages().input_stage.0.lock().unpoisoned()
     }
 
     /// Returns the main radio's model.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn main_radio_model(&self) -> Option<Model> {
         self.messages()
             .setup_info
             .0
             .lock()
-            .unwrap()
+            .unpoisoned()
             .as_ref()
             .unwrap()
             .main_radio_model
     }
 
     /// Returns the expansion radio's model (if one exists).
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn expansion_radio_model(&self) -> Option<Model> {
         self.rfe
             .messages()
             .setup_info
             .0
             .lock()
-            .unwrap()
+            .unpoisoned()
             .as_ref()
             .unwrap()
             .expansion_radio_model
     }
 
     /// Returns the active radio module.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn active_radio_module(&self) -> RadioModule {
+        if self.is_expansion_radio_module_active() {
+            RadioModule::Expansion
+        } else {
+            RadioModule::Main
+        }
+    }
+
+    /// Returns the product name of the active radio's model, e.g. `"WSUB1G+"`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn active_radio_module_name(&self) -> String {
+        self.active_radio_model().to_string()
+    }
+
+    /// Describes the active radio module and its model, e.g. `"Main (WSUB1G+)"` or
+    /// `"Expansion (WSUB3G)"`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn active_module_description(&self) -> String {
+        format!(
+            "{} ({})",
+            self.active_radio_module(),
+            self.active_radio_model()
+        )
+    }
+
+    /// Returns the active radio's model.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn active_radio_model(&self) -> Model {
         if self.is_expansion_radio_module_active() {
             self.expansion_radio_model().unwrap_or_default()
@@ -376,7 +768,22 @@ impl SpectrumAnalyzer {
         }
     }
 
-    /// Returns the inactive radio module (if one exists).
+    /// Returns the range of frequencies the active radio can measure.
+    ///
+    /// This is the same range that start and stop frequencies are validated against.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn active_frequency_range(&self) -> RangeInclusive<Frequency> {
+        self.active_radio_model().frequency_range()
+    }
+
+    /// Returns the range of spans the active radio supports.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn active_span_range(&self) -> RangeInclusive<Frequency> {
+        self.active_radio_model().span_range()
+    }
+
+    /// Returns the inactive radio's model (if one exists).
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn inactive_radio_model(&self) -> Option<Model> {
         let expansion_radio_model = self.expansion_radio_model();
         if expansion_radio_model.is_some() {
@@ -391,23 +798,172 @@ impl SpectrumAnalyzer {
     }
 
     /// Starts the spectrum analyzer's Wi-Fi analyzer.
-    #[tracing::instrument]
-    pub fn start_wifi_analyzer(&self, wifi_band: WifiBand) -> io::Result<()> {
-        self.send_command(Command::StartWifiAnalyzer(wifi_band))
+    ///
+    /// Returns an error if the RF Explorer is in tracking mode or none of its radios can measure the
+    /// Wi-Fi band.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn start_wifi_analyzer(&self, wifi_band: WifiBand) -> Result<()> {
+        if self.mode() == Mode::AnalyzerTracking {
+            return Err(Error::InvalidOperation(
+                "The Wi-Fi analyzer can't be started while the RF Explorer is in tracking mode"
+                    .to_string(),
+            ));
+        }
+
+        let models = [self.main_radio_model(), self.expansion_radio_model()];
+        if !models
+            .iter()
+            .flatten()
+            .any(|model| model.supports_wifi_band(wifi_band))
+        {
+            let models = models
+                .iter()
+                .flatten()
+                .map(Model::to_string)
+                .collect::<Vec<_>>()
+                .join(" and ");
+            return Err(Error::InvalidOperation(format!(
+                "This RF Explorer ({models}) doesn't support the Wi-Fi analyzer on the {wifi_band} band"
+            )));
+        }
+
+        if self.wifi_channels(wifi_band).is_empty() {
+            return Err(Error::InvalidOperation(format!(
+                "None of the {wifi_band} Wi-Fi channels allowed in {} can be measured by this RF Explorer",
+                self.wifi_region()
+            )));
+        }
+
+        Ok(self.send_command(Command::StartWifiAnalyzer(wifi_band))?)
+    }
+
+    /// Sets the region whose channel plan decides which Wi-Fi channels are reported and allowed.
+    ///
+    /// The region defaults to `WifiRegion::All`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn set_wifi_region(&self, wifi_region: WifiRegion) {
+        *self.messages().wifi_region.lock().unpoisoned() = wifi_region;
+    }
+
+    /// The region whose channel plan decides which Wi-Fi channels are reported and allowed.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn wifi_region(&self) -> WifiRegion {
+        *self.messages().wifi_region.lock().unpoisoned()
+    }
+
+    /// The Wi-Fi channels in the band that are allowed in the current region and can be measured
+    /// by one of the RF Explorer's radios.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn wifi_channels(&self, wifi_band: WifiBand) -> Vec<u32> {
+        let models = [self.main_radio_model(), self.expansion_radio_model()];
+        self.wifi_region()
+            .channels(wifi_band)
+            .iter()
+            .copied()
+            .filter(|channel| {
+                WifiRegion::center_frequency(wifi_band, *channel).is_some_and(|center| {
+                    models
+                        .iter()
+                        .flatten()
+                        .any(|model| model.frequency_range().contains(&center))
+                })
+            })
+            .collect()
     }
 
     /// Stops the spectrum analyzer's Wi-Fi analyzer.
-    #[tracing::instrument(skip(self))]
-    pub fn stop_wifi_analyzer(&self) -> io::Result<()> {
-        self.send_command(Command::StopWifiAnalyzer)
+    ///
+    /// Returns an error if the RF Explorer has reported that it's in a mode other than
+    /// `Mode::WifiAnalyzer`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn stop_wifi_analyzer(&self) -> Result<()> {
+        if let Some(mode) = self
+            .config()
+            .as_ref()
+            .map(|config| config.mode)
+            .filter(|mode| *mode != Mode::WifiAnalyzer)
+        {
+            return Err(Error::InvalidOperation(format!(
+                "The Wi-Fi analyzer isn't running, the RF Explorer is in {mode} mode"
+            )));
+        }
+
+        Ok(self.send_command(Command::StopWifiAnalyzer)?)
+    }
+
+    /// Switches the spectrum analyzer to the given mode and waits for the RF Explorer to report it.
+    ///
+    /// The Wi-Fi analyzer can be started from spectrum analyzer mode, using the 2.4 GHz band if the
+    /// RF Explorer supports it and the 5 GHz band otherwise (use `start_wifi_analyzer` to choose the
+    /// band). Spectrum analyzer mode can be returned to from the Wi-Fi analyzer, tracking, and RF
+    /// sniffer modes. Other transitions return an `InvalidOperation` error.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn set_mode(&self, mode: Mode) -> Result<()> {
+        ensure_not_reader_thread()?;
+        let current_mode = self.mode();
+        if mode == current_mode {
+            return Ok(());
+        }
+
+        let settable_modes = current_mode.settable_modes();
+        if !settable_modes.contains(&mode) {
+            let settable_modes = settable_modes
+                .iter()
+                .map(Mode::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(Error::InvalidOperation(format!(
+                "Can't switch from {current_mode} mode to {mode} mode, the modes that can be switched to are: {}",
+                if settable_modes.is_empty() { "none" } else { &settable_modes }
+            )));
+        }
+
+        match (current_mode, mode) {
+            (Mode::SpectrumAnalyzer, Mode::WifiAnalyzer) => {
+                let wifi_band = [WifiBand::TwoPointFourGhz, WifiBand::FiveGhz]
+                    .into_iter()
+                    .find(|wifi_band| {
+                        [self.main_radio_model(), self.expansion_radio_model()]
+                            .iter()
+                            .flatten()
+                            .any(|model| model.supports_wifi_band(*wifi_band))
+                    })
+                    .unwrap_or(WifiBand::TwoPointFourGhz);
+                self.start_wifi_analyzer(wifi_band)?;
+            }
+            (Mode::WifiAnalyzer, Mode::SpectrumAnalyzer) => self.stop_wifi_analyzer()?,
+            // Sending a config returns the RF Explorer to spectrum analyzer mode
+            _ => self.send_command(Command::SetConfig {
+                start: self.start_freq(),
+                stop: self.stop_freq(),
+                min_amp_dbm: self.min_amp_dbm(),
+                max_amp_dbm: self.max_amp_dbm(),
+            })?,
+        }
+        self.send_command(crate::rf_explorer::Command::RequestConfig)?;
+
+        let (_guard, wait_result) = self.wait_for_config_while(|config| {
+            config
+                .as_ref()
+                .filter(|config| config.mode == mode)
+                .is_none()
+        })?;
+
+        if !wait_result.timed_out() {
+            Ok(())
+        } else {
+            Err(Error::TimedOut(COMMAND_RESPONSE_TIMEOUT))
+        }
     }
 
     /// Requests the spectrum analyzer enter tracking mode.
-    #[tracing::instrument(skip(self))]
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn request_tracking(&self, start_hz: u64, step_hz: u64) -> Result<TrackingStatus> {
+        ensure_not_reader_thread()?;
+
         // Set the tracking status to None so we can tell whether or not we've received a new
         // tracking status message by checking for Some
-        *self.messages().tracking_status.0.lock().unwrap() = None;
+        *self.messages().tracking_status.0.lock().unpoisoned() = None;
 
         // Send the command to enter tracking mode
         self.send_command(Command::StartTracking {
@@ -419,11 +975,11 @@ impl SpectrumAnalyzer {
         let (lock, condvar) = &self.messages().tracking_status;
         let (tracking_status, wait_result) = condvar
             .wait_timeout_while(
-                lock.lock().unwrap(),
+                lock.lock().unpoisoned(),
                 COMMAND_RESPONSE_TIMEOUT,
-                |tracking_status| tracking_status.is_some(),
+                |tracking_status| tracking_status.is_none(),
             )
-            .unwrap();
+            .unpoisoned();
 
         if !wait_result.timed_out() {
             Ok(tracking_status.unwrap_or_default())
@@ -432,14 +988,102 @@ impl SpectrumAnalyzer {
         }
     }
 
+    /// Enters tracking mode and returns an iterator that steps from the start frequency to the stop
+    /// frequency, yielding the sweep measured at each step.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn start_tracking_stepped(
+        &self,
+        start_hz: u64,
+        step_hz: u64,
+        stop_hz: u64,
+    ) -> Result<TrackingIterator<'_>> {
+        if step_hz == 0 {
+            return Err(Error::InvalidInput(
+                "The step frequency must be greater than 0 Hz".to_string(),
+            ));
+        }
+
+        if start_hz > stop_hz {
+            return Err(Error::InvalidInput(
+                "The start frequency must be less than or equal to the stop frequency".to_string(),
+            ));
+        }
+
+        let Ok(last_step) = u16::try_from((stop_hz - start_hz) / step_hz) else {
+            return Err(Error::InvalidInput(format!(
+                "Tracking supports at most {} steps",
+                u32::from(u16::MAX) + 1
+            )));
+        };
+
+        self.request_tracking(start_hz, step_hz)?;
+        Ok(TrackingIterator::new(
+            self,
+            Frequency::from_hz(start_hz),
+            Frequency::from_hz(step_hz),
+            last_step,
+        ))
+    }
+
     /// Steps over the tracking step frequency and makes a measurement.
-    #[tracing::instrument(skip(self))]
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn tracking_step(&self, step: u16) -> io::Result<()> {
+        self.messages()
+            .tracking_step_pending
+            .store(true, Ordering::Relaxed);
         self.send_command(Command::TrackingStep(step))
     }
 
+    /// Steps over the tracking step frequency and waits for the RF Explorer to measure it,
+    /// returning the amplitude (in dBm) measured at the step.
+    ///
+    /// The RF Explorer responds to each tracking step with a sweep, and the amplitude measured at
+    /// the step is the one at the step's index. Waiting for each measurement before stepping again
+    /// keeps steps from being skipped, so this can be used to step in a custom order.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn tracking_step_and_wait(&self, step: u16, timeout: Duration) -> Result<f32> {
+        let sweep = self.tracking_step_and_wait_for_sweep(step, timeout)?;
+        sweep
+            .amplitudes_dbm
+            .get(usize::from(step))
+            .copied()
+            .ok_or_else(|| {
+                Error::InvalidMessage(format!(
+                    "Received a sweep of {} amplitudes in response to tracking step {step}",
+                    sweep.len()
+                ))
+            })
+    }
+
+    pub(crate) fn tracking_step_and_wait_for_sweep(
+        &self,
+        step: u16,
+        timeout: Duration,
+    ) -> Result<Sweep> {
+        ensure_not_reader_thread()?;
+
+        // The sequence number is read before stepping so a sweep that arrives before waiting
+        // begins isn't missed
+        let sweep_seq = self.messages().sweep_seq.load(Ordering::Relaxed);
+        self.tracking_step(step)?;
+
+        let (lock, condvar) = &self.messages().sweep;
+        let (sweep, wait_result) = condvar
+            .wait_timeout_while(lock.lock().unpoisoned(), timeout, |_| {
+                self.messages().sweep_seq.load(Ordering::Relaxed) == sweep_seq
+            })
+            .unpoisoned();
+
+        match &*sweep {
+            Some(sweep) if !wait_result.timed_out() => Ok(sweep.clone()),
+            _ => Err(Error::TimedOut(timeout)),
+        }
+    }
+
     /// Activates the RF Explorer's main radio.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn activate_main_radio(&self) -> Result<()> {
+        ensure_not_reader_thread()?;
         if !self.is_expansion_radio_module_active() {
             return Err(Error::InvalidOperation(
                 "Main radio module is already active.".to_string(),
@@ -449,12 +1093,12 @@ impl SpectrumAnalyzer {
         self.send_command(Command::SwitchModuleMain)?;
 
         // Wait until config shows that the main radio module is active
-        let _ = self.wait_for_config_while(|config| {
+        drop(self.wait_for_config_while(|config| {
             config
                 .as_ref()
                 .filter(|config| !config.is_expansion_radio_module_active)
                 .is_none()
-        });
+        })?);
 
         if !self.is_expansion_radio_module_active() {
             Ok(())
@@ -464,7 +1108,9 @@ impl SpectrumAnalyzer {
     }
 
     /// Activates the RF Explorer's expansion radio (if one exists).
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn activate_expansion_radio(&self) -> Result<()> {
+        ensure_not_reader_thread()?;
         if self.expansion_radio_model().is_none() {
             return Err(Error::InvalidOperation(
                 "This RF Explorer does not contain an expansion radio module.".to_string(),
@@ -480,12 +1126,12 @@ impl SpectrumAnalyzer {
         self.send_command(Command::SwitchModuleExp)?;
 
         // Wait until config shows that the expansion radio module is active
-        let _ = self.wait_for_config_while(|config| {
+        drop(self.wait_for_config_while(|config| {
             config
                 .as_ref()
                 .filter(|config| config.is_expansion_radio_module_active)
                 .is_none()
-        });
+        })?);
 
         if self.is_expansion_radio_module_active() {
             Ok(())
@@ -495,41 +1141,100 @@ impl SpectrumAnalyzer {
     }
 
     /// Sets the start and stop frequency of sweeps measured by the spectrum analyzer.
+    #[tracing::instrument(skip(self, start, stop), target = "rfe::spectrum_analyzer", fields(start = tracing::field::Empty, stop = tracing::field::Empty))]
     pub fn set_start_stop(
         &self,
         start: impl Into<Frequency>,
         stop: impl Into<Frequency>,
     ) -> Result<()> {
-        self.set_config(
-            start.into(),
-            stop.into(),
-            self.min_amp_dbm(),
-            self.max_amp_dbm(),
-        )
+        let (start, stop) = (start.into(), stop.into());
+        record_freq_fields([("start", start), ("stop", stop)]);
+        self.set_config(start, stop, self.min_amp_dbm(), self.max_amp_dbm())
+    }
+
+    /// Sets the start and stop frequency of sweeps measured by the spectrum analyzer to the start
+    /// and end of a frequency range.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn set_frequency_range(&self, range: RangeInclusive<Frequency>) -> Result<()> {
+        let (start, stop) = range.into_inner();
+        self.set_start_stop(start, stop)
     }
 
     /// Sets the start frequency, stop frequency, and number of points of sweeps measured by the spectrum analyzer.
+    #[tracing::instrument(skip(self, start, stop), target = "rfe::spectrum_analyzer", fields(start = tracing::field::Empty, stop = tracing::field::Empty))]
     pub fn set_start_stop_sweep_len(
         &self,
         start: impl Into<Frequency>,
         stop: impl Into<Frequency>,
         sweep_len: u16,
     ) -> Result<()> {
+        let (start, stop) = (start.into(), stop.into());
+        record_freq_fields([("start", start), ("stop", stop)]);
         self.set_sweep_len(sweep_len)?;
         self.set_start_stop(start, stop)
     }
 
     /// Sets the center frequency and span of sweeps measured by the spectrum analyzer.
+    #[tracing::instrument(skip(self, center, span), target = "rfe::spectrum_analyzer", fields(center = tracing::field::Empty, span = tracing::field::Empty))]
     pub fn set_center_span(
         &self,
         center: impl Into<Frequency>,
         span: impl Into<Frequency>,
     ) -> Result<()> {
         let (center, span) = (center.into(), span.into());
+        record_freq_fields([("center", center), ("span", span)]);
         self.set_start_stop(center - span / 2, center + span / 2)
     }
 
+    /// Centers sweeps on the given channel of a channel plan.
+    ///
+    /// The span is set to twice the plan's channel bandwidth, widened to the active radio's minimum
+    /// span if necessary.
+    #[tracing::instrument(skip(self, channel_plan), target = "rfe::spectrum_analyzer")]
+    pub fn tune_to(&self, channel_plan: &ChannelPlan, channel: u32) -> Result<()> {
+        let Some(center) = channel_plan.center_frequency(channel) else {
+            return Err(Error::InvalidInput(format!(
+                "Channel {channel} is not part of a channel plan with {} channels",
+                channel_plan.channel_count()
+            )));
+        };
+
+        let span = (channel_plan.channel_bandwidth * 2).max(*self.active_span_range().start());
+        self.set_center_span(center, span)
+    }
+
+    /// Returns `(channel, occupancy)` for every channel in a channel plan, where occupancy is the
+    /// fraction of the sweep's points within the channel whose amplitude is above `threshold_dbm`.
+    ///
+    /// `config` should be the `Config` the sweep was measured with, since it's used to determine
+    /// the frequency of each point. Channels outside of the sweep have an occupancy of 0.
+    #[tracing::instrument(skip(channel_plan, config, sweep), target = "rfe::spectrum_analyzer")]
+    pub fn measure_channel_occupancy(
+        channel_plan: &ChannelPlan,
+        threshold_dbm: f32,
+        config: &Config,
+        sweep: &Sweep,
+    ) -> Vec<(u32, f32)> {
+        channel_plan.occupancy(threshold_dbm, config, sweep)
+    }
+
+    /// Returns `(frequency, excess_db)` for every point in `live` whose amplitude exceeds the
+    /// corresponding point in `reference` by more than `threshold_db`.
+    ///
+    /// `config` should be the `Config` the sweeps were measured with, since it's used to determine
+    /// the frequency of each point. An empty `Vec` is returned if no new signals are found.
+    #[tracing::instrument(skip(reference, config, live), target = "rfe::spectrum_analyzer")]
+    pub fn detect_interference(
+        reference: &Sweep,
+        threshold_db: f32,
+        config: &Config,
+        live: &Sweep,
+    ) -> Vec<(Frequency, f32)> {
+        live.excess_over(reference, threshold_db, config)
+    }
+
     /// Sets the center frequency, span, and number of points of sweeps measured by the spectrum analyzer.
+    #[tracing::instrument(skip(self, center, span), target = "rfe::spectrum_analyzer", fields(center = tracing::field::Empty, span = tracing::field::Empty))]
     pub fn set_center_span_sweep_len(
         &self,
         center: impl Into<Frequency>,
@@ -537,11 +1242,15 @@ impl SpectrumAnalyzer {
         sweep_len: u16,
     ) -> Result<()> {
         let (center, span) = (center.into(), span.into());
+        record_freq_fields([("center", center), ("span", span)]);
         self.set_start_stop_sweep_len(center - span / 2, center + span / 2, sweep_len)
     }
 
     /// Sets the minimum and maximum amplitudes displayed on the RF Explorer's screen.
-    #[tracing::instrument(skip(self))]
+    ///
+    /// The maximum amplitude must be at least 10 dB greater than the minimum amplitude. A
+    /// `ValueClamped` error is returned if the RF Explorer adjusts the range instead of applying it.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn set_min_max_amps(&self, min_amp_dbm: i16, max_amp_dbm: i16) -> Result<()> {
         self.set_config(
             self.start_freq(),
@@ -551,8 +1260,24 @@ impl SpectrumAnalyzer {
         )
     }
 
+    /// Sets the minimum and maximum amplitudes displayed on the RF Explorer's screen.
+    ///
+    /// The RF Explorer only supports whole dBm values, so the amplitudes are rounded to the
+    /// nearest integer (with halfway values rounded away from zero) before being sent.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn set_min_max_amps_f32(&self, min_amp_dbm: f32, max_amp_dbm: f32) -> Result<()> {
+        if !min_amp_dbm.is_finite() || !max_amp_dbm.is_finite() {
+            return Err(Error::InvalidInput(
+                "The minimum and maximum amplitudes must be finite".to_string(),
+            ));
+        }
+
+        // Casting saturates values outside of i16's range, which are then rejected by validation
+        self.set_min_max_amps(min_amp_dbm.round() as i16, max_amp_dbm.round() as i16)
+    }
+
     /// Sets the spectrum analyzer's configuration.
-    #[tracing::instrument(skip(self), ret, err)]
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer", ret, err)]
     fn set_config(
         &self,
         start: Frequency,
@@ -560,9 +1285,11 @@ impl SpectrumAnalyzer {
         min_amp_dbm: i16,
         max_amp_dbm: i16,
     ) -> Result<()> {
+        ensure_not_reader_thread()?;
         self.validate_start_stop(start, stop)?;
-        self.validate_min_max_amps(min_amp_dbm, max_amp_dbm)?;
+        Self::validate_min_max_amps(min_amp_dbm, max_amp_dbm)?;
 
+        let config_seq = self.messages().config_seq.load(Ordering::Relaxed);
         self.send_command(Command::SetConfig {
             start,
             stop,
@@ -580,64 +1307,412 @@ impl SpectrumAnalyzer {
             return Ok(());
         }
 
-        // Wait until the current config contains the requested values
+        // The firmware may adjust the amplitude range instead of applying it as requested, in
+        // which case the Config it sends in response contains the requested frequencies but not
+        // the requested amplitudes
+        let is_clamped = |config: &Config| {
+            self.messages().config_seq.load(Ordering::Relaxed) != config_seq
+                && config.contains_start_stop_amp_range(
+                    start,
+                    stop,
+                    config.min_amp_dbm,
+                    config.max_amp_dbm,
+                )
+        };
+
+        // Wait until the current config contains the requested values or adjusted amplitudes
         trace!("Waiting to receive updated 'Config'");
-        let (_, wait_result) = self.wait_for_config_while(|config| {
+        let (config, wait_result) = self.wait_for_config_while(|config| {
             let Some(config) = config else {
                 return true;
             };
 
             !config.contains_start_stop_amp_range(start, stop, min_amp_dbm, max_amp_dbm)
-        });
+                && !is_clamped(config)
+        })?;
 
-        if !wait_result.timed_out() {
-            Ok(())
-        } else {
-            Err(Error::TimedOut(COMMAND_RESPONSE_TIMEOUT))
+        match config.as_ref() {
+            Some(config) if !wait_result.timed_out() => {
+                if config.contains_start_stop_amp_range(start, stop, min_amp_dbm, max_amp_dbm) {
+                    Ok(())
+                } else {
+                    Err(Error::ValueClamped(format!(
+                        "Requested an amplitude range of {min_amp_dbm} to {max_amp_dbm} dBm but the RF Explorer set {} to {} dBm",
+                        config.min_amp_dbm, config.max_amp_dbm
+                    )))
+                }
+            }
+            _ => Err(Error::TimedOut(COMMAND_RESPONSE_TIMEOUT)),
         }
     }
 
-    /// Sets the callback that is called when the spectrum analyzer receives a sweep.
-    pub fn set_sweep_callback(&self, cb: impl FnMut(&[f32]) + Send + 'static) {
-        *self.messages().sweep_callback.lock().unwrap() = Some(Box::new(cb));
-    }
+    /// Sets the start and stop frequencies, amplitude range, and sweep length from a `Config`.
+    ///
+    /// The sweep length is only sent if it differs from the current sweep length.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn set_config_from(&self, config: &Config) -> Result<()> {
+        if config.sweep_len != self.sweep_len() {
+            self.set_sweep_len(config.sweep_len)?;
+        }
 
-    /// Removes the callback that is called when the spectrum analyzer receives a `Sweep`.
-    pub fn remove_sweep_callback(&self) {
-        *self.messages().sweep_callback.lock().unwrap() = None;
+        self.set_config(
+            config.start_freq,
+            config.stop_freq,
+            config.min_amp_dbm,
+            config.max_amp_dbm,
+        )
     }
 
-    /// Sets the callback that is called when the spectrum analyzer receives a `Config`.
-    pub fn set_config_callback(&self, cb: impl FnMut() + Send + 'static) {
-        *self.messages().config_callback.lock().unwrap() = Some(Box::new(cb));
+    /// Returns the spectrum analyzer's cached state as it was at a single point in time.
+    ///
+    /// Every piece of state is locked before any of it is read, so the snapshot can't contain
+    /// e.g. a `Sweep` that arrived after its `Config` changed. Use `device_snapshot` instead for
+    /// the settings that can be restored later.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn analyzer_snapshot(&self) -> AnalyzerSnapshot {
+        let messages = self.messages();
+
+        // Locks are always acquired in this order to avoid deadlocks
+        let config = messages.config.0.lock().unpoisoned();
+        let sweep = messages.sweep.0.lock().unpoisoned();
+        let dsp_mode = messages.dsp_mode.0.lock().unpoisoned();
+        let input_stage = messages.input_stage.0.lock().unpoisoned();
+        let tracking_status = messages.tracking_status.0.lock().unpoisoned();
+
+        AnalyzerSnapshot {
+            config: config.clone(),
+            sweep: sweep.clone(),
+            dsp_mode: *dsp_mode,
+            input_stage: *input_stage,
+            tracking_status: *tracking_status,
+        }
     }
 
-    /// Removes the callback that is called when the spectrum analyzer receives a `Config`.
-    pub fn remove_config_callback(&self) {
-        *self.messages().config_callback.lock().unwrap() = None;
+    /// Returns a snapshot of the spectrum analyzer's current settings, which can be applied again
+    /// with `restore`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn device_snapshot(&self) -> DeviceSnapshot {
+        DeviceSnapshot {
+            radio_module: self.active_radio_module(),
+            start: self.start_freq(),
+            stop: self.stop_freq(),
+            min_amp_dbm: self.min_amp_dbm(),
+            max_amp_dbm: self.max_amp_dbm(),
+            sweep_len: self.sweep_len(),
+            calc_mode: self.calc_mode(),
+            dsp_mode: self.dsp_mode(),
+            input_stage: self.input_stage(),
+            amp_offset_db: self.amp_offset_db(),
+        }
     }
 
-    /// Sets the number of points in each sweep measured by the spectrum analyzer.
-    #[tracing::instrument(skip(self))]
-    pub fn set_sweep_len(&self, sweep_len: u16) -> Result<()> {
-        // Only 'Plus' models can set the number of points in a sweep
-        if !self.active_radio_model().is_plus_model() {
-            return Err(Error::InvalidOperation(
-                "Only RF Explorer 'Plus' models support setting the number of sweep points"
-                    .to_string(),
-            ));
+    /// Applies the settings in a snapshot to the spectrum analyzer.
+    ///
+    /// The radio module is switched first, followed by the sweep configuration and then the
+    /// remaining settings. Every setting is attempted even if an earlier one fails, and the
+    /// returned error lists each setting that couldn't be restored.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer", err)]
+    pub fn restore(&self, snapshot: &DeviceSnapshot) -> Result<()> {
+        let mut failures = Vec::new();
+
+        if snapshot.radio_module != self.active_radio_module() {
+            let result = match snapshot.radio_module {
+                RadioModule::Main => self.activate_main_radio(),
+                RadioModule::Expansion => self.activate_expansion_radio(),
+            };
+            if let Err(error) = result {
+                failures.push(format!("radio module ({error})"));
+            }
         }
 
-        if sweep_len <= 4096 {
-            self.send_command(Command::SetSweepPointsExt(sweep_len))?;
-        } else {
-            self.send_command(Command::SetSweepPointsLarge(sweep_len))?;
+        if snapshot.sweep_len != self.sweep_len() {
+            if let Err(error) = self.set_sweep_len(snapshot.sweep_len) {
+                failures.push(format!("sweep length ({error})"));
+            }
         }
 
-        // The requested number of sweep points gets rounded down to a number that's a multiple of 16
-        let expected_sweep_len = if sweep_len < 112 {
-            Self::MIN_SWEEP_LEN
-        } else {
+        if let Err(error) = self.set_config(
+            snapshot.start,
+            snapshot.stop,
+            snapshot.min_amp_dbm,
+            snapshot.max_amp_dbm,
+        ) {
+            failures.push(format!("config ({error})"));
+        }
+
+        if let Some(calc_mode) = snapshot.calc_mode {
+            if let Err(error) = self.set_calc_mode(calc_mode) {
+                failures.push(format!("calc mode ({error})"));
+            }
+        }
+
+        if let Some(dsp_mode) = snapshot.dsp_mode {
+            if let Err(error) = self.set_dsp_mode(dsp_mode) {
+                failures.push(format!("DSP mode ({error})"));
+            }
+        }
+
+        if let Some(input_stage) = snapshot.input_stage {
+            if let Err(error) = self.set_input_stage(input_stage) {
+                failures.push(format!("input stage ({error})"));
+            }
+        }
+
+        if let Some(amp_offset_db) = snapshot.amp_offset_db {
+            if let Err(error) = self.set_offset_db(amp_offset_db) {
+                failures.push(format!("amplitude offset ({error})"));
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidOperation(format!(
+                "Failed to restore the following settings: {}",
+                failures.join(", ")
+            )))
+        }
+    }
+
+    /// Sets the callback that is called when the spectrum analyzer receives a sweep.
+    ///
+    /// The callback runs on the background thread that reads messages from the RF Explorer, so no
+    /// other messages are received while it runs and it should return quickly. Methods that wait
+    /// for a message return an `InvalidOperation` error when called from within the callback, but
+    /// methods that read the most recent sweep without waiting, e.g. `latest_sweep`, can be called,
+    /// and the callback can replace or remove itself. If the callback panics, the panic is caught
+    /// and passed to the error callback, and sweeps continue to be received.
+    #[tracing::instrument(skip(self, cb), target = "rfe::spectrum_analyzer")]
+    pub fn set_sweep_callback(&self, cb: impl FnMut(&[f32]) + Send + 'static) {
+        let messages = self.messages();
+        replace_callback(
+            &messages.sweep_callback,
+            &messages.sweep_callback_generation,
+            Some(Box::new(cb)),
+        );
+    }
+
+    /// Removes the callback that is called when the spectrum analyzer receives a `Sweep`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn remove_sweep_callback(&self) {
+        let messages = self.messages();
+        replace_callback(
+            &messages.sweep_callback,
+            &messages.sweep_callback_generation,
+            None,
+        );
+    }
+
+    /// Sets the callback that is called when more than `clipped_fraction` (between 0 and 1) of a
+    /// sweep's points are at or above the configured maximum amplitude.
+    ///
+    /// The callback receives the indices of the clipping points. This usually means a strong signal
+    /// is overloading the RF Explorer's front end, which can be fixed by enabling an attenuator with
+    /// `set_input_stage(InputStage::Attenuator30dB)`.
+    #[tracing::instrument(skip(self, cb), target = "rfe::spectrum_analyzer")]
+    pub fn set_overload_callback(
+        &self,
+        clipped_fraction: f32,
+        cb: impl FnMut(&[usize]) + Send + 'static,
+    ) {
+        let messages = self.messages();
+        replace_callback(
+            &messages.overload_callback,
+            &messages.overload_callback_generation,
+            Some((clipped_fraction, Box::new(cb))),
+        );
+    }
+
+    /// Removes the callback that is called when sweeps are clipping.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn remove_overload_callback(&self) {
+        let messages = self.messages();
+        replace_callback(
+            &messages.overload_callback,
+            &messages.overload_callback_generation,
+            None,
+        );
+    }
+
+    /// Sets the callback that is called when the spectrum analyzer receives a `Config`.
+    #[tracing::instrument(skip(self, cb), target = "rfe::spectrum_analyzer")]
+    pub fn set_config_callback(&self, cb: impl FnMut() + Send + 'static) {
+        *self.messages().config_callback.lock().unpoisoned() = Some(Box::new(cb));
+    }
+
+    /// Removes the callback that is called when the spectrum analyzer receives a `Config`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn remove_config_callback(&self) {
+        *self.messages().config_callback.lock().unpoisoned() = None;
+    }
+
+    /// Sets the callback that is called with the previous and new `Mode` when the spectrum
+    /// analyzer receives a `Config` reporting a different mode.
+    ///
+    /// The config callback is also called for these `Config`s.
+    #[tracing::instrument(skip(self, cb), target = "rfe::spectrum_analyzer")]
+    pub fn set_mode_change_callback(&self, cb: impl FnMut(Mode, Mode) + Send + 'static) {
+        *self.messages().mode_change_callback.lock().unpoisoned() = Some(Box::new(cb));
+    }
+
+    /// Removes the callback that is called when the spectrum analyzer's `Mode` changes.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn remove_mode_change_callback(&self) {
+        *self.messages().mode_change_callback.lock().unpoisoned() = None;
+    }
+
+    /// Sets the callback that is called with the previous and new `Config` when the spectrum
+    /// analyzer receives a `Config` that differs from the previous one.
+    ///
+    /// Unlike the config callback, this isn't called for the first `Config` or for `Config`s that
+    /// only differ by their timestamp.
+    #[tracing::instrument(skip(self, cb), target = "rfe::spectrum_analyzer")]
+    pub fn set_config_change_callback(&self, cb: impl FnMut(Config, Config) + Send + 'static) {
+        *self.messages().config_change_callback.lock().unpoisoned() = Some(Box::new(cb));
+    }
+
+    /// Removes the callback that is called when the spectrum analyzer's `Config` changes.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn remove_config_change_callback(&self) {
+        *self.messages().config_change_callback.lock().unpoisoned() = None;
+    }
+
+    /// Sets what happens to sweeps whose length doesn't match the current `Config`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn set_sweep_len_mismatch_policy(&self, policy: SweepLenMismatchPolicy) {
+        *self
+            .messages()
+            .sweep_len_mismatch_policy
+            .lock()
+            .unpoisoned() = policy;
+    }
+
+    /// The number of sweeps dropped because their length didn't match the current `Config` while
+    /// using `SweepLenMismatchPolicy::Suppress`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn suppressed_sweep_count(&self) -> u64 {
+        self.messages()
+            .suppressed_sweep_count
+            .load(Ordering::Relaxed)
+    }
+
+    /// Keeps up to `capacity` of the most recent sweeps in a history that can be read with
+    /// `recent_sweeps`.
+    ///
+    /// The capacity defaults to 0, which disables the history. Lowering the capacity discards the
+    /// oldest sweeps.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn set_sweep_history_capacity(&self, capacity: usize) {
+        self.messages()
+            .sweep_history
+            .lock()
+            .unpoisoned()
+            .set_capacity(capacity);
+    }
+
+    /// Returns the sweeps kept in the history from oldest to newest.
+    ///
+    /// The history is empty unless `set_sweep_history_capacity` has been called with a non-zero
+    /// capacity. It's cleared whenever the RF Explorer reports a change to its `Config`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn recent_sweeps(&self) -> Vec<Sweep> {
+        self.messages().sweep_history.lock().unpoisoned().sweeps()
+    }
+
+    /// Starts accumulating the highest amplitude seen in each bin of the received sweeps, which
+    /// can be read with `max_hold_sweep`.
+    ///
+    /// The accumulated sweep is reset whenever the RF Explorer reports a change to its `Config`.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn enable_max_hold_accumulation(&self) {
+        self.messages()
+            .max_hold_skipped_processed
+            .store(false, Ordering::Relaxed);
+        self.messages()
+            .max_hold_enabled
+            .store(true, Ordering::Relaxed);
+    }
+
+    /// Sets whether max-hold accumulation includes sweeps the RF Explorer already processed
+    /// because of its calc mode, e.g. averaged sweeps.
+    ///
+    /// Processed sweeps are skipped by default, and the first skipped sweep is reported to the
+    /// error callback.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn set_max_hold_accepts_processed_sweeps(&self, accept: bool) {
+        self.messages()
+            .max_hold_accepts_processed
+            .store(accept, Ordering::Relaxed);
+    }
+
+    /// Stops accumulating the max-hold sweep, leaving the sweep accumulated so far in place.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn disable_max_hold_accumulation(&self) {
+        self.messages()
+            .max_hold_enabled
+            .store(false, Ordering::Relaxed);
+    }
+
+    /// Returns the sweep holding the highest amplitude seen in each bin since max-hold
+    /// accumulation was enabled or last cleared.
+    ///
+    /// Unlike `sweep`, which returns the most recent sweep, the max-hold sweep is kept separately
+    /// so that both can be displayed together.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn max_hold_sweep(&self) -> Option<Sweep> {
+        self.messages().max_hold_sweep.lock().unpoisoned().clone()
+    }
+
+    /// Discards the accumulated max-hold sweep.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn clear_max_hold(&self) {
+        *self.messages().max_hold_sweep.lock().unpoisoned() = None;
+    }
+
+    /// Starts recording every message received from the spectrum analyzer.
+    ///
+    /// The log holds up to `capacity` messages. Any log that was already being recorded is
+    /// discarded.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn enable_message_log(&self, capacity: usize) {
+        *self.messages().message_log.lock().unpoisoned() = Some(MessageLog::new(capacity));
+    }
+
+    /// Stops recording messages and returns the log.
+    ///
+    /// An empty log is returned if message logging wasn't enabled.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn disable_message_log(&self) -> MessageLog {
+        self.messages()
+            .message_log
+            .lock()
+            .unpoisoned()
+            .take()
+            .unwrap_or_default()
+    }
+
+    /// Sets the number of points in each sweep measured by the spectrum analyzer.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn set_sweep_len(&self, sweep_len: u16) -> Result<()> {
+        ensure_not_reader_thread()?;
+        // Only 'Plus' models can set the number of points in a sweep
+        if !self.active_radio_model().is_plus_model() {
+            return Err(Error::InvalidOperation(
+                "Only RF Explorer 'Plus' models support setting the number of sweep points"
+                    .to_string(),
+            ));
+        }
+
+        if sweep_len <= 4096 {
+            self.send_command(Command::SetSweepPointsExt(sweep_len))?;
+        } else {
+            self.send_command(Command::SetSweepPointsLarge(sweep_len))?;
+        }
+
+        // The requested number of sweep points gets rounded down to a number that's a multiple of 16
+        let expected_sweep_len = if sweep_len < 112 {
+            Self::MIN_SWEEP_LEN
+        } else {
             (sweep_len / 16) * 16
         };
 
@@ -648,12 +1723,12 @@ impl SpectrumAnalyzer {
 
         // Wait until the current config contains the requested sweep points
         info!("Waiting to receive updated config");
-        let (_, wait_result) = self.wait_for_config_while(|config| {
+        let (_guard, wait_result) = self.wait_for_config_while(|config| {
             config
                 .as_ref()
                 .filter(|config| config.sweep_len == expected_sweep_len)
                 .is_none()
-        });
+        })?;
 
         if !wait_result.timed_out() {
             Ok(())
@@ -663,44 +1738,222 @@ impl SpectrumAnalyzer {
         }
     }
 
+    /// Sets the number of sweep points so that the frequency spacing between points is as close
+    /// as possible to, without exceeding, the target resolution.
+    ///
+    /// Returns the number of points in each sweep and the resulting step size.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn set_resolution(&self, target_rbw: Frequency) -> Result<(u16, Frequency)> {
+        if target_rbw.as_hz() == 0 {
+            return Err(Error::InvalidInput(
+                "The target resolution must be greater than 0 Hz".to_string(),
+            ));
+        }
+
+        let max_sweep_len = if self.active_radio_model().is_plus_model() {
+            Self::MAX_SWEEP_LEN
+        } else {
+            Self::MIN_SWEEP_LEN
+        };
+
+        let Some(sweep_len) =
+            Self::sweep_len_for_resolution(self.span(), target_rbw, max_sweep_len)
+        else {
+            return Err(Error::InvalidOperation(format!(
+                "A resolution of {} kHz cannot be reached with a span of {} MHz using at most {} sweep points",
+                target_rbw.as_khz_f64(),
+                self.span().as_mhz_f64(),
+                max_sweep_len
+            )));
+        };
+
+        if sweep_len != self.sweep_len() {
+            self.set_sweep_len(sweep_len)?;
+        }
+
+        Ok((self.sweep_len(), self.step_size()))
+    }
+
+    /// Returns the smallest supported number of sweep points whose step size does not exceed the
+    /// target resolution, or `None` if the target can't be reached with `max_sweep_len` points.
+    fn sweep_len_for_resolution(
+        span: Frequency,
+        target_rbw: Frequency,
+        max_sweep_len: u16,
+    ) -> Option<u16> {
+        // The step size is span / (sweep_len - 1), so solve for the number of points and round up
+        // to the next multiple of 16 the RF Explorer supports
+        let min_points = span.as_hz().div_ceil(target_rbw.as_hz()) + 1;
+        let sweep_len = min_points
+            .div_ceil(16)
+            .saturating_mul(16)
+            .max(u64::from(Self::MIN_SWEEP_LEN));
+
+        u16::try_from(sweep_len)
+            .ok()
+            .filter(|&sweep_len| sweep_len <= max_sweep_len)
+    }
+
     /// Sets the spectrum analyzer's calculator mode.
-    #[tracing::instrument(skip(self))]
+    ///
+    /// The RF Explorer doesn't send a response to this command, so this returns as soon as the
+    /// command is sent rather than waiting for confirmation. Firmware that includes the calculator
+    /// mode in its `Config` reports the new mode the next time it sends a `Config`, after which
+    /// `calc_mode()` returns it.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn set_calc_mode(&self, calc_mode: CalcMode) -> io::Result<()> {
         self.send_command(Command::SetCalcMode(calc_mode))
     }
 
     /// Sets the spectrum analyzer's input stage.
-    #[tracing::instrument(skip(self))]
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn set_input_stage(&self, input_stage: InputStage) -> io::Result<()> {
         self.send_command(Command::SetInputStage(input_stage))
     }
 
+    /// Starts automatically switching the input stage between the LNA, direct, and attenuator stages
+    /// based on the peak amplitude of each sweep.
+    ///
+    /// Each switch is reported to the input stage switch callback and counted in
+    /// `auto_input_ranging_stats`. Returns an error if the RF Explorer hasn't reported an input
+    /// stage, since models without one can't switch it.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn enable_auto_input_ranging(&self, policy: AutoRangingPolicy) -> Result<()> {
+        if self.input_stage().is_none() {
+            return Err(Error::InvalidOperation(
+                "This RF Explorer hasn't reported an input stage, so it can't be switched automatically"
+                    .to_string(),
+            ));
+        }
+
+        if !matches!(
+            policy.lower_peak_dbm.partial_cmp(&policy.upper_peak_dbm),
+            Some(std::cmp::Ordering::Less)
+        ) {
+            return Err(Error::InvalidInput(
+                "The lower peak amplitude must be less than the upper peak amplitude".to_string(),
+            ));
+        }
+
+        *self.messages().auto_input_ranging.lock().unpoisoned() = Some(AutoRanger::new(policy));
+        let generation = self
+            .messages()
+            .auto_input_ranging_generation
+            .fetch_add(1, Ordering::SeqCst)
+            + 1;
+        let messages = self.rfe.messages_weak();
+        let serial_port = self.rfe.serial_port_weak();
+        thread::spawn(move || run_auto_input_ranging(messages, serial_port, generation));
+        Ok(())
+    }
+
+    /// Stops automatically switching the input stage.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn disable_auto_input_ranging(&self) {
+        *self.messages().auto_input_ranging.lock().unpoisoned() = None;
+        self.messages()
+            .auto_input_ranging_generation
+            .fetch_add(1, Ordering::SeqCst);
+        self.messages().sweep.1.notify_all();
+    }
+
+    /// Returns statistics about automatic input ranging, or `None` if it isn't enabled.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn auto_input_ranging_stats(&self) -> Option<AutoRangingStats> {
+        self.messages()
+            .auto_input_ranging
+            .lock()
+            .unpoisoned()
+            .as_ref()
+            .map(|auto_ranger| auto_ranger.stats)
+    }
+
+    /// Sets the callback that is called when automatic input ranging switches the input stage.
+    #[tracing::instrument(skip(self, cb), target = "rfe::spectrum_analyzer")]
+    pub fn set_input_stage_switch_callback(
+        &self,
+        cb: impl FnMut(InputStageSwitch) + Send + 'static,
+    ) {
+        *self
+            .messages()
+            .input_stage_switch_callback
+            .lock()
+            .unpoisoned() = Some(Box::new(cb));
+    }
+
+    /// Removes the callback that is called when automatic input ranging switches the input stage.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn remove_input_stage_switch_callback(&self) {
+        *self
+            .messages()
+            .input_stage_switch_callback
+            .lock()
+            .unpoisoned() = None;
+    }
+
+    /// Captures one frame of `ScreenData` every `interval`, keeping the screen dump disabled in
+    /// between so that it doesn't slow down sweeps.
+    ///
+    /// Each cycle enables the screen dump, waits for the next frame, and disables the screen dump
+    /// again. Frames can be read with `screen_data` or `wait_for_next_screen_data`. Errors during
+    /// a cycle are passed to the error callback. Passing `None` stops capturing frames.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn set_screenshot_interval(&self, interval: Option<Duration>) {
+        let generation = self
+            .messages()
+            .screenshot_generation
+            .fetch_add(1, Ordering::SeqCst)
+            + 1;
+        let Some(interval) = interval else {
+            return;
+        };
+
+        let messages = self.rfe.messages_weak();
+        let serial_port = self.rfe.serial_port_weak();
+        thread::spawn(move || run_screenshot_cycle(messages, serial_port, generation, interval));
+    }
+
+    /// Sets the callback that is called with errors that occur in background tasks, such as the
+    /// screenshot cycle started by `set_screenshot_interval`, and with messages from the RF
+    /// Explorer that fail to parse.
+    #[tracing::instrument(skip(self, cb), target = "rfe::spectrum_analyzer")]
+    pub fn set_error_callback(&self, cb: impl FnMut(Error) + Send + 'static) {
+        *self.messages().error_callback.lock().unpoisoned() = Some(Box::new(cb));
+    }
+
+    /// Removes the callback that is called with errors that occur in background tasks.
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
+    pub fn remove_error_callback(&self) {
+        *self.messages().error_callback.lock().unpoisoned() = None;
+    }
+
     /// Adds or subtracts an offset to the amplitudes in each sweep.
-    #[tracing::instrument(skip(self))]
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn set_offset_db(&self, offset_db: i8) -> io::Result<()> {
         self.send_command(Command::SetOffsetDB(offset_db))
     }
 
     /// Sets the spectrum analyzer's DSP mode.
-    #[tracing::instrument(skip(self))]
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer")]
     pub fn set_dsp_mode(&self, dsp_mode: DspMode) -> Result<()> {
         // Check to see if the DspMode is already set to the desired value
-        if *self.messages().dsp_mode.0.lock().unwrap() == Some(dsp_mode) {
+        if *self.messages().dsp_mode.0.lock().unpoisoned() == Some(dsp_mode) {
             return Ok(());
         }
 
         // Send the command to set the DSP mode
+        ensure_not_reader_thread()?;
         self.send_command(Command::SetDsp(dsp_mode))?;
 
         // Wait to see if we receive a DSP mode message in response
         let (lock, condvar) = &self.messages().dsp_mode;
-        let (_, wait_result) = condvar
+        let (_guard, wait_result) = condvar
             .wait_timeout_while(
-                lock.lock().unwrap(),
+                lock.lock().unpoisoned(),
                 COMMAND_RESPONSE_TIMEOUT,
                 |new_dsp_mode| *new_dsp_mode != Some(dsp_mode),
             )
-            .unwrap();
+            .unpoisoned();
 
         if !wait_result.timed_out() {
             Ok(())
@@ -712,14 +1965,19 @@ impl SpectrumAnalyzer {
     fn wait_for_config_while(
         &self,
         condition: impl FnMut(&mut Option<Config>) -> bool,
-    ) -> (MutexGuard<Option<Config>>, WaitTimeoutResult) {
+    ) -> Result<(MutexGuard<'_, Option<Config>>, WaitTimeoutResult)> {
+        ensure_not_reader_thread()?;
         let (lock, condvar) = &self.messages().config;
-        condvar
-            .wait_timeout_while(lock.lock().unwrap(), COMMAND_RESPONSE_TIMEOUT, condition)
-            .unwrap()
+        Ok(condvar
+            .wait_timeout_while(
+                lock.lock().unpoisoned(),
+                COMMAND_RESPONSE_TIMEOUT,
+                condition,
+            )
+            .unpoisoned())
     }
 
-    #[tracing::instrument(skip(self), ret, err)]
+    #[tracing::instrument(skip(self), target = "rfe::spectrum_analyzer", ret, err)]
     fn validate_start_stop(&self, start: Frequency, stop: Frequency) -> Result<()> {
         if start >= stop {
             return Err(Error::InvalidInput(
@@ -727,9 +1985,7 @@ impl SpectrumAnalyzer {
             ));
         }
 
-        let active_model = self.active_radio_model();
-
-        let min_max_freq = active_model.min_freq()..=active_model.max_freq();
+        let min_max_freq = self.active_frequency_range();
         if !min_max_freq.contains(&start) {
             return Err(Error::InvalidInput(format!(
                     "The start frequency {} MHz is not within the RF Explorer's frequency range of {}-{} MHz",
@@ -746,7 +2002,7 @@ impl SpectrumAnalyzer {
                 )));
         }
 
-        let min_max_span = active_model.min_span()..=active_model.max_span();
+        let min_max_span = self.active_span_range();
         if !min_max_span.contains(&(stop - start)) {
             return Err(Error::InvalidInput(format!(
                 "The span {} MHz is not within the RF Explorer's span range of {}-{} MHz",
@@ -759,8 +2015,8 @@ impl SpectrumAnalyzer {
         Ok(())
     }
 
-    #[tracing::instrument(skip(self), ret, err)]
-    fn validate_min_max_amps(&self, min_amp_dbm: i16, max_amp_dbm: i16) -> Result<()> {
+    #[tracing::instrument(target = "rfe::spectrum_analyzer", ret, err)]
+    fn validate_min_max_amps(min_amp_dbm: i16, max_amp_dbm: i16) -> Result<()> {
         // The bottom amplitude must be less than the top amplitude
         if min_amp_dbm >= max_amp_dbm {
             error!("");
@@ -786,104 +2042,587 @@ impl SpectrumAnalyzer {
             )));
         }
 
+        // The firmware adjusts amplitude ranges that are too narrow to display
+        if max_amp_dbm - min_amp_dbm < Self::MIN_AMP_RANGE_DB {
+            return Err(Error::InvalidInput(format!(
+                "The maximum amplitude must be at least {} dB greater than the minimum amplitude",
+                Self::MIN_AMP_RANGE_DB
+            )));
+        }
+
         Ok(())
     }
 }
 
+type SweepCallback = Option<Box<dyn FnMut(&[f32]) + Send>>;
+type ModeChangeCallback = Option<Box<dyn FnMut(Mode, Mode) + Send>>;
+type ConfigChangeCallback = Option<Box<dyn FnMut(Config, Config) + Send>>;
+type OverloadCallback = Option<(f32, Box<dyn FnMut(&[usize]) + Send>)>;
+
+/// Replaces the callback in `slot`, which is called with `call_unlocked`.
+fn replace_callback<C>(slot: &Mutex<Option<C>>, generation: &AtomicU64, cb: Option<C>) {
+    let mut slot = slot.lock().unpoisoned();
+    *slot = cb;
+    generation.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Calls the callback in `slot` without holding its lock, so that the callback can replace or
+/// remove itself, then puts it back unless it was replaced or removed while it ran.
+fn call_unlocked<C>(
+    slot: &Mutex<Option<C>>,
+    generation: &AtomicU64,
+    call: impl FnOnce(&mut C) -> Result<()>,
+) -> Result<()> {
+    let (mut cb, cb_generation) = {
+        let mut slot = slot.lock().unpoisoned();
+        let Some(cb) = slot.take() else {
+            return Ok(());
+        };
+        (cb, generation.load(Ordering::Relaxed))
+    };
+    let result = call(&mut cb);
+    let mut slot = slot.lock().unpoisoned();
+    if generation.load(Ordering::Relaxed) == cb_generation {
+        *slot = Some(cb);
+    }
+    result
+}
+
+/// The longest the automatic input ranging thread waits before checking if it should stop.
+const AUTO_INPUT_RANGING_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Switches the input stage based on each new sweep until automatic input ranging is disabled or
+/// re-enabled, or the device is dropped.
+fn run_auto_input_ranging(
+    messages: Weak<MessageContainer>,
+    serial_port: Weak<SerialPort>,
+    generation: u64,
+) {
+    let mut last_sweep_seq = None;
+    let mut last_reported_input_stage = None;
+    let mut input_stage = None;
+    loop {
+        let Some(messages) = messages.upgrade() else {
+            return;
+        };
+        if messages
+            .auto_input_ranging_generation
+            .load(Ordering::SeqCst)
+            != generation
+        {
+            return;
+        }
+
+        let (lock, condvar) = &messages.sweep;
+        let sweep_guard = lock.lock().unpoisoned();
+        let sweep_seq = messages.sweep_seq.load(Ordering::Relaxed);
+        if *last_sweep_seq.get_or_insert(sweep_seq) == sweep_seq {
+            let _ = condvar
+                .wait_timeout(sweep_guard, AUTO_INPUT_RANGING_POLL_INTERVAL)
+                .unpoisoned();
+            continue;
+        }
+        last_sweep_seq = Some(sweep_seq);
+        let Some(sweep) = sweep_guard.clone() else {
+            continue;
+        };
+        drop(sweep_guard);
+
+        // Follow input stage changes reported by the RF Explorer, e.g. if it was changed manually
+        let reported_input_stage = *messages.input_stage.0.lock().unpoisoned();
+        if reported_input_stage != last_reported_input_stage {
+            last_reported_input_stage = reported_input_stage;
+            input_stage = reported_input_stage.or(input_stage);
+        }
+        let Some(current_input_stage) = input_stage else {
+            continue;
+        };
+
+        let switch = match messages.auto_input_ranging.lock().unpoisoned().as_mut() {
+            Some(auto_ranger) => auto_ranger.observe(&sweep, current_input_stage),
+            None => return,
+        };
+        let Some(switch) = switch else {
+            continue;
+        };
+
+        let Some(serial_port) = serial_port.upgrade() else {
+            return;
+        };
+        if let Err(error) = serial_port.send_command(Cow::from(Command::SetInputStage(switch.to))) {
+            warn!(%error, "Failed to switch the input stage");
+            continue;
+        }
+        info!(from = %switch.from, to = %switch.to, switch.peak_dbm, "Switched the input stage");
+        input_stage = Some(switch.to);
+
+        let result = match *messages.input_stage_switch_callback.lock().unpoisoned() {
+            Some(ref mut cb) => call_callback("Input stage switch", || cb(switch)),
+            None => Ok(()),
+        };
+        messages.report_callback_result(result);
+    }
+}
+
+const SCREENSHOT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+fn run_screenshot_cycle(
+    messages_weak: Weak<MessageContainer>,
+    serial_port_weak: Weak<SerialPort>,
+    generation: u64,
+    interval: Duration,
+) {
+    let is_current = |messages: &MessageContainer| {
+        messages.screenshot_generation.load(Ordering::SeqCst) == generation
+    };
+    loop {
+        let (Some(messages), Some(serial_port)) =
+            (messages_weak.upgrade(), serial_port_weak.upgrade())
+        else {
+            return;
+        };
+        if !is_current(&messages) {
+            return;
+        }
+
+        if let Err(error) = capture_screenshot(&messages, &serial_port) {
+            warn!(%error, "Failed to capture a screenshot");
+            messages.report_error(error);
+        }
+        drop((messages, serial_port));
+
+        // Sleep in short intervals so that changes to the screenshot interval take effect quickly
+        let next_screenshot = Instant::now() + interval;
+        while let Some(remaining) = next_screenshot
+            .checked_duration_since(Instant::now())
+            .filter(|remaining| !remaining.is_zero())
+        {
+            thread::sleep(remaining.min(SCREENSHOT_POLL_INTERVAL));
+            if !messages_weak
+                .upgrade()
+                .is_some_and(|messages| is_current(&messages))
+            {
+                return;
+            }
+        }
+    }
+}
+
+/// Enables the screen dump, waits for the next `ScreenData`, and disables the screen dump.
+fn capture_screenshot(messages: &MessageContainer, serial_port: &SerialPort) -> Result<()> {
+    let (lock, condvar) = &messages.screen_data;
+    let last_screen_data_seq = messages.screen_data_seq.load(Ordering::Relaxed);
+    serial_port.send_command(Cow::from(crate::rf_explorer::Command::EnableDumpScreen))?;
+    let (screen_data, wait_result) = condvar
+        .wait_timeout_while(lock.lock().unpoisoned(), NEXT_SCREEN_DATA_TIMEOUT, |_| {
+            messages.screen_data_seq.load(Ordering::Relaxed) == last_screen_data_seq
+        })
+        .unpoisoned();
+    drop(screen_data);
+
+    // Disable the screen dump even if no screen data was received
+    let disable_result =
+        serial_port.send_command(Cow::from(crate::rf_explorer::Command::DisableDumpScreen));
+    if wait_result.timed_out() {
+        return Err(Error::TimedOut(NEXT_SCREEN_DATA_TIMEOUT));
+    }
+    disable_result.map_err(Error::from)
+}
+
+/// Waits for the next message of any type to be cached.
+fn wait_for_any_message(messages: &MessageContainer, timeout: Duration) -> Result<Message> {
+    let (lock, condvar) = &messages.any_message;
+    let any_message = lock.lock().unpoisoned();
+    messages.any_message_waiters.fetch_add(1, Ordering::Relaxed);
+    let any_message_seq = messages.any_message_seq.load(Ordering::Relaxed);
+    let (mut any_message, wait_result) = condvar
+        .wait_timeout_while(any_message, timeout, |_| {
+            messages.any_message_seq.load(Ordering::Relaxed) == any_message_seq
+        })
+        .unpoisoned();
+
+    let message = match &*any_message {
+        Some(message) if !wait_result.timed_out() => Ok(message.clone()),
+        _ => Err(Error::TimedOut(timeout)),
+    };
+    // The last waiter drops the message so a large sweep isn't kept around
+    if messages.any_message_waiters.fetch_sub(1, Ordering::Relaxed) == 1 {
+        *any_message = None;
+    }
+    message
+}
+
+/// Returns a clone of the message if its sequence number is newer than the last one seen.
+///
+/// The message's lock must be held so that the message and sequence number are consistent.
+fn try_next<T: Clone>(message: &Option<T>, seq: &AtomicU64, last_seen_seq: &mut u64) -> Option<T> {
+    let seq = seq.load(Ordering::Relaxed);
+    if seq == *last_seen_seq {
+        return None;
+    }
+
+    let message = message.clone()?;
+    *last_seen_seq = seq;
+    Some(message)
+}
+
+/// Records frequencies passed as `impl Into<Frequency>` on the current span, since they can't be
+/// recorded as arguments.
+fn record_freq_fields<const N: usize>(fields: [(&str, Frequency); N]) {
+    let span = tracing::Span::current();
+    for (name, freq) in fields {
+        span.record(name, tracing::field::debug(freq));
+    }
+}
+
 #[derive(Default)]
 struct MessageContainer {
     pub(crate) config: (Mutex<Option<Config>>, Condvar),
     pub(crate) config_callback: Mutex<Option<Box<dyn FnMut() + Send>>>,
+    pub(crate) mode_change_callback: Mutex<ModeChangeCallback>,
+    pub(crate) config_change_callback: Mutex<ConfigChangeCallback>,
     pub(crate) sweep: (Mutex<Option<Sweep>>, Condvar),
-    pub(crate) sweep_callback: Mutex<Option<Box<dyn FnMut(&[f32]) + Send>>>,
+    pub(crate) sweep_callback: Mutex<SweepCallback>,
+    pub(crate) overload_callback: Mutex<OverloadCallback>,
+    // Incremented while the callback's lock is held whenever the callback is set or removed
+    pub(crate) sweep_callback_generation: AtomicU64,
+    pub(crate) overload_callback_generation: AtomicU64,
     pub(crate) screen_data: (Mutex<Option<ScreenData>>, Condvar),
     pub(crate) dsp_mode: (Mutex<Option<DspMode>>, Condvar),
     pub(crate) tracking_status: (Mutex<Option<TrackingStatus>>, Condvar),
+    // Set when a tracking step is requested and cleared when the resulting sweep is received
+    pub(crate) tracking_step_pending: AtomicBool,
     pub(crate) input_stage: (Mutex<Option<InputStage>>, Condvar),
     pub(crate) setup_info: (Mutex<Option<SetupInfo>>, Condvar),
+    pub(crate) is_other_device_type: AtomicBool,
     pub(crate) serial_number: (Mutex<Option<SerialNumber>>, Condvar),
+    pub(crate) message_log: Mutex<Option<MessageLog>>,
+    // Only cloned into while a caller is waiting for the next message of any type
+    pub(crate) any_message: (Mutex<Option<Message>>, Condvar),
+    pub(crate) any_message_waiters: AtomicUsize,
+    pub(crate) any_message_seq: AtomicU64,
+    pub(crate) sweep_history: Mutex<SweepHistory>,
+    pub(crate) max_hold_enabled: AtomicBool,
+    pub(crate) max_hold_sweep: Mutex<Option<Sweep>>,
+    pub(crate) max_hold_accepts_processed: AtomicBool,
+    pub(crate) max_hold_skipped_processed: AtomicBool,
+    pub(crate) baud_rate: AtomicU32,
+    pub(crate) sweep_len_mismatch_policy: Mutex<SweepLenMismatchPolicy>,
+    pub(crate) wifi_region: Mutex<WifiRegion>,
+    pub(crate) last_message_at: Mutex<Option<Instant>>,
+    pub(crate) sweep_rate: Mutex<SweepRate>,
+    #[cfg(feature = "tokio")]
+    pub(crate) config_watch: tokio::sync::watch::Sender<Option<Config>>,
+    #[cfg(feature = "tokio")]
+    pub(crate) sweep_watch: tokio::sync::watch::Sender<Option<Sweep>>,
+    pub(crate) suppressed_sweep_count: AtomicU64,
+    pub(crate) auto_input_ranging: Mutex<Option<AutoRanger>>,
+    pub(crate) auto_input_ranging_generation: AtomicU64,
+    pub(crate) input_stage_switch_callback: Mutex<Callback<InputStageSwitch>>,
+    pub(crate) screenshot_generation: AtomicU64,
+    pub(crate) error_callback: Mutex<Callback<Error>>,
+    // Incremented while the corresponding message's lock is held so that readers see a
+    // consistent message and sequence number
+    pub(crate) config_seq: AtomicU64,
+    pub(crate) sweep_seq: AtomicU64,
+    pub(crate) screen_data_seq: AtomicU64,
+    // The number of sweeps received, including ones that failed to parse or were suppressed
+    pub(crate) sweep_frame_count: AtomicU64,
+}
+
+impl MessageContainer {
+    /// Passes an error to the error callback, if one is set.
+    ///
+    /// This must not be called while holding another callback's lock or a message's lock, since the
+    /// error callback may replace that callback or read that message.
+    fn report_error(&self, error: Error) {
+        if let Some(ref mut cb) = *self.error_callback.lock().unpoisoned() {
+            // A panicking error callback is only logged since reporting it would call it again
+            let _ = call_callback("Error", || cb(error));
+        }
+    }
+
+    /// Reports a callback's panic to the error callback.
+    fn report_callback_result(&self, result: Result<()>) {
+        if let Err(error) = result {
+            self.report_error(error);
+        }
+    }
 }
 
 impl crate::common::MessageContainer for MessageContainer {
     type Message = super::Message;
+    const DEVICE_TYPE: DeviceType = DeviceType::SpectrumAnalyzer;
 
     fn cache_message(&self, message: Self::Message) {
+        tracing::trace!(
+            prefix = String::from_utf8_lossy(message.prefix()).as_ref(),
+            "Caching message"
+        );
+        let received_at = Instant::now();
+        *self.last_message_at.lock().unpoisoned() = Some(received_at);
+        if let Some(ref mut message_log) = *self.message_log.lock().unpoisoned() {
+            message_log.push(message.clone(), received_at);
+        }
+        if self.any_message_waiters.load(Ordering::Relaxed) > 0 {
+            let mut any_message = self.any_message.0.lock().unpoisoned();
+            *any_message = Some(message.clone());
+            self.any_message_seq.fetch_add(1, Ordering::Relaxed);
+            drop(any_message);
+            self.any_message.1.notify_all();
+        }
         match message {
             Self::Message::Config(config) => {
-                *self.config.0.lock().unwrap() = Some(config);
-                self.config.1.notify_one();
-                if let Some(ref mut cb) = *self.config_callback.lock().unwrap() {
-                    cb();
+                let mut cached_config = self.config.0.lock().unpoisoned();
+                let previous_mode = cached_config.as_ref().map(|config| config.mode);
+                let mode = config.mode;
+                let config_change = cached_config
+                    .as_ref()
+                    .filter(|cached_config| !cached_config.eq_ignoring_timestamp(&config))
+                    .map(|cached_config| (cached_config.clone(), config.clone()));
+                // Sweeps measured with different settings shouldn't be mixed in the history
+                if config_change.is_some() {
+                    self.sweep_history.lock().unpoisoned().clear();
+                    *self.max_hold_sweep.lock().unpoisoned() = None;
+                }
+                #[cfg(feature = "tokio")]
+                self.config_watch.send_replace(Some(config.clone()));
+                *cached_config = Some(config);
+                self.config_seq.fetch_add(1, Ordering::Relaxed);
+                drop(cached_config);
+                self.config.1.notify_all();
+                let result = match *self.config_callback.lock().unpoisoned() {
+                    Some(ref mut cb) => call_callback("Config", cb),
+                    None => Ok(()),
+                };
+                self.report_callback_result(result);
+                if let Some(previous_mode) = previous_mode.filter(|previous| *previous != mode) {
+                    let result = match *self.mode_change_callback.lock().unpoisoned() {
+                        Some(ref mut cb) => {
+                            call_callback("Mode change", || cb(previous_mode, mode))
+                        }
+                        None => Ok(()),
+                    };
+                    self.report_callback_result(result);
+                }
+                if let Some((previous_config, config)) = config_change {
+                    let result = match *self.config_change_callback.lock().unpoisoned() {
+                        Some(ref mut cb) => {
+                            call_callback("Config change", || cb(previous_config, config))
+                        }
+                        None => Ok(()),
+                    };
+                    self.report_callback_result(result);
                 }
             }
-            Self::Message::Sweep(sweep) => {
-                *self.sweep.0.lock().unwrap() = Some(sweep);
-                self.sweep.1.notify_one();
-                if let Some(ref mut cb) = *self.sweep_callback.lock().unwrap() {
-                    if let Some(ref sweep) = *self.sweep.0.lock().unwrap() {
-                        cb(sweep.amplitudes_dbm.as_slice());
+            Self::Message::Sweep(mut sweep) => {
+                self.sweep_rate.lock().unpoisoned().record(received_at);
+                let sequence = self.sweep_frame_count.fetch_add(1, Ordering::Relaxed) + 1;
+                // Tag the sweep with the radio module that was active when it was received
+                let config = self.config.0.lock().unpoisoned();
+                sweep.radio_module = config.as_ref().map(|config| {
+                    if config.is_expansion_radio_module_active {
+                        RadioModule::Expansion
+                    } else {
+                        RadioModule::Main
+                    }
+                });
+                sweep.matched_config = config.as_ref().map(|config| sweep.matches_config(config));
+                sweep.calc_mode = config.as_ref().and_then(|config| config.calc_mode);
+                sweep.estimated_latency = Sweep::estimate_latency(
+                    sweep.len(),
+                    self.baud_rate.load(Ordering::Relaxed),
+                    config.as_ref().and_then(|config| config.rbw),
+                );
+                let max_amp_dbm = config.as_ref().map(|config| config.max_amp_dbm);
+                drop(config);
+                if sweep.matched_config == Some(false)
+                    && *self.sweep_len_mismatch_policy.lock().unpoisoned()
+                        == SweepLenMismatchPolicy::Suppress
+                {
+                    self.suppressed_sweep_count.fetch_add(1, Ordering::Relaxed);
+                    trace!(
+                        sweep_len = sweep.len(),
+                        "Suppressed a sweep that doesn't match the config"
+                    );
+                    return;
+                }
+                sweep.sequence = Some(sequence);
+                self.sweep_history.lock().unpoisoned().push(&sweep);
+                if self.max_hold_enabled.load(Ordering::Relaxed)
+                    && sweep.is_processed()
+                    && !self.max_hold_accepts_processed.load(Ordering::Relaxed)
+                {
+                    // Only the first skipped sweep is reported to avoid an error for every sweep
+                    if !self
+                        .max_hold_skipped_processed
+                        .swap(true, Ordering::Relaxed)
+                    {
+                        warn!(
+                            "Max-hold accumulation is skipping sweeps processed by the RF Explorer"
+                        );
+                        let calc_mode = sweep.calc_mode.unwrap_or_default();
+                        self.report_error(Error::InvalidOperation(format!(
+                            "Max-hold accumulation skipped sweeps already processed by the RF Explorer's {calc_mode} calc mode"
+                        )));
+                    }
+                } else if self.max_hold_enabled.load(Ordering::Relaxed) {
+                    let mut max_hold_sweep = self.max_hold_sweep.lock().unpoisoned();
+                    match *max_hold_sweep {
+                        Some(ref mut max_hold_sweep) => max_hold_sweep.accumulate_max_hold(&sweep),
+                        None => *max_hold_sweep = Some(sweep.clone()),
                     }
                 }
+                #[cfg(feature = "tokio")]
+                self.sweep_watch.send_replace(Some(sweep.clone()));
+                let mut cached_sweep = self.sweep.0.lock().unpoisoned();
+                *cached_sweep = Some(sweep);
+                self.tracking_step_pending.store(false, Ordering::Relaxed);
+                self.sweep_seq.fetch_add(1, Ordering::Relaxed);
+                drop(cached_sweep);
+                // Notify every waiter since automatic input ranging may be waiting alongside users
+                self.sweep.1.notify_all();
+                // The callbacks are called without holding their locks or the sweep's lock, so that
+                // they can read the sweep and replace or remove themselves
+                let result = call_unlocked(
+                    &self.sweep_callback,
+                    &self.sweep_callback_generation,
+                    |cb| {
+                        let amplitudes_dbm = self
+                            .sweep
+                            .0
+                            .lock()
+                            .unpoisoned()
+                            .as_ref()
+                            .map(|sweep| sweep.amplitudes_dbm.clone());
+                        match amplitudes_dbm {
+                            Some(amplitudes_dbm) => call_callback("Sweep", || cb(&amplitudes_dbm)),
+                            None => Ok(()),
+                        }
+                    },
+                );
+                self.report_callback_result(result);
+                let result = call_unlocked(
+                    &self.overload_callback,
+                    &self.overload_callback_generation,
+                    |(threshold, cb)| {
+                        let clipping_bins = match (&*self.sweep.0.lock().unpoisoned(), max_amp_dbm)
+                        {
+                            (Some(sweep), Some(max_amp_dbm)) => {
+                                let clipping_bins = sweep.clipping_bins(max_amp_dbm);
+                                (!sweep.is_empty()
+                                    && clipping_bins.len() as f32 / sweep.len() as f32 > *threshold)
+                                    .then_some(clipping_bins)
+                            }
+                            _ => None,
+                        };
+                        match clipping_bins {
+                            Some(clipping_bins) => call_callback("Overload", || cb(&clipping_bins)),
+                            None => Ok(()),
+                        }
+                    },
+                );
+                self.report_callback_result(result);
             }
             Self::Message::ScreenData(screen_data) => {
-                *self.screen_data.0.lock().unwrap() = Some(screen_data);
-                self.screen_data.1.notify_one();
+                let mut cached_screen_data = self.screen_data.0.lock().unpoisoned();
+                *cached_screen_data = Some(screen_data);
+                self.screen_data_seq.fetch_add(1, Ordering::Relaxed);
+                drop(cached_screen_data);
+                // Notify every waiter since the screenshot cycle may be waiting alongside users
+                self.screen_data.1.notify_all();
             }
             Self::Message::DspMode(dsp_mode) => {
-                *self.dsp_mode.0.lock().unwrap() = Some(dsp_mode);
-                self.dsp_mode.1.notify_one();
+                *self.dsp_mode.0.lock().unpoisoned() = Some(dsp_mode);
+                self.dsp_mode.1.notify_all();
             }
             Self::Message::InputStage(input_stage) => {
-                *self.input_stage.0.lock().unwrap() = Some(input_stage);
-                self.input_stage.1.notify_one();
+                *self.input_stage.0.lock().unpoisoned() = Some(input_stage);
+                self.input_stage.1.notify_all();
             }
             Self::Message::TrackingStatus(tracking_status) => {
-                *self.tracking_status.0.lock().unwrap() = Some(tracking_status);
-                self.tracking_status.1.notify_one();
+                *self.tracking_status.0.lock().unpoisoned() = Some(tracking_status);
+                self.tracking_status.1.notify_all();
             }
             Self::Message::SerialNumber(serial_number) => {
-                *self.serial_number.0.lock().unwrap() = Some(serial_number);
-                self.serial_number.1.notify_one();
+                *self.serial_number.0.lock().unpoisoned() = Some(serial_number);
+                self.serial_number.1.notify_all();
             }
             Self::Message::SetupInfo(setup_info) => {
-                *self.setup_info.0.lock().unwrap() = Some(setup_info);
-                self.setup_info.1.notify_one();
+                *self.setup_info.0.lock().unpoisoned() = Some(setup_info);
+                self.setup_info.1.notify_all();
             }
         }
     }
 
+    fn baud_rate_changed(&self, baud_rate: u32) {
+        self.baud_rate.store(baud_rate, Ordering::Relaxed);
+    }
+
+    fn message_parse_failed(&self, message: &[u8], error: Error) {
+        // Sweeps that fail to parse are still numbered so that they show up as gaps
+        if Sweep::has_prefix(message) {
+            self.sweep_frame_count.fetch_add(1, Ordering::Relaxed);
+        }
+        self.report_error(error);
+    }
+
+    fn other_device_type_found(&self) {
+        self.is_other_device_type.store(true, Ordering::Relaxed);
+        // The locks are taken before notifying so that a waiter can't miss the notification
+        // between checking the flag and waiting
+        drop(self.config.0.lock().unpoisoned());
+        self.config.1.notify_all();
+        drop(self.setup_info.0.lock().unpoisoned());
+        self.setup_info.1.notify_all();
+    }
+
     fn wait_for_device_info(&self) -> ConnectionResult<()> {
         let (config_lock, config_cvar) = &self.config;
         let (setup_info_lock, setup_info_cvar) = &self.setup_info;
 
         // Check to see if we've already received a Config and SetupInfo
-        if config_lock.lock().unwrap().is_some() && setup_info_lock.lock().unwrap().is_some() {
+        if config_lock.lock().unpoisoned().is_some()
+            && setup_info_lock.lock().unpoisoned().is_some()
+        {
             return Ok(());
         }
 
         // Wait to see if we receive a Config and SetupInfo before timing out
-        if config_cvar
+        let config_received = config_cvar
             .wait_timeout_while(
-                config_lock.lock().unwrap(),
+                config_lock.lock().unpoisoned(),
                 RECEIVE_INITIAL_DEVICE_INFO_TIMEOUT,
-                |config| config.is_none(),
+                |config| config.is_none() && !self.is_other_device_type.load(Ordering::Relaxed),
             )
-            .unwrap()
+            .unpoisoned()
             .0
-            .is_some()
-            && setup_info_cvar
+            .is_some();
+
+        // Don't wait for the SetupInfo if the Config never arrived since the device isn't responding
+        let setup_info_received = if config_received {
+            setup_info_cvar
                 .wait_timeout_while(
-                    setup_info_lock.lock().unwrap(),
+                    setup_info_lock.lock().unpoisoned(),
                     RECEIVE_INITIAL_DEVICE_INFO_TIMEOUT,
-                    |setup_info| setup_info.is_none(),
+                    |setup_info| {
+                        setup_info.is_none() && !self.is_other_device_type.load(Ordering::Relaxed)
+                    },
                 )
-                .unwrap()
+                .unpoisoned()
                 .0
                 .is_some()
-        {
+        } else {
+            setup_info_lock.lock().unpoisoned().is_some()
+        };
+
+        if config_received && setup_info_received {
             Ok(())
         } else {
-            Err(ConnectionError::DeviceInfoNotReceived)
+            Err(ConnectionError::InitializationTimeout {
+                missing_config: !config_received,
+                missing_setup_info: !setup_info_received,
+            })
         }
     }
 }
@@ -891,14 +2630,731 @@ impl crate::common::MessageContainer for MessageContainer {
 impl Debug for MessageContainer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("MessageContainer")
-            .field("config", &self.config.0.lock().unwrap())
-            .field("sweep", &self.sweep.0.lock().unwrap())
-            .field("screen_data", &self.screen_data.0.lock().unwrap())
-            .field("dsp_mode", &self.dsp_mode.0.lock().unwrap())
-            .field("tracking_status", &self.tracking_status.0.lock().unwrap())
-            .field("input_stage", &self.input_stage.0.lock().unwrap())
-            .field("setup_info", &self.setup_info.0.lock().unwrap())
-            .field("serial_number", &self.serial_number.0.lock().unwrap())
+            .field("config", &self.config.0.lock().unpoisoned())
+            .field("sweep", &self.sweep.0.lock().unpoisoned())
+            .field("screen_data", &self.screen_data.0.lock().unpoisoned())
+            .field("dsp_mode", &self.dsp_mode.0.lock().unpoisoned())
+            .field(
+                "tracking_status",
+                &self.tracking_status.0.lock().unpoisoned(),
+            )
+            .field("input_stage", &self.input_stage.0.lock().unpoisoned())
+            .field("setup_info", &self.setup_info.0.lock().unpoisoned())
+            .field("serial_number", &self.serial_number.0.lock().unpoisoned())
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::sync::Arc;
+    use crate::common::test_support::sweep;
+    use crate::common::MessageContainer as _;
+    use crate::spectrum_analyzer::{Message, SweepGapDetector};
+
+    const STRESS_WAITERS: usize = 16;
+    const STRESS_MESSAGES: u64 = 200;
+    const STRESS_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Returns the `Config` of a 6G spectrum analyzer with the given sweep length and mode.
+    fn test_config(sweep_len: u16, mode: u8) -> Config {
+        let bytes = format!(
+            "#C2-F:5249000,0196428,-030,-118,{sweep_len:04},1,{mode:03},4850000,6100000,0600000,00200,0000,000"
+        );
+        Config::try_from(bytes.as_bytes()).unwrap()
+    }
+
+    /// Blocks until every stress test waiter has checked its condition.
+    ///
+    /// Waiters check their condition while holding the message's lock and only release it by
+    /// waiting on the condvar, so messages cached afterwards can only be published while the
+    /// waiters are blocked and have to wake them.
+    fn wait_for_stress_waiters(ready: &AtomicUsize) {
+        while ready.load(Ordering::Relaxed) < STRESS_WAITERS {
+            thread::yield_now();
+        }
+    }
+
+    #[test]
+    fn every_config_waiter_is_woken() {
+        let messages = Arc::new(MessageContainer::default());
+        let ready = Arc::new(AtomicUsize::new(0));
+        let config = test_config(112, 0);
+
+        let waiters: Vec<_> = (0..STRESS_WAITERS)
+            .map(|_| {
+                let messages = messages.clone();
+                let ready = ready.clone();
+                thread::spawn(move || {
+                    let (lock, condvar) = &messages.config;
+                    let mut is_ready = false;
+                    let (_config, wait_result) = condvar
+                        .wait_timeout_while(lock.lock().unpoisoned(), STRESS_TIMEOUT, |_| {
+                            if !is_ready {
+                                is_ready = true;
+                                ready.fetch_add(1, Ordering::Relaxed);
+                            }
+                            messages.config_seq.load(Ordering::Relaxed) < STRESS_MESSAGES
+                        })
+                        .unpoisoned();
+                    !wait_result.timed_out()
+                })
+            })
+            .collect();
+
+        wait_for_stress_waiters(&ready);
+        for _ in 0..STRESS_MESSAGES {
+            messages.cache_message(Message::Config(config.clone()));
+        }
+        for waiter in waiters {
+            assert!(waiter.join().unwrap(), "A waiter missed the final Config");
+        }
+    }
+
+    #[test]
+    fn every_sweep_waiter_sees_the_latest_sweep() {
+        let messages = Arc::new(MessageContainer::default());
+        let ready = Arc::new(AtomicUsize::new(0));
+
+        let waiters: Vec<_> = (0..STRESS_WAITERS)
+            .map(|_| {
+                let messages = messages.clone();
+                let ready = ready.clone();
+                thread::spawn(move || {
+                    let (lock, condvar) = &messages.sweep;
+                    let mut is_ready = false;
+                    let (sweep, wait_result) = condvar
+                        .wait_timeout_while(lock.lock().unpoisoned(), STRESS_TIMEOUT, |sweep| {
+                            if !is_ready {
+                                is_ready = true;
+                                ready.fetch_add(1, Ordering::Relaxed);
+                            }
+                            sweep
+                                .as_ref()
+                                .is_none_or(|sweep| sweep.len() < STRESS_MESSAGES as usize)
+                        })
+                        .unpoisoned();
+                    !wait_result.timed_out()
+                        && sweep.as_ref().map(Sweep::len) == Some(STRESS_MESSAGES as usize)
+                })
+            })
+            .collect();
+
+        // A single producer caches sweeps in order, like the reader thread does
+        let producer = {
+            let messages = messages.clone();
+            let ready = ready.clone();
+            thread::spawn(move || {
+                wait_for_stress_waiters(&ready);
+                for len in 1..=STRESS_MESSAGES {
+                    messages.cache_message(Message::Sweep(sweep(&vec![-50.; len as usize])));
+                }
+            })
+        };
+        producer.join().unwrap();
+
+        for waiter in waiters {
+            assert!(waiter.join().unwrap(), "A waiter missed the final Sweep");
+        }
+    }
+
+    #[test]
+    fn wait_for_any_message_returns_the_next_message() {
+        let messages = Arc::new(MessageContainer::default());
+        messages.cache_message(Message::DspMode(DspMode::Auto));
+
+        let waiter = {
+            let messages = messages.clone();
+            thread::spawn(move || wait_for_any_message(&messages, Duration::from_secs(5)))
+        };
+        while messages.any_message_waiters.load(Ordering::Relaxed) == 0 {
+            thread::yield_now();
+        }
+        messages.cache_message(Message::DspMode(DspMode::Fast));
+
+        assert_eq!(
+            waiter.join().unwrap().unwrap(),
+            Message::DspMode(DspMode::Fast)
+        );
+        assert!(messages.any_message.0.lock().unpoisoned().is_none());
+        assert!(matches!(
+            wait_for_any_message(&messages, Duration::from_millis(10)),
+            Err(Error::TimedOut(_))
+        ));
+    }
+
+    #[test]
+    fn reject_narrow_amp_ranges() {
+        assert!(SpectrumAnalyzer::validate_min_max_amps(-60, -50).is_ok());
+        assert!(matches!(
+            SpectrumAnalyzer::validate_min_max_amps(-51, -50),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            SpectrumAnalyzer::validate_min_max_amps(i16::MIN, i16::MAX),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn tag_sweep_with_active_radio_module() {
+        let messages = MessageContainer::default();
+        messages.cache_message(Message::Sweep(Sweep::default()));
+        assert_eq!(
+            messages
+                .sweep
+                .0
+                .lock()
+                .unpoisoned()
+                .as_ref()
+                .unwrap()
+                .radio_module(),
+            None
+        );
+
+        let config = test_config(112, 0);
+        messages.cache_message(Message::Config(config));
+        messages.cache_message(Message::Sweep(Sweep::default()));
+        assert_eq!(
+            messages
+                .sweep
+                .0
+                .lock()
+                .unpoisoned()
+                .as_ref()
+                .unwrap()
+                .radio_module(),
+            Some(RadioModule::Expansion)
+        );
+    }
+
+    #[test]
+    fn number_cached_sweeps() {
+        let messages = MessageContainer::default();
+        let latest_sweep = || messages.sweep.0.lock().unpoisoned().clone().unwrap();
+        let mut detector = SweepGapDetector::new();
+
+        messages.cache_message(Message::Sweep(Sweep::default()));
+        assert_eq!(latest_sweep().sequence(), Some(1));
+        assert_eq!(detector.push(&latest_sweep()), None);
+
+        messages.cache_message(Message::Sweep(Sweep::default()));
+        messages.cache_message(Message::Sweep(Sweep::default()));
+        assert_eq!(latest_sweep().sequence(), Some(3));
+        assert_eq!(
+            detector.push(&latest_sweep()).map(|gap| gap.missing),
+            Some(2..=2)
+        );
+
+        // Sweeps that fail to parse or are suppressed are missed too
+        messages.message_parse_failed(
+            b"$S\x70\xc8",
+            Error::InvalidMessage("incomplete sweep".to_string()),
+        );
+        *messages.sweep_len_mismatch_policy.lock().unpoisoned() = SweepLenMismatchPolicy::Suppress;
+        messages.cache_message(Message::Config(Config::default()));
+        messages.cache_message(Message::Sweep(sweep(&[-100.; 2])));
+        let sweep_len = usize::from(Config::default().sweep_len);
+        messages.cache_message(Message::Sweep(sweep(&vec![-100.; sweep_len])));
+        assert_eq!(latest_sweep().sequence(), Some(6));
+        assert_eq!(
+            detector.push(&latest_sweep()).map(|gap| gap.missing),
+            Some(4..=5)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn config_watcher_receives_configs() {
+        let messages = MessageContainer::default();
+        let mut config_rx = messages.config_watch.subscribe();
+        assert!(config_rx.borrow().is_none());
+
+        let config = test_config(112, 0);
+        messages.cache_message(Message::Config(config.clone()));
+        assert!(config_rx.has_changed().unwrap());
+        assert_eq!(*config_rx.borrow_and_update(), Some(config));
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn sweep_watcher_receives_sweeps() {
+        let messages = MessageContainer::default();
+        let mut sweep_rx = messages.sweep_watch.subscribe();
+        assert!(sweep_rx.borrow().is_none());
+
+        messages.cache_message(Message::Sweep(sweep(&[-50., -60.])));
+        assert!(sweep_rx.has_changed().unwrap());
+        assert_eq!(
+            sweep_rx
+                .borrow_and_update()
+                .as_ref()
+                .map(|sweep| sweep.amplitudes_dbm().to_vec()),
+            Some(vec![-50., -60.])
+        );
+    }
+
+    #[test]
+    fn call_mode_change_callback_only_when_mode_changes() {
+        let messages = MessageContainer::default();
+        let transitions = Arc::new(Mutex::new(Vec::new()));
+        let cb_transitions = transitions.clone();
+        *messages.mode_change_callback.lock().unpoisoned() =
+            Some(Box::new(move |previous, mode| {
+                cb_transitions.lock().unpoisoned().push((previous, mode))
+            }));
+
+        messages.cache_message(Message::Config(test_config(112, 0)));
+        messages.cache_message(Message::Config(test_config(112, 0)));
+        messages.cache_message(Message::Config(test_config(112, 5)));
+        messages.cache_message(Message::Config(test_config(112, 5)));
+        assert_eq!(
+            *transitions.lock().unpoisoned(),
+            [(Mode::SpectrumAnalyzer, Mode::AnalyzerTracking)]
+        );
+    }
+
+    #[test]
+    fn call_config_change_callback_only_when_config_changes() {
+        let messages = MessageContainer::default();
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let cb_changes = changes.clone();
+        *messages.config_change_callback.lock().unpoisoned() =
+            Some(Box::new(move |previous: Config, config: Config| {
+                cb_changes
+                    .lock()
+                    .unpoisoned()
+                    .push((previous.sweep_len, config.sweep_len))
+            }));
+
+        messages.cache_message(Message::Config(test_config(112, 0)));
+        messages.cache_message(Message::Config(test_config(112, 0)));
+        messages.cache_message(Message::Config(test_config(240, 0)));
+        messages.cache_message(Message::Config(test_config(240, 0)));
+        assert_eq!(*changes.lock().unpoisoned(), [(112, 240)]);
+    }
+
+    #[test]
+    fn clear_sweep_history_when_config_changes() {
+        let messages = MessageContainer::default();
+        messages.sweep_history.lock().unpoisoned().set_capacity(10);
+
+        messages.cache_message(Message::Config(test_config(112, 0)));
+        messages.cache_message(Message::Sweep(Sweep::default()));
+        messages.cache_message(Message::Config(test_config(112, 0)));
+        messages.cache_message(Message::Sweep(Sweep::default()));
+        assert_eq!(messages.sweep_history.lock().unpoisoned().sweeps().len(), 2);
+
+        messages.cache_message(Message::Config(test_config(224, 0)));
+        assert!(messages
+            .sweep_history
+            .lock()
+            .unpoisoned()
+            .sweeps()
+            .is_empty());
+    }
+
+    #[test]
+    fn accumulate_max_hold_sweep_only_when_enabled() {
+        let messages = MessageContainer::default();
+        let sweep = |amplitudes_dbm: Vec<f32>| Message::Sweep(sweep(&amplitudes_dbm));
+
+        messages.cache_message(sweep(vec![-50., -60.]));
+        assert!(messages.max_hold_sweep.lock().unpoisoned().is_none());
+
+        messages.max_hold_enabled.store(true, Ordering::Relaxed);
+        messages.cache_message(sweep(vec![-70., -40.]));
+        messages.cache_message(sweep(vec![-80., -90.]));
+        assert_eq!(
+            messages
+                .max_hold_sweep
+                .lock()
+                .unpoisoned()
+                .as_ref()
+                .map(|sweep| sweep.amplitudes_dbm().to_vec()),
+            Some(vec![-70., -40.])
+        );
+        assert_eq!(
+            messages
+                .sweep
+                .0
+                .lock()
+                .unpoisoned()
+                .as_ref()
+                .map(|sweep| sweep.amplitudes_dbm().to_vec()),
+            Some(vec![-80., -90.])
+        );
+    }
+
+    #[test]
+    fn max_hold_skips_processed_sweeps_unless_accepted() {
+        let messages = MessageContainer::default();
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let errors_clone = errors.clone();
+        *messages.error_callback.lock().unpoisoned() = Some(Box::new(move |error: Error| {
+            errors_clone.lock().unpoisoned().push(error.to_string())
+        }));
+        *messages.config.0.lock().unpoisoned() = Some(Config {
+            calc_mode: Some(CalcMode::Avg),
+            ..Config::default()
+        });
+        let sweep = || Message::Sweep(sweep(&[-50., -60.]));
+
+        messages.max_hold_enabled.store(true, Ordering::Relaxed);
+        messages.cache_message(sweep());
+        messages.cache_message(sweep());
+        assert!(messages.max_hold_sweep.lock().unpoisoned().is_none());
+        assert_eq!(errors.lock().unpoisoned().len(), 1);
+        assert_eq!(
+            messages
+                .sweep
+                .0
+                .lock()
+                .unpoisoned()
+                .as_ref()
+                .and_then(Sweep::calc_mode),
+            Some(CalcMode::Avg)
+        );
+
+        messages
+            .max_hold_accepts_processed
+            .store(true, Ordering::Relaxed);
+        messages.cache_message(sweep());
+        assert!(messages.max_hold_sweep.lock().unpoisoned().is_some());
+    }
+
+    #[test]
+    fn call_overload_callback_when_clipped_fraction_exceeded() {
+        let messages = MessageContainer::default();
+        let clipping_bins = Arc::new(Mutex::new(Vec::new()));
+        let cb_clipping_bins = clipping_bins.clone();
+        *messages.overload_callback.lock().unpoisoned() = Some((
+            0.25,
+            Box::new(move |bins| cb_clipping_bins.lock().unpoisoned().push(bins.to_vec())),
+        ));
+        let config = test_config(4, 0);
+        messages.cache_message(Message::Config(config));
+
+        let sweep = |amplitudes_dbm: [f32; 4]| Message::Sweep(sweep(&amplitudes_dbm));
+        messages.cache_message(sweep([-80.0, -30.0, -80.0, -80.0]));
+        messages.cache_message(sweep([-80.0, -30.0, -25.0, -80.0]));
+        assert_eq!(*clipping_bins.lock().unpoisoned(), [vec![1, 2]]);
+    }
+
+    #[test]
+    fn callbacks_can_replace_themselves() {
+        let messages = Arc::new(MessageContainer::default());
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        replace_callback(
+            &messages.sweep_callback,
+            &messages.sweep_callback_generation,
+            Some(Box::new({
+                let messages = Arc::downgrade(&messages);
+                let calls = Arc::clone(&calls);
+                move |_| {
+                    // The callback can read the sweep and replace itself
+                    let messages = messages.upgrade().unwrap();
+                    assert!(messages.sweep.0.lock().unpoisoned().is_some());
+                    calls.lock().unpoisoned().push("first sweep");
+                    let calls = Arc::clone(&calls);
+                    replace_callback(
+                        &messages.sweep_callback,
+                        &messages.sweep_callback_generation,
+                        Some(Box::new(move |_| {
+                            calls.lock().unpoisoned().push("second sweep")
+                        })),
+                    );
+                }
+            })),
+        );
+        replace_callback(
+            &messages.overload_callback,
+            &messages.overload_callback_generation,
+            Some((
+                0.25,
+                Box::new({
+                    let messages = Arc::downgrade(&messages);
+                    let calls = Arc::clone(&calls);
+                    move |_| {
+                        let messages = messages.upgrade().unwrap();
+                        calls.lock().unpoisoned().push("overload");
+                        replace_callback(
+                            &messages.overload_callback,
+                            &messages.overload_callback_generation,
+                            None,
+                        );
+                    }
+                }),
+            )),
+        );
+        messages.cache_message(Message::Config(test_config(4, 0)));
+
+        for _ in 0..2 {
+            messages.cache_message(Message::Sweep(sweep(&[-80.0, -30.0, -25.0, -80.0])));
+        }
+        assert_eq!(
+            *calls.lock().unpoisoned(),
+            ["first sweep", "overload", "second sweep"]
+        );
+    }
+
+    #[test]
+    fn suppress_sweeps_that_dont_match_config() {
+        let messages = MessageContainer::default();
+        let config = test_config(2, 0);
+        messages.cache_message(Message::Config(config));
+        let sweep = |len: usize| Message::Sweep(sweep(&vec![-80.0; len]));
+        let matched_config = |messages: &MessageContainer| {
+            messages
+                .sweep
+                .0
+                .lock()
+                .unpoisoned()
+                .as_ref()
+                .and_then(Sweep::matched_config)
+        };
+
+        messages.cache_message(sweep(3));
+        assert_eq!(matched_config(&messages), Some(false));
+
+        *messages.sweep_len_mismatch_policy.lock().unpoisoned() = SweepLenMismatchPolicy::Suppress;
+        messages.cache_message(sweep(2));
+        messages.cache_message(sweep(3));
+        assert_eq!(matched_config(&messages), Some(true));
+        assert_eq!(messages.suppressed_sweep_count.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn clear_pending_tracking_step_when_sweep_received() {
+        let messages = MessageContainer::default();
+        messages
+            .tracking_step_pending
+            .store(true, Ordering::Relaxed);
+        messages.cache_message(Message::Sweep(Sweep::default()));
+        assert!(!messages.tracking_step_pending.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn sweep_len_for_resolution_rounds_up_to_multiple_of_16() {
+        // 10 MHz / 10 kHz = 1000 steps, so 1001 points rounded up to 1008
+        assert_eq!(
+            SpectrumAnalyzer::sweep_len_for_resolution(
+                Frequency::from_mhz(10),
+                Frequency::from_khz(10),
+                SpectrumAnalyzer::MAX_SWEEP_LEN
+            ),
+            Some(1008)
+        );
+    }
+
+    #[test]
+    fn sweep_len_for_resolution_uses_min_sweep_len() {
+        assert_eq!(
+            SpectrumAnalyzer::sweep_len_for_resolution(
+                Frequency::from_mhz(1),
+                Frequency::from_mhz(1),
+                SpectrumAnalyzer::MAX_SWEEP_LEN
+            ),
+            Some(SpectrumAnalyzer::MIN_SWEEP_LEN)
+        );
+    }
+
+    #[test]
+    fn sweep_len_for_resolution_rejects_unreachable_resolution() {
+        assert_eq!(
+            SpectrumAnalyzer::sweep_len_for_resolution(
+                Frequency::from_mhz(100),
+                Frequency::from_khz(10),
+                SpectrumAnalyzer::MIN_SWEEP_LEN
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn keep_caching_messages_after_sweep_callback_panics() {
+        let messages = MessageContainer::default();
+        *messages.sweep_callback.lock().unpoisoned() =
+            Some(Box::new(|_| panic!("sweep callback panicked")));
+        messages.cache_message(Message::Sweep(Sweep::default()));
+
+        // Poison the lock as if a user's thread panicked while holding it
+        let _ = std::panic::catch_unwind(|| {
+            let _guard = messages.sweep.0.lock().unpoisoned();
+            panic!("thread panicked while holding the sweep lock");
+        });
+
+        messages.cache_message(Message::DspMode(DspMode::Fast));
+        messages.cache_message(Message::Sweep(Sweep::default()));
+        assert_eq!(
+            *messages.dsp_mode.0.lock().unpoisoned(),
+            Some(DspMode::Fast)
+        );
+        assert!(messages.sweep.0.lock().unpoisoned().is_some());
+    }
+
+    #[test]
+    fn report_panicking_callback_to_error_callback() {
+        let messages = Arc::new(MessageContainer::default());
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        *messages.sweep_callback.lock().unpoisoned() =
+            Some(Box::new(|_| panic!("sweep callback panicked")));
+        *messages.error_callback.lock().unpoisoned() = Some(Box::new({
+            let messages = Arc::downgrade(&messages);
+            let errors = Arc::clone(&errors);
+            move |error| {
+                // The error callback can read the sweep and remove the callback that panicked
+                let messages = messages.upgrade().unwrap();
+                assert!(messages.sweep.0.lock().unpoisoned().is_some());
+                *messages.sweep_callback.lock().unpoisoned() = None;
+                errors.lock().unpoisoned().push(error);
+            }
+        }));
+
+        messages.cache_message(Message::Sweep(Sweep::default()));
+        messages.cache_message(Message::Sweep(Sweep::default()));
+        let errors = errors.lock().unpoisoned();
+        assert_eq!(errors.len(), 1);
+        assert!(
+            matches!(&errors[0], Error::CallbackPanicked(reason) if reason.contains("sweep callback panicked"))
+        );
+    }
+
+    #[test]
+    fn panicking_error_callback_is_not_reported_to_itself() {
+        let messages = MessageContainer::default();
+        let calls = Arc::new(AtomicU64::new(0));
+        *messages.config_callback.lock().unpoisoned() =
+            Some(Box::new(|| panic!("config callback panicked")));
+        *messages.error_callback.lock().unpoisoned() = Some(Box::new({
+            let calls = Arc::clone(&calls);
+            move |_| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                panic!("error callback panicked");
+            }
+        }));
+
+        messages.cache_message(Message::Config(Config::default()));
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn cursor_only_returns_newer_sweeps() {
+        let messages = MessageContainer::default();
+        let mut cursor = MessageCursor::new();
+        let try_next_sweep = |cursor: &mut MessageCursor| {
+            let sweep = messages.sweep.0.lock().unpoisoned();
+            try_next(&sweep, &messages.sweep_seq, &mut cursor.sweep_seq)
+        };
+        assert_eq!(try_next_sweep(&mut cursor), None);
+
+        messages.cache_message(Message::Sweep(Sweep::default()));
+        assert!(try_next_sweep(&mut cursor).is_some());
+        assert_eq!(try_next_sweep(&mut cursor), None);
+
+        messages.cache_message(Message::Sweep(Sweep::default()));
+        assert!(try_next_sweep(&mut MessageCursor::new()).is_some());
+        assert!(try_next_sweep(&mut cursor).is_some());
+    }
+
+    /// Models of the notify and wait paths that loom checks under every interleaving.
+    ///
+    /// Run with `RUSTFLAGS="--cfg loom" cargo test --release --lib loom_models`. Loom doesn't model
+    /// time, so the waits only return once they're notified and a missed wakeup fails as a deadlock.
+    #[cfg(loom)]
+    mod loom_models {
+        use loom::thread;
+
+        use super::*;
+
+        #[test]
+        fn config_waiter_is_woken() {
+            loom::model(|| {
+                let messages = Arc::new(MessageContainer::default());
+                let producer = {
+                    let messages = messages.clone();
+                    thread::spawn(move || {
+                        messages.cache_message(Message::Config(test_config(112, 0)))
+                    })
+                };
+
+                let (lock, condvar) = &messages.config;
+                let config = condvar
+                    .wait_while(lock.lock().unpoisoned(), |config| config.is_none())
+                    .unpoisoned();
+                assert_eq!(config.as_ref().map(|config| config.sweep_len), Some(112));
+                drop(config);
+                producer.join().unwrap();
+            });
+        }
+
+        #[test]
+        fn sweep_waiter_is_woken() {
+            loom::model(|| {
+                let messages = Arc::new(MessageContainer::default());
+                let producer = {
+                    let messages = messages.clone();
+                    thread::spawn(move || messages.cache_message(Message::Sweep(sweep(&[-50.]))))
+                };
+
+                let (lock, condvar) = &messages.sweep;
+                let sweep = condvar
+                    .wait_while(lock.lock().unpoisoned(), |_| {
+                        messages.sweep_seq.load(Ordering::Relaxed) == 0
+                    })
+                    .unpoisoned();
+                assert_eq!(sweep.as_ref().map(Sweep::len), Some(1));
+                drop(sweep);
+                producer.join().unwrap();
+            });
+        }
+
+        #[test]
+        fn screen_data_waiter_is_woken() {
+            loom::model(|| {
+                let messages = Arc::new(MessageContainer::default());
+                let producer = {
+                    let messages = messages.clone();
+                    thread::spawn(move || {
+                        let mut screen_data = ScreenData::PREFIX.to_vec();
+                        screen_data.resize(ScreenData::PREFIX.len() + 8 * 128, 0);
+                        let screen_data = ScreenData::try_from(screen_data.as_slice()).unwrap();
+                        messages.cache_message(Message::ScreenData(screen_data));
+                    })
+                };
+
+                let (lock, condvar) = &messages.screen_data;
+                let screen_data = condvar
+                    .wait_while(lock.lock().unpoisoned(), |_| {
+                        messages.screen_data_seq.load(Ordering::Relaxed) == 0
+                    })
+                    .unpoisoned();
+                assert!(screen_data.is_some());
+                drop(screen_data);
+                producer.join().unwrap();
+            });
+        }
+
+        #[test]
+        fn any_message_waiter_is_woken() {
+            loom::model(|| {
+                let messages = Arc::new(MessageContainer::default());
+                let waiter = {
+                    let messages = messages.clone();
+                    thread::spawn(move || wait_for_any_message(&messages, Duration::ZERO))
+                };
+
+                // Only messages cached after the waiter is registered are passed to it
+                while messages.any_message_waiters.load(Ordering::Relaxed) == 0 {
+                    thread::yield_now();
+                }
+                messages.cache_message(Message::DspMode(DspMode::Fast));
+                assert_eq!(
+                    waiter.join().unwrap().unwrap(),
+                    Message::DspMode(DspMode::Fast)
+                );
+            });
+        }
+    }
+}
@@ -111,4 +111,20 @@ mod tests {
     fn reject_setup_with_incorrect_prefix() {
         assert!(SetupInfo::<Model>::try_from(b"$C2-M:006,005,01.12B26".as_ref()).is_err());
     }
+
+    #[test]
+    fn accept_single_firmware_version() {
+        let setup = SetupInfo::<Model>::try_from(b"#C2-M:006,005,01.15".as_ref()).unwrap();
+        assert_eq!(setup.main_firmware_version(), "01.15");
+        assert_eq!(setup.expansion_firmware_version(), None);
+    }
+
+    #[test]
+    fn accept_separate_expansion_firmware_version() {
+        let setup =
+            SetupInfo::<Model>::try_from(b"#C2-M:006,005,01.15,02.03\r\n".as_ref()).unwrap();
+        assert_eq!(setup.firmware_version, "01.15,02.03");
+        assert_eq!(setup.main_firmware_version(), "01.15");
+        assert_eq!(setup.expansion_firmware_version(), Some("02.03"));
+    }
 }
@@ -1,4 +1,7 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    time::{Duration, Instant},
+};
 
 use chrono::{DateTime, Utc};
 use nom::{
@@ -9,27 +12,373 @@ use nom::{
     number::complete::{be_u16, u8 as nom_u8},
 };
 
-use super::{Config, Model};
-use crate::common::MessageParseError;
+use super::{CalcMode, Config, Model, RadioModule};
+use crate::common::{Frequency, MessageParseError};
 use crate::rf_explorer::{parsers::*, SetupInfo};
 
-#[derive(Debug, Clone, PartialEq, Default)]
-pub(crate) struct Sweep {
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sweep {
     pub(crate) amplitudes_dbm: Vec<f32>,
     pub(crate) timestamp: DateTime<Utc>,
+    pub(crate) captured_at: Option<Instant>,
+    pub(crate) radio_module: Option<RadioModule>,
+    pub(crate) matched_config: Option<bool>,
+    pub(crate) estimated_latency: Duration,
+    pub(crate) calc_mode: Option<CalcMode>,
+    pub(crate) sequence: Option<u64>,
 }
 
 impl Sweep {
     pub(crate) const STANDARD_PREFIX: &'static [u8] = b"$S";
     pub(crate) const EXT_PREFIX: &'static [u8] = b"$s";
     pub(crate) const LARGE_PREFIX: &'static [u8] = b"$z";
+    const PREFIXES: [&'static [u8]; 3] =
+        [Self::STANDARD_PREFIX, Self::EXT_PREFIX, Self::LARGE_PREFIX];
     const EEOT_BYTES: [u8; 5] = [255, 254, 255, 254, 0];
+    // The prefix, length, and line ending bytes sent along with the amplitudes
+    const FRAMING_LEN: usize = 6;
+    // Each byte is sent with a start and stop bit
+    const BITS_PER_BYTE: u32 = 10;
+
+    /// Returns `true` if the bytes start with the prefix of a standard, extended, or large sweep.
+    pub(crate) fn has_prefix(bytes: &[u8]) -> bool {
+        Self::PREFIXES
+            .iter()
+            .any(|prefix| bytes.starts_with(prefix))
+    }
+
+    /// Returns a `Sweep` with the given amplitudes that was measured using `config`.
+    ///
+    /// This is useful for testing code that processes sweeps without an RF Explorer. An
+    /// `InvalidInput` error is returned if the number of amplitudes doesn't match the config's
+    /// sweep length or if an amplitude isn't one an RF Explorer could report, like with
+    /// `Sweep::from_amplitudes`.
+    pub fn from_config_and_amplitudes(
+        config: &Config,
+        amplitudes_dbm: Vec<f32>,
+    ) -> crate::Result<Self> {
+        Self::validate_amplitudes(&amplitudes_dbm)?;
+        if amplitudes_dbm.len() != usize::from(config.sweep_len) {
+            return Err(crate::Error::InvalidInput(format!(
+                "Expected {} amplitudes to match the config's sweep length but got {}",
+                config.sweep_len,
+                amplitudes_dbm.len()
+            )));
+        }
+
+        Ok(Sweep {
+            amplitudes_dbm,
+            timestamp: Utc::now(),
+            captured_at: None,
+            radio_module: Some(if config.is_expansion_radio_module_active {
+                RadioModule::Expansion
+            } else {
+                RadioModule::Main
+            }),
+            matched_config: Some(true),
+            estimated_latency: Duration::ZERO,
+            calc_mode: config.calc_mode,
+            sequence: None,
+        })
+    }
+
+    /// Returns a `Sweep` with the given amplitudes that was captured at `timestamp`.
+    ///
+    /// This is useful for testing analysis code and building simulators without an RF Explorer.
+    /// An `InvalidInput` error is returned if an amplitude isn't one an RF Explorer could report,
+    /// i.e. it isn't finite or isn't between -127.5 and 0 dBm, or if there are more than 65,535
+    /// amplitudes.
+    pub fn from_amplitudes(
+        amplitudes_dbm: Vec<f32>,
+        timestamp: DateTime<Utc>,
+    ) -> crate::Result<Self> {
+        Self::validate_amplitudes(&amplitudes_dbm)?;
+        Ok(Sweep {
+            amplitudes_dbm,
+            timestamp,
+            ..Sweep::default()
+        })
+    }
+
+    /// Returns an `InvalidInput` error if there are more amplitudes than a sweep can contain or an
+    /// amplitude isn't one an RF Explorer could report.
+    fn validate_amplitudes(amplitudes_dbm: &[f32]) -> crate::Result<()> {
+        if amplitudes_dbm.len() > usize::from(u16::MAX) {
+            return Err(crate::Error::InvalidInput(format!(
+                "Sweeps can have at most {} amplitudes but got {}",
+                u16::MAX,
+                amplitudes_dbm.len()
+            )));
+        }
+
+        // Amplitudes are sent as a byte that's the amplitude in dBm multiplied by -2
+        let min_amp_dbm = f32::from(u8::MAX) / -2.;
+        if let Some((index, amp_dbm)) = amplitudes_dbm
+            .iter()
+            .enumerate()
+            .find(|(_, amp_dbm)| !(min_amp_dbm..=0.).contains(*amp_dbm))
+        {
+            return Err(crate::Error::InvalidInput(format!(
+                "The amplitude {amp_dbm} dBm at index {index} isn't between {min_amp_dbm} and 0 dBm"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns a `Sweep` without any amplitudes.
+    ///
+    /// This is equivalent to `Sweep::default()`.
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// The amplitudes (in dBm) of each point in the sweep.
+    pub fn amplitudes_dbm(&self) -> &[f32] {
+        &self.amplitudes_dbm
+    }
+
+    /// The amplitudes (in watts) of each point in the sweep.
+    pub fn amplitudes_watts(&self) -> Vec<f32> {
+        self.amplitudes_dbm
+            .iter()
+            .map(|amp_dbm| Self::dbm_to_watts(*amp_dbm))
+            .collect()
+    }
+
+    /// Converts a power in dBm to watts.
+    pub fn dbm_to_watts(dbm: f32) -> f32 {
+        10f32.powf(dbm / 10.) / 1000.
+    }
+
+    /// Converts a power in watts to dBm.
+    ///
+    /// A power of 0 watts is converted to negative infinity.
+    pub fn watts_to_dbm(watts: f32) -> f32 {
+        10. * (watts * 1000.).log10()
+    }
+
+    /// Returns an iterator over the index and amplitude (in dBm) of each point in the sweep.
+    pub fn iter_with_index(&self) -> impl Iterator<Item = (usize, f32)> + '_ {
+        self.amplitudes_dbm.iter().copied().enumerate()
+    }
+
+    /// Returns the indices of points whose amplitude is at or above `config_max_amp_dbm`.
+    ///
+    /// A strong signal that saturates the RF Explorer's front end causes amplitudes to clip at the
+    /// top of the configured amplitude range, so these points likely aren't accurate.
+    pub fn clipping_bins(&self, config_max_amp_dbm: i16) -> Vec<usize> {
+        self.iter_with_index()
+            .filter(|(_, amp_dbm)| *amp_dbm >= f32::from(config_max_amp_dbm))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Returns a copy of the sweep with a frequency-dependent correction (in dB) added to each
+    /// amplitude, e.g. to compensate for the loss of an external antenna or cable.
+    ///
+    /// `table` maps frequencies to corrections, and the correction at the frequency of each point
+    /// in the sweep, which is calculated from `config`, is linearly interpolated between the
+    /// nearest entries. Points outside the table's frequencies use the nearest entry's correction.
+    /// The table doesn't need to be sorted, and an empty table leaves the amplitudes unchanged.
+    pub fn apply_correction_table(&self, table: &[(Frequency, f32)], config: &Config) -> Sweep {
+        let mut table = table.to_vec();
+        table.sort_by_key(|(freq, _)| *freq);
+        let correction_db = |freq: Frequency| -> f32 {
+            // The first entry at or above the point's frequency
+            let upper = table.partition_point(|(table_freq, _)| *table_freq < freq);
+            match (
+                upper.checked_sub(1).map(|lower| table[lower]),
+                table.get(upper),
+            ) {
+                (Some((lower_freq, lower_db)), Some(&(upper_freq, upper_db))) => {
+                    let fraction = (freq - lower_freq).as_hz() as f64
+                        / (upper_freq - lower_freq).as_hz() as f64;
+                    lower_db + (upper_db - lower_db) * fraction as f32
+                }
+                (Some((_, correction_db)), None) | (None, Some(&(_, correction_db))) => {
+                    correction_db
+                }
+                (None, None) => 0.,
+            }
+        };
+
+        let amplitudes_dbm = self
+            .iter_with_index()
+            .map(|(i, amp_dbm)| {
+                amp_dbm + correction_db(config.start_freq + config.step_size * i as u64)
+            })
+            .collect();
+        Sweep {
+            amplitudes_dbm,
+            ..self.clone()
+        }
+    }
+
+    /// The number of points in the sweep.
+    pub fn len(&self) -> usize {
+        self.amplitudes_dbm.len()
+    }
+
+    /// Returns `true` if the sweep doesn't contain any amplitudes.
+    pub fn is_empty(&self) -> bool {
+        self.amplitudes_dbm.is_empty()
+    }
+
+    /// The time at which this `Sweep` was captured.
+    ///
+    /// This is based on the system clock, so it's suitable for display but can jump if the clock is
+    /// adjusted. Use `elapsed_since_capture` to measure intervals between sweeps.
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    /// The time at which this `Sweep` was received and parsed, which is the same as `timestamp`.
+    pub fn received_at(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    /// An estimate of the time at which the RF Explorer started measuring this `Sweep`.
+    ///
+    /// This is `received_at` minus the time it took to send the sweep over the serial connection
+    /// at the connection's baud rate and minus the time the RF Explorer needed to measure the sweep,
+    /// which is estimated from the sweep length and the RBW in the `Config`. Both are estimates:
+    /// buffering on either side of the connection isn't accounted for and the measurement time is a
+    /// lower bound, so the true measurement start is likely a little earlier.
+    pub fn estimated_measured_at(&self) -> DateTime<Utc> {
+        self.timestamp - chrono::Duration::from_std(self.estimated_latency).unwrap_or_default()
+    }
+
+    /// Estimates the time between an RF Explorer starting to measure a sweep with `sweep_len`
+    /// points and the sweep being received over a serial connection at `baud_rate`.
+    ///
+    /// The measurement time is estimated as `1 / rbw` per point. Unknown values (a `baud_rate` of
+    /// 0 or no `rbw`) don't contribute to the estimate.
+    pub(crate) fn estimate_latency(
+        sweep_len: usize,
+        baud_rate: u32,
+        rbw: Option<Frequency>,
+    ) -> Duration {
+        let sweep_len = sweep_len as f64;
+        let transfer_secs = if baud_rate > 0 {
+            (sweep_len + Self::FRAMING_LEN as f64) * f64::from(Self::BITS_PER_BYTE)
+                / f64::from(baud_rate)
+        } else {
+            0.
+        };
+        let measurement_secs = rbw
+            .map(|rbw| rbw.as_hz())
+            .filter(|&rbw_hz| rbw_hz > 0)
+            .map(|rbw_hz| sweep_len / rbw_hz as f64)
+            .unwrap_or_default();
+        Duration::from_secs_f64(transfer_secs + measurement_secs)
+    }
+
+    /// The time elapsed since this `Sweep` was captured, measured with a monotonic clock.
+    ///
+    /// `Duration::ZERO` is returned if the `Sweep` wasn't captured, e.g. `Sweep::default()`.
+    pub fn elapsed_since_capture(&self) -> Duration {
+        self.captured_at
+            .map(|captured_at| captured_at.elapsed())
+            .unwrap_or_default()
+    }
+
+    /// The radio module that was active when this `Sweep` was received.
+    ///
+    /// `None` is returned if the active radio module wasn't known, e.g. because a `Config` hadn't
+    /// been received yet or the `Sweep` wasn't received from an RF Explorer.
+    pub fn radio_module(&self) -> Option<RadioModule> {
+        self.radio_module
+    }
+
+    /// Returns `true` if the sweep has as many points as the config says it should.
+    pub fn matches_config(&self, config: &Config) -> bool {
+        self.len() == usize::from(config.sweep_len)
+    }
+
+    /// Whether this `Sweep` matched the `Config` that was current when it was received.
+    ///
+    /// `None` is returned if no `Config` had been received yet or the `Sweep` wasn't received from
+    /// an RF Explorer.
+    pub fn matched_config(&self) -> Option<bool> {
+        self.matched_config
+    }
+
+    /// The calc mode the RF Explorer was using when this `Sweep` was received.
+    ///
+    /// `None` is returned if the calc mode wasn't known, e.g. because the RF Explorer's firmware
+    /// doesn't report it or the `Sweep` wasn't received from an RF Explorer.
+    pub fn calc_mode(&self) -> Option<CalcMode> {
+        self.calc_mode
+    }
+
+    /// The position of this `Sweep` among the sweeps received since connecting, starting at 1.
+    ///
+    /// Each sweep that's received is numbered one higher than the last, including sweeps that failed
+    /// to parse or were suppressed, so a jump in the sequence means sweeps were missed, e.g. by a
+    /// subscriber that didn't keep up. `None` is returned if the `Sweep` wasn't received from an RF
+    /// Explorer.
+    pub fn sequence(&self) -> Option<u64> {
+        self.sequence
+    }
+
+    /// Returns `true` if the RF Explorer combined several measurements into this `Sweep` because
+    /// of its calc mode, e.g. by averaging them.
+    pub fn is_processed(&self) -> bool {
+        self.calc_mode
+            .is_some_and(|calc_mode| calc_mode.is_processed())
+    }
+
+    /// Raises each amplitude to the corresponding amplitude in `sweep` if it's higher, taking on
+    /// the rest of `sweep`'s metadata.
+    ///
+    /// If the sweeps have different lengths, this sweep is replaced by `sweep`.
+    pub(crate) fn accumulate_max_hold(&mut self, sweep: &Sweep) {
+        if self.len() != sweep.len() {
+            *self = sweep.clone();
+            return;
+        }
+        for (max_amp, amp) in self.amplitudes_dbm.iter_mut().zip(&sweep.amplitudes_dbm) {
+            *max_amp = max_amp.max(*amp);
+        }
+        self.timestamp = sweep.timestamp;
+        self.captured_at = sweep.captured_at;
+        self.radio_module = sweep.radio_module;
+        self.matched_config = sweep.matched_config;
+        self.estimated_latency = sweep.estimated_latency;
+        self.calc_mode = sweep.calc_mode;
+        self.sequence = sweep.sequence;
+    }
+}
+
+impl Default for Sweep {
+    /// Returns a `Sweep` without any amplitudes.
+    ///
+    /// `Sweep::default().is_empty()` is always `true`. The timestamp of a default `Sweep` is the
+    /// Unix epoch and does not correspond to when a sweep was measured.
+    fn default() -> Self {
+        Sweep {
+            amplitudes_dbm: Vec::new(),
+            timestamp: DateTime::default(),
+            captured_at: None,
+            radio_module: None,
+            matched_config: None,
+            estimated_latency: Duration::ZERO,
+            calc_mode: None,
+            sequence: None,
+        }
+    }
 }
 
 impl<'a> TryFrom<&'a [u8]> for Sweep {
     type Error = MessageParseError<'a>;
 
     fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+        // Bytes that can't be the start of any kind of sweep are a different message type
+        if !Self::has_prefix(bytes) {
+            return Err(MessageParseError::UnknownMessageType);
+        }
+
         // Parse the prefix of the message
         let (bytes, prefix) = alt((
             tag(Self::STANDARD_PREFIX),
@@ -72,6 +421,12 @@ impl<'a> TryFrom<&'a [u8]> for Sweep {
         Ok(Sweep {
             amplitudes_dbm,
             timestamp: Utc::now(),
+            captured_at: Some(Instant::now()),
+            radio_module: None,
+            matched_config: None,
+            estimated_latency: Duration::ZERO,
+            calc_mode: None,
+            sequence: None,
         })
     }
 }
@@ -80,6 +435,24 @@ impl<'a> TryFrom<&'a [u8]> for Sweep {
 mod tests {
     use super::*;
 
+    #[test]
+    fn default_sweep_is_empty() {
+        let sweep = Sweep::default();
+        assert!(sweep.is_empty());
+        assert_eq!(sweep.len(), 0);
+        assert_eq!(sweep, Sweep::empty());
+    }
+
+    #[test]
+    fn find_clipping_bins() {
+        let sweep = Sweep {
+            amplitudes_dbm: vec![-80.0, -30.0, -29.5, -30.5, -10.0],
+            ..Sweep::default()
+        };
+        assert_eq!(sweep.clipping_bins(-30), [1, 2, 4]);
+        assert!(Sweep::default().clipping_bins(-30).is_empty());
+    }
+
     #[test]
     fn parse_sweep() {
         let length = 112;
@@ -170,6 +543,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn route_each_prefix_to_its_sweep_format() {
+        // Standard sweeps send their length in one byte, extended sweeps send the number of
+        // 16-amplitude blocks minus 1, and large sweeps send their length in two bytes
+        for (prefix, len_bytes, sweep_len) in [
+            (Sweep::STANDARD_PREFIX, &[2][..], 2),
+            (Sweep::EXT_PREFIX, &[0][..], 16),
+            (Sweep::LARGE_PREFIX, &[0, 3][..], 3),
+        ] {
+            let mut bytes = [prefix, len_bytes].concat();
+            bytes.extend(std::iter::repeat_n(200, sweep_len));
+            bytes.extend_from_slice(b"\r\n");
+            assert_eq!(Sweep::try_from(&bytes[..]).unwrap().len(), sweep_len);
+            assert!(Sweep::has_prefix(&bytes));
+        }
+    }
+
+    #[test]
+    fn reject_unknown_sweep_prefix() {
+        assert_eq!(
+            Sweep::try_from(&b"$x\x02\xc8\xc8\r\n"[..]),
+            Err(MessageParseError::UnknownMessageType)
+        );
+        assert!(!Sweep::has_prefix(b"$x\x02\xc8\xc8\r\n"));
+    }
+
     #[test]
     fn reject_sweep_with_too_many_amplitudes() {
         let length = 112;
@@ -183,7 +582,15 @@ mod tests {
             120, 125, 239, 100,
         ];
         let sweep_error = Sweep::try_from(&bytes[..]).unwrap_err();
-        assert_eq!(sweep_error, MessageParseError::Invalid);
+        assert!(matches!(
+            sweep_error,
+            MessageParseError::Invalid {
+                expected: "the end of the message",
+                ..
+            }
+        ));
+        // The parse fails on the first byte after the 112 amplitudes
+        assert_eq!(sweep_error.offset(&bytes), Some(115));
     }
 
     #[test]
@@ -238,4 +645,125 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn convert_between_dbm_and_watts() {
+        assert!((Sweep::dbm_to_watts(30.) - 1.).abs() < 1e-6);
+        assert!((Sweep::dbm_to_watts(0.) - 0.001).abs() < 1e-9);
+        assert!(Sweep::watts_to_dbm(0.001).abs() < 1e-4);
+        assert_eq!(Sweep::watts_to_dbm(0.), f32::NEG_INFINITY);
+
+        let sweep = Sweep {
+            amplitudes_dbm: vec![-30., 10.],
+            ..Sweep::default()
+        };
+        let amplitudes_watts = sweep.amplitudes_watts();
+        assert!((amplitudes_watts[0] - 1e-6).abs() < 1e-12);
+        assert!((amplitudes_watts[1] - 0.01).abs() < 1e-8);
+    }
+
+    #[test]
+    fn accumulate_max_hold_keeps_highest_amplitudes() {
+        let mut max_hold = Sweep {
+            amplitudes_dbm: vec![-100., -50., -75.],
+            ..Sweep::default()
+        };
+        max_hold.accumulate_max_hold(&Sweep {
+            amplitudes_dbm: vec![-90., -60., -75.],
+            ..Sweep::default()
+        });
+        assert_eq!(max_hold.amplitudes_dbm(), [-90., -50., -75.]);
+
+        max_hold.accumulate_max_hold(&Sweep {
+            amplitudes_dbm: vec![-110., -120.],
+            ..Sweep::default()
+        });
+        assert_eq!(max_hold.amplitudes_dbm(), [-110., -120.]);
+    }
+
+    #[test]
+    fn estimate_latency_from_transfer_and_measurement_time() {
+        // 4090 bytes at 10 bits per byte over a 40,900 bps connection takes 1 s
+        assert_eq!(
+            Sweep::estimate_latency(4084, 40_900, None),
+            Duration::from_secs(1)
+        );
+        // 100 points at an RBW of 1 kHz takes 100 ms
+        assert_eq!(
+            Sweep::estimate_latency(100, 0, Some(Frequency::from_khz(1))),
+            Duration::from_millis(100)
+        );
+        assert_eq!(
+            Sweep::estimate_latency(4084, 40_900, Some(Frequency::from_hz(4084))),
+            Duration::from_secs(2)
+        );
+        assert_eq!(Sweep::estimate_latency(112, 0, None), Duration::ZERO);
+    }
+
+    #[test]
+    fn estimated_measured_at_precedes_received_at() {
+        let sweep = Sweep {
+            estimated_latency: Duration::from_millis(250),
+            ..Sweep::default()
+        };
+        assert_eq!(
+            sweep.received_at() - sweep.estimated_measured_at(),
+            chrono::Duration::milliseconds(250)
+        );
+    }
+
+    #[test]
+    fn from_config_and_amplitudes_checks_sweep_len() {
+        let config = Config::try_from(
+            b"#C2-F:5249000,0196428,-030,-118,0003,1,000,4850000,6100000,0600000,00200,0000,000"
+                .as_slice(),
+        )
+        .unwrap();
+
+        let sweep = Sweep::from_config_and_amplitudes(&config, vec![-80., -70., -60.]).unwrap();
+        assert_eq!(sweep.amplitudes_dbm(), [-80., -70., -60.]);
+        assert!(sweep.matches_config(&config));
+        assert_eq!(sweep.radio_module(), Some(RadioModule::Expansion));
+
+        assert!(Sweep::from_config_and_amplitudes(&config, vec![-80.]).is_err());
+        assert!(Sweep::from_config_and_amplitudes(&config, vec![-80., f32::NAN, -60.]).is_err());
+        assert!(Sweep::from_config_and_amplitudes(&config, vec![-80., -70., 10.]).is_err());
+    }
+
+    #[test]
+    fn from_amplitudes_checks_amplitudes() {
+        let timestamp = DateTime::from_timestamp_millis(1_672_628_645_000).unwrap();
+        let sweep = Sweep::from_amplitudes(vec![-127.5, -60., 0.], timestamp).unwrap();
+        assert_eq!(sweep.amplitudes_dbm(), [-127.5, -60., 0.]);
+        assert_eq!(sweep.timestamp(), timestamp);
+        assert_eq!(sweep.sequence(), None);
+
+        for amp_dbm in [f32::NAN, f32::NEG_INFINITY, -128., 0.5] {
+            assert!(matches!(
+                Sweep::from_amplitudes(vec![-60., amp_dbm], timestamp),
+                Err(crate::Error::InvalidInput(_))
+            ));
+        }
+        assert!(Sweep::from_amplitudes(vec![-60.; 65_536], timestamp).is_err());
+    }
+
+    #[test]
+    fn interpolate_correction_table() {
+        // Five points from 100 MHz to 104 MHz in 1 MHz steps
+        let config = Config::try_from(
+            b"#C2-F:0100000,1000000,-010,-120,0005,0,000,0000050,0960000,0959950,00110,0000,000"
+                .as_slice(),
+        )
+        .unwrap();
+        let sweep = Sweep::from_config_and_amplitudes(&config, vec![-80.; 5]).unwrap();
+
+        let table = [
+            (Frequency::from_mhz(103), 4.),
+            (Frequency::from_mhz(101), 2.),
+        ];
+        let corrected = sweep.apply_correction_table(&table, &config);
+        assert_eq!(corrected.amplitudes_dbm(), [-78., -78., -77., -76., -76.]);
+        assert_eq!(corrected.timestamp(), sweep.timestamp());
+        assert_eq!(sweep.apply_correction_table(&[], &config), sweep);
+    }
 }
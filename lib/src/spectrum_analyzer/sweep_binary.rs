@@ -0,0 +1,113 @@
+use std::io::{self, Read, Write};
+
+use chrono::DateTime;
+
+use super::Sweep;
+
+impl Sweep {
+    const BINARY_MAGIC: &'static [u8; 4] = b"RFES";
+    const BINARY_VERSION: u8 = 1;
+
+    /// Writes the sweep's timestamp and amplitudes in a compact binary format that's much faster to
+    /// read and write than CSV.
+    ///
+    /// The format is a 4-byte magic number (`RFES`), a 1-byte format version, the timestamp as an
+    /// 8-byte count of microseconds since the Unix epoch, a 2-byte point count, and each amplitude
+    /// (in dBm) as a 4-byte float. Numbers are little-endian. An `InvalidInput` error is returned if
+    /// the sweep has more than 65,535 points.
+    pub fn write_binary(&self, mut writer: impl Write) -> io::Result<()> {
+        let point_count = u16::try_from(self.amplitudes_dbm.len()).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Sweeps with more than 65,535 points can't be written",
+            )
+        })?;
+
+        let mut bytes = Vec::with_capacity(15 + self.amplitudes_dbm.len() * 4);
+        bytes.extend_from_slice(Self::BINARY_MAGIC);
+        bytes.push(Self::BINARY_VERSION);
+        bytes.extend_from_slice(&self.timestamp.timestamp_micros().to_le_bytes());
+        bytes.extend_from_slice(&point_count.to_le_bytes());
+        for amp_dbm in &self.amplitudes_dbm {
+            bytes.extend_from_slice(&amp_dbm.to_le_bytes());
+        }
+        writer.write_all(&bytes)
+    }
+
+    /// Reads a sweep written by `Sweep::write_binary`.
+    ///
+    /// An `InvalidData` error is returned if the bytes don't start with the magic number or were
+    /// written in an unsupported version of the format.
+    pub fn read_binary(mut reader: impl Read) -> io::Result<Sweep> {
+        let mut header = [0; 15];
+        reader.read_exact(&mut header)?;
+        let invalid_data = |error: &str| io::Error::new(io::ErrorKind::InvalidData, error);
+
+        if &header[..4] != Self::BINARY_MAGIC {
+            return Err(invalid_data("Not a binary sweep"));
+        }
+        if header[4] != Self::BINARY_VERSION {
+            return Err(invalid_data("Unsupported binary sweep version"));
+        }
+        let timestamp_micros = i64::from_le_bytes(header[5..13].try_into().unwrap_or_default());
+        let timestamp = DateTime::from_timestamp_micros(timestamp_micros)
+            .ok_or_else(|| invalid_data("Invalid binary sweep timestamp"))?;
+        let point_count = u16::from_le_bytes([header[13], header[14]]);
+
+        let mut amplitude_bytes = vec![0; usize::from(point_count) * 4];
+        reader.read_exact(&mut amplitude_bytes)?;
+        let amplitudes_dbm = amplitude_bytes
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+
+        Ok(Sweep {
+            amplitudes_dbm,
+            timestamp,
+            ..Sweep::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_round_trip() {
+        let sweep = Sweep {
+            amplitudes_dbm: vec![-100., -90.5, -80.],
+            timestamp: DateTime::from_timestamp_micros(1_672_628_645_123_456).unwrap(),
+            ..Sweep::default()
+        };
+        let mut bytes = Vec::new();
+        sweep.write_binary(&mut bytes).unwrap();
+        sweep.write_binary(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 2 * (15 + 3 * 4));
+
+        let mut reader = bytes.as_slice();
+        assert_eq!(Sweep::read_binary(&mut reader).unwrap(), sweep);
+        assert_eq!(Sweep::read_binary(&mut reader).unwrap(), sweep);
+        assert_eq!(
+            Sweep::read_binary(&mut reader).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn reject_unknown_format() {
+        let mut bytes = Vec::new();
+        Sweep::default().write_binary(&mut bytes).unwrap();
+        bytes[4] = 2;
+        assert_eq!(
+            Sweep::read_binary(bytes.as_slice()).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert_eq!(
+            Sweep::read_binary(&b"RFEX\x01\0\0\0\0\0\0\0\0\0\0"[..])
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use super::{Config, Sweep};
+use crate::{Error, Frequency, Result};
+
+/// The difference between the amplitudes of a single point in two sweeps.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BinDelta {
+    pub index: usize,
+    /// The amplitude of the compared sweep minus the amplitude of the reference sweep (in dB).
+    pub delta_db: f32,
+}
+
+impl BinDelta {
+    /// The absolute difference, treating NaN as an infinitely large difference.
+    fn magnitude_db(&self) -> f32 {
+        if self.delta_db.is_nan() {
+            f32::INFINITY
+        } else {
+            self.delta_db.abs()
+        }
+    }
+}
+
+/// The result of comparing a sweep against a reference sweep with `Sweep::approx_eq`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepComparison {
+    outliers: Vec<BinDelta>,
+    max_outliers: usize,
+}
+
+impl SweepComparison {
+    /// Returns `true` if no more than the allowed number of points differed by more than the
+    /// tolerance.
+    pub fn passed(&self) -> bool {
+        self.outliers.len() <= self.max_outliers
+    }
+
+    /// The points that differed by more than the tolerance, ordered from the largest difference to
+    /// the smallest.
+    ///
+    /// Points where either amplitude is NaN are treated as the largest differences.
+    pub fn outliers(&self) -> &[BinDelta] {
+        &self.outliers
+    }
+
+    /// The point that differed the most, if any point differed by more than the tolerance.
+    pub fn worst(&self) -> Option<BinDelta> {
+        self.outliers.first().copied()
+    }
+}
+
+impl Sweep {
+    /// Compares this sweep against a reference sweep, allowing each point to differ by up to
+    /// `tolerance_db` and up to `max_outliers` points to exceed that tolerance.
+    ///
+    /// Returns an error if the sweeps contain a different number of points.
+    pub fn approx_eq(
+        &self,
+        reference: &Sweep,
+        tolerance_db: f32,
+        max_outliers: usize,
+    ) -> Result<SweepComparison> {
+        if self.len() != reference.len() {
+            return Err(Error::InvalidInput(format!(
+                "Cannot compare a sweep with {} points to a sweep with {} points",
+                self.len(),
+                reference.len()
+            )));
+        }
+
+        let mut outliers: Vec<BinDelta> = self
+            .iter_with_index()
+            .zip(reference.amplitudes_dbm())
+            .map(|((index, amp_dbm), reference_amp_dbm)| BinDelta {
+                index,
+                delta_db: amp_dbm - reference_amp_dbm,
+            })
+            .filter(|bin| bin.magnitude_db() > tolerance_db)
+            .collect();
+        outliers.sort_by(|a, b| b.magnitude_db().total_cmp(&a.magnitude_db()));
+
+        Ok(SweepComparison {
+            outliers,
+            max_outliers,
+        })
+    }
+
+    /// Returns the frequency of each point that exceeds the corresponding point in a reference
+    /// sweep by more than `threshold_db`, along with how much it exceeds it by (in dB).
+    ///
+    /// `config` is used to determine the frequency of each point. Only as many points as the
+    /// shorter sweep contains are compared.
+    pub(crate) fn excess_over(
+        &self,
+        reference: &Sweep,
+        threshold_db: f32,
+        config: &Config,
+    ) -> Vec<(Frequency, f32)> {
+        let start_hz = config.start_freq.as_hz();
+        let step_hz = config.step_size.as_hz();
+        self.iter_with_index()
+            .zip(reference.amplitudes_dbm())
+            .map(|((index, amp_dbm), reference_amp_dbm)| (index, amp_dbm - reference_amp_dbm))
+            .filter(|(_, excess_db)| *excess_db > threshold_db)
+            .map(|(index, excess_db)| {
+                (
+                    Frequency::from_hz(start_hz + index as u64 * step_hz),
+                    excess_db,
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_support::sweep;
+
+    #[test]
+    fn excess_over_reference_includes_frequency() {
+        let config = Config {
+            start_freq: Frequency::from_mhz(100),
+            step_size: Frequency::from_khz(10),
+            ..Config::default()
+        };
+        let reference = sweep(&[-80.0, -80.0, -80.0, -80.0]);
+        let live = sweep(&[-79.0, -60.0, -95.0, -70.0]);
+
+        assert_eq!(
+            live.excess_over(&reference, 6.0, &config),
+            [
+                (Frequency::from_khz(100_010), 20.0),
+                (Frequency::from_khz(100_030), 10.0)
+            ]
+        );
+        assert!(reference.excess_over(&reference, 6.0, &config).is_empty());
+    }
+
+    #[test]
+    fn pass_within_tolerance_and_outlier_count() {
+        let reference = sweep(&[-80.0, -80.0, -80.0, -80.0]);
+        let captured = sweep(&[-80.5, -75.0, -80.0, -90.0]);
+
+        let comparison = captured.approx_eq(&reference, 1.0, 2).unwrap();
+        assert!(comparison.passed());
+        assert_eq!(
+            comparison.outliers(),
+            &[
+                BinDelta {
+                    index: 3,
+                    delta_db: -10.0
+                },
+                BinDelta {
+                    index: 1,
+                    delta_db: 5.0
+                },
+            ]
+        );
+
+        let comparison = captured.approx_eq(&reference, 1.0, 1).unwrap();
+        assert!(!comparison.passed());
+        assert_eq!(comparison.worst().map(|bin| bin.index), Some(3));
+    }
+
+    #[test]
+    fn nan_amplitudes_are_outliers() {
+        // Sweeps can't be built from NaN amplitudes, so the amplitudes are replaced afterwards
+        let live = Sweep {
+            amplitudes_dbm: vec![f32::NAN, -50.0],
+            ..sweep(&[])
+        };
+        let comparison = live.approx_eq(&sweep(&[-80.0, -80.0]), 1.0, 0).unwrap();
+        assert_eq!(comparison.worst().map(|bin| bin.index), Some(0));
+        assert_eq!(comparison.outliers().len(), 2);
+    }
+
+    #[test]
+    fn reject_sweeps_with_different_lengths() {
+        assert!(matches!(
+            sweep(&[-80.0]).approx_eq(&sweep(&[-80.0, -80.0]), 1.0, 0),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+use std::{ops::RangeInclusive, time::Duration};
+
+use chrono::{DateTime, Utc};
+
+use super::Sweep;
+
+/// A run of consecutive sweeps that a `SweepGapDetector` didn't see.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SweepGap {
+    /// The sequence numbers of the missing sweeps.
+    pub missing: RangeInclusive<u64>,
+    /// The time between the sweeps received on either side of the gap.
+    pub span: Duration,
+}
+
+impl SweepGap {
+    /// The number of missing sweeps.
+    pub fn missing_count(&self) -> u64 {
+        self.missing.end() - self.missing.start() + 1
+    }
+}
+
+/// Reports the sweeps missing from a stream of sweeps using their sequence numbers.
+///
+/// Feed it every sweep a subscriber receives, e.g. from `SpectrumAnalyzer::try_next_sweep` or
+/// `SpectrumAnalyzer::sweep_watcher`, to find the sweeps the subscriber missed.
+#[derive(Debug, Clone, Default)]
+pub struct SweepGapDetector {
+    last_sweep: Option<(u64, DateTime<Utc>)>,
+    gap_count: u64,
+    missing_sweep_count: u64,
+}
+
+impl SweepGapDetector {
+    /// Creates a detector that hasn't seen any sweeps.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a sweep, returning the gap between it and the last sweep recorded if there is one.
+    ///
+    /// Sweeps without a sequence number and sweeps that were already recorded are ignored. A
+    /// sequence number lower than the last one, e.g. after reconnecting, starts a new stream
+    /// without reporting a gap.
+    pub fn push(&mut self, sweep: &Sweep) -> Option<SweepGap> {
+        let sequence = sweep.sequence()?;
+        let last_sweep = self.last_sweep.replace((sequence, sweep.timestamp()));
+        let (last_sequence, last_timestamp) = last_sweep?;
+        if sequence == last_sequence {
+            self.last_sweep = last_sweep;
+            return None;
+        }
+        if sequence < last_sequence + 2 {
+            return None;
+        }
+
+        let gap = SweepGap {
+            missing: last_sequence + 1..=sequence - 1,
+            span: (sweep.timestamp() - last_timestamp)
+                .to_std()
+                .unwrap_or_default(),
+        };
+        self.gap_count += 1;
+        self.missing_sweep_count += gap.missing_count();
+        Some(gap)
+    }
+
+    /// The number of gaps found.
+    pub fn gap_count(&self) -> u64 {
+        self.gap_count
+    }
+
+    /// The total number of sweeps missing from all of the gaps found.
+    pub fn missing_sweep_count(&self) -> u64 {
+        self.missing_sweep_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered_sweep(sequence: u64, millis: i64) -> Sweep {
+        let timestamp = DateTime::from_timestamp_millis(millis).unwrap();
+        Sweep {
+            sequence: Some(sequence),
+            ..Sweep::from_amplitudes(Vec::new(), timestamp).unwrap()
+        }
+    }
+
+    #[test]
+    fn report_missing_sweeps() {
+        let mut detector = SweepGapDetector::new();
+        assert_eq!(detector.push(&numbered_sweep(1, 0)), None);
+        assert_eq!(detector.push(&numbered_sweep(2, 100)), None);
+        assert_eq!(detector.push(&numbered_sweep(2, 100)), None);
+        assert_eq!(
+            detector.push(&numbered_sweep(5, 400)),
+            Some(SweepGap {
+                missing: 3..=4,
+                span: Duration::from_millis(300),
+            })
+        );
+        assert_eq!(detector.push(&Sweep::default()), None);
+        assert_eq!(
+            detector
+                .push(&numbered_sweep(7, 600))
+                .map(|gap| gap.missing_count()),
+            Some(1)
+        );
+        assert_eq!(detector.gap_count(), 2);
+        assert_eq!(detector.missing_sweep_count(), 3);
+    }
+
+    #[test]
+    fn restart_after_reconnecting() {
+        let mut detector = SweepGapDetector::new();
+        detector.push(&numbered_sweep(10, 0));
+        assert_eq!(detector.push(&numbered_sweep(1, 100)), None);
+        assert_eq!(detector.push(&numbered_sweep(2, 200)), None);
+        assert_eq!(detector.gap_count(), 0);
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::VecDeque;
+
+use super::Sweep;
+
+/// The most recent sweeps received from a spectrum analyzer.
+///
+/// Once the history holds `capacity` sweeps, the oldest sweep is discarded for each new one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SweepHistory {
+    capacity: usize,
+    sweeps: VecDeque<Sweep>,
+}
+
+impl SweepHistory {
+    /// Changes the maximum number of sweeps held, discarding the oldest sweeps if necessary.
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.sweeps.len() > capacity {
+            self.sweeps.pop_front();
+        }
+        self.sweeps.shrink_to(capacity);
+    }
+
+    /// Records a copy of the sweep if the history has a non-zero capacity.
+    pub(crate) fn push(&mut self, sweep: &Sweep) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.sweeps.len() == self.capacity {
+            self.sweeps.pop_front();
+        }
+
+        self.sweeps.push_back(sweep.clone());
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.sweeps.clear();
+    }
+
+    /// Returns the recorded sweeps from oldest to newest.
+    pub(crate) fn sweeps(&self) -> Vec<Sweep> {
+        self.sweeps.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_support::sweep;
+
+    #[test]
+    fn discard_oldest_sweep_when_full() {
+        let mut history = SweepHistory::default();
+        history.push(&sweep(&[-10.0]));
+        assert!(history.sweeps().is_empty());
+
+        history.set_capacity(2);
+        history.push(&sweep(&[-10.0]));
+        history.push(&sweep(&[-20.0]));
+        history.push(&sweep(&[-30.0]));
+        assert_eq!(history.sweeps(), [sweep(&[-20.0]), sweep(&[-30.0])]);
+
+        history.set_capacity(1);
+        assert_eq!(history.sweeps(), [sweep(&[-30.0])]);
+    }
+}
@@ -0,0 +1,11 @@
+/// What a spectrum analyzer does with sweeps whose length doesn't match the current `Config`.
+///
+/// This typically happens briefly after the RF Explorer is reconfigured.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum SweepLenMismatchPolicy {
+    /// Deliver every sweep, leaving it to the caller to check `Sweep::matched_config`.
+    #[default]
+    Deliver,
+    /// Drop sweeps that don't match the current `Config` until a matching sweep arrives.
+    Suppress,
+}
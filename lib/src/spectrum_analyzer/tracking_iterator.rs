@@ -0,0 +1,64 @@
+use super::{SpectrumAnalyzer, Sweep};
+use crate::{Frequency, Result};
+
+/// An iterator that steps a spectrum analyzer in tracking mode over a range of frequencies.
+///
+/// Each item is the frequency of the step and the sweep measured at it. Iteration ends after the
+/// last step or after the first error.
+#[derive(Debug)]
+pub struct TrackingIterator<'a> {
+    analyzer: &'a SpectrumAnalyzer,
+    start: Frequency,
+    step: Frequency,
+    next_step: u16,
+    last_step: u16,
+    is_done: bool,
+}
+
+impl<'a> TrackingIterator<'a> {
+    pub(crate) fn new(
+        analyzer: &'a SpectrumAnalyzer,
+        start: Frequency,
+        step: Frequency,
+        last_step: u16,
+    ) -> Self {
+        TrackingIterator {
+            analyzer,
+            start,
+            step,
+            next_step: 0,
+            last_step,
+            is_done: false,
+        }
+    }
+}
+
+impl Iterator for TrackingIterator<'_> {
+    type Item = Result<(Frequency, Sweep)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done || self.next_step > self.last_step {
+            return None;
+        }
+
+        let step = self.next_step;
+        let result = self
+            .analyzer
+            .tracking_step_and_wait_for_sweep(step, SpectrumAnalyzer::NEXT_SWEEP_TIMEOUT)
+            .map(|sweep| (self.start + self.step * u64::from(step), sweep));
+
+        match step.checked_add(1) {
+            Some(next_step) if result.is_ok() => self.next_step = next_step,
+            _ => self.is_done = true,
+        }
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.is_done || self.next_step > self.last_step {
+            (0, Some(0))
+        } else {
+            (0, Some(usize::from(self.last_step - self.next_step) + 1))
+        }
+    }
+}
@@ -1,8 +1,32 @@
-use num_enum::IntoPrimitive;
+use std::{fmt::Display, ops::RangeInclusive};
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive)]
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+use crate::Frequency;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
 #[repr(u8)]
 pub enum WifiBand {
     TwoPointFourGhz = 1,
     FiveGhz,
 }
+
+impl WifiBand {
+    /// The range of frequencies used by Wi-Fi channels in the band.
+    pub fn frequency_range(&self) -> RangeInclusive<Frequency> {
+        match self {
+            WifiBand::TwoPointFourGhz => Frequency::from_mhz(2400)..=Frequency::from_mhz(2500),
+            WifiBand::FiveGhz => Frequency::from_mhz(5150)..=Frequency::from_mhz(5850),
+        }
+    }
+}
+
+impl Display for WifiBand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wifi_band = match self {
+            WifiBand::TwoPointFourGhz => "2.4 GHz",
+            WifiBand::FiveGhz => "5 GHz",
+        };
+        write!(f, "{wifi_band}")
+    }
+}
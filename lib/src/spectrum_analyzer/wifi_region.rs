@@ -0,0 +1,120 @@
+use std::fmt::Display;
+
+use super::{ChannelPlan, WifiBand};
+use crate::Frequency;
+
+/// The regulatory domain that decides which Wi-Fi channels are allowed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum WifiRegion {
+    /// Every channel allowed in at least one of the other regions.
+    #[default]
+    All,
+    /// The United States and Canada.
+    Fcc,
+    /// Europe.
+    Etsi,
+    /// Japan.
+    Mkk,
+}
+
+impl WifiRegion {
+    const TWO_POINT_FOUR_GHZ_ALL: &'static [u32] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14];
+    const TWO_POINT_FOUR_GHZ_FCC: &'static [u32] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+    const TWO_POINT_FOUR_GHZ_ETSI: &'static [u32] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+    const FIVE_GHZ_FCC: &'static [u32] = &[
+        36, 40, 44, 48, 52, 56, 60, 64, 100, 104, 108, 112, 116, 120, 124, 128, 132, 136, 140, 144,
+        149, 153, 157, 161, 165,
+    ];
+    const FIVE_GHZ_ETSI: &'static [u32] = &[
+        36, 40, 44, 48, 52, 56, 60, 64, 100, 104, 108, 112, 116, 120, 124, 128, 132, 136, 140,
+    ];
+
+    /// The 20 MHz channels the region allows in the band, in ascending order.
+    pub fn channels(&self, wifi_band: WifiBand) -> &'static [u32] {
+        match (wifi_band, self) {
+            (WifiBand::TwoPointFourGhz, WifiRegion::All | WifiRegion::Mkk) => {
+                Self::TWO_POINT_FOUR_GHZ_ALL
+            }
+            (WifiBand::TwoPointFourGhz, WifiRegion::Fcc) => Self::TWO_POINT_FOUR_GHZ_FCC,
+            (WifiBand::TwoPointFourGhz, WifiRegion::Etsi) => Self::TWO_POINT_FOUR_GHZ_ETSI,
+            (WifiBand::FiveGhz, WifiRegion::All | WifiRegion::Fcc) => Self::FIVE_GHZ_FCC,
+            (WifiBand::FiveGhz, WifiRegion::Etsi | WifiRegion::Mkk) => Self::FIVE_GHZ_ETSI,
+        }
+    }
+
+    /// Returns `true` if the region allows the channel in the band.
+    pub fn allows_channel(&self, wifi_band: WifiBand, channel: u32) -> bool {
+        self.channels(wifi_band).contains(&channel)
+    }
+
+    /// Returns `true` if the channel requires dynamic frequency selection (DFS) because it's shared
+    /// with radar.
+    pub fn is_dfs_channel(wifi_band: WifiBand, channel: u32) -> bool {
+        wifi_band == WifiBand::FiveGhz && (52..=144).contains(&channel)
+    }
+
+    /// Returns the center frequency of a Wi-Fi channel, or `None` if the channel isn't part of the
+    /// band.
+    pub fn center_frequency(wifi_band: WifiBand, channel: u32) -> Option<Frequency> {
+        match wifi_band {
+            WifiBand::TwoPointFourGhz => ChannelPlan::wifi_2_4_ghz().center_frequency(channel),
+            WifiBand::FiveGhz => Self::FIVE_GHZ_FCC
+                .contains(&channel)
+                .then(|| Frequency::from_mhz(5000 + 5 * u64::from(channel))),
+        }
+    }
+}
+
+impl Display for WifiRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wifi_region = match self {
+            WifiRegion::All => "all regions",
+            WifiRegion::Fcc => "FCC",
+            WifiRegion::Etsi => "ETSI",
+            WifiRegion::Mkk => "MKK",
+        };
+        write!(f, "{wifi_region}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channels_depend_on_region() {
+        assert!(!WifiRegion::Fcc.allows_channel(WifiBand::TwoPointFourGhz, 12));
+        assert!(WifiRegion::Etsi.allows_channel(WifiBand::TwoPointFourGhz, 13));
+        assert!(!WifiRegion::Etsi.allows_channel(WifiBand::TwoPointFourGhz, 14));
+        assert!(WifiRegion::Mkk.allows_channel(WifiBand::TwoPointFourGhz, 14));
+        assert!(!WifiRegion::Etsi.allows_channel(WifiBand::FiveGhz, 149));
+        assert!(WifiRegion::Fcc.allows_channel(WifiBand::FiveGhz, 165));
+    }
+
+    #[test]
+    fn all_is_the_union_of_every_region() {
+        for wifi_band in [WifiBand::TwoPointFourGhz, WifiBand::FiveGhz] {
+            for region in [WifiRegion::Fcc, WifiRegion::Etsi, WifiRegion::Mkk] {
+                assert!(region
+                    .channels(wifi_band)
+                    .iter()
+                    .all(|channel| WifiRegion::All.allows_channel(wifi_band, *channel)));
+            }
+        }
+    }
+
+    #[test]
+    fn channel_center_frequencies() {
+        assert_eq!(
+            WifiRegion::center_frequency(WifiBand::TwoPointFourGhz, 14),
+            Some(Frequency::from_mhz(2484))
+        );
+        assert_eq!(
+            WifiRegion::center_frequency(WifiBand::FiveGhz, 36),
+            Some(Frequency::from_mhz(5180))
+        );
+        assert_eq!(WifiRegion::center_frequency(WifiBand::FiveGhz, 37), None);
+        assert!(WifiRegion::is_dfs_channel(WifiBand::FiveGhz, 100));
+        assert!(!WifiRegion::is_dfs_channel(WifiBand::FiveGhz, 36));
+    }
+}
@@ -0,0 +1,242 @@
+use std::{
+    io::{self, Read, Write},
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use rfe::{spectrum_analyzer::Command, Frequency};
+
+const READ_TIMEOUT: Duration = Duration::from_millis(20);
+const SWEEP_INTERVAL: Duration = Duration::from_millis(50);
+const SWEEP_LEN: u16 = 112;
+const NOISE_FLOOR_DBM: f32 = -100.;
+pub const PEAK_DBM: f32 = -40.;
+pub const TRACKING_DBM: f32 = -60.;
+pub const SERIAL_NUMBER: &str = "0SME38SI2X7NGR48";
+
+/// A simulated RF Explorer spectrum analyzer with a WSUB1G main radio and a 2.4G expansion radio.
+///
+/// The simulator responds to the commands written to it with the messages a real device sends,
+/// using the same formats as the captures the parser tests are based on, and streams sweeps while
+/// it's in spectrum analyzer mode. Reads time out when no bytes arrive for a while, like a serial
+/// port's reads do.
+#[derive(Debug, Clone, Default)]
+pub struct SimulatedDevice {
+    shared: Arc<(Mutex<State>, Condvar)>,
+}
+
+#[derive(Debug)]
+struct State {
+    input: Vec<u8>,
+    output: Vec<u8>,
+    received_commands: Vec<Vec<u8>>,
+    is_streaming: bool,
+    next_sweep_at: Instant,
+    is_expansion_radio_active: bool,
+    is_tracking: bool,
+    start_khz: u64,
+    stop_khz: u64,
+    min_amp_dbm: i16,
+    max_amp_dbm: i16,
+    max_amp_limit_dbm: Option<i16>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            input: Vec::new(),
+            output: Vec::new(),
+            received_commands: Vec::new(),
+            is_streaming: false,
+            next_sweep_at: Instant::now(),
+            is_expansion_radio_active: false,
+            is_tracking: false,
+            start_khz: 240_000,
+            stop_khz: 340_000,
+            min_amp_dbm: -120,
+            max_amp_dbm: -10,
+            max_amp_limit_dbm: None,
+        }
+    }
+}
+
+impl SimulatedDevice {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns every command written to the simulator, in the order they were received.
+    pub fn received_commands(&self) -> Vec<Vec<u8>> {
+        self.state().received_commands.clone()
+    }
+
+    /// Makes the simulator lower requested maximum amplitudes above `limit_dbm` to `limit_dbm`
+    /// instead of applying them.
+    pub fn clamp_max_amp_dbm(&self, limit_dbm: i16) {
+        self.state().max_amp_limit_dbm = Some(limit_dbm);
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.shared.0.lock().unwrap()
+    }
+}
+
+impl Read for SimulatedDevice {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let (lock, condvar) = &*self.shared;
+        let mut state = lock.lock().unwrap();
+        let deadline = Instant::now() + READ_TIMEOUT;
+        loop {
+            if !state.output.is_empty() {
+                let len = buf.len().min(state.output.len());
+                buf[..len].copy_from_slice(&state.output[..len]);
+                state.output.drain(..len);
+                return Ok(len);
+            }
+
+            let now = Instant::now();
+            if state.is_streaming && !state.is_tracking && now >= state.next_sweep_at {
+                state.next_sweep_at = now + SWEEP_INTERVAL;
+                state.send_sweep(None);
+                continue;
+            }
+            if now >= deadline {
+                return Err(io::ErrorKind::TimedOut.into());
+            }
+
+            let wake_at = if state.is_streaming {
+                deadline.min(state.next_sweep_at)
+            } else {
+                deadline
+            };
+            state = condvar
+                .wait_timeout(state, wake_at.saturating_duration_since(now))
+                .unwrap()
+                .0;
+        }
+    }
+}
+
+impl Write for SimulatedDevice {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let (lock, condvar) = &*self.shared;
+        let mut state = lock.lock().unwrap();
+        state.input.extend_from_slice(buf);
+
+        // Every command starts with '#' followed by the length of the entire command
+        while let [b'#', len, ..] = state.input[..] {
+            let len = usize::from(len);
+            if state.input.len() < len {
+                break;
+            }
+            let command: Vec<u8> = state.input.drain(..len).collect();
+            state.respond_to(&command);
+            state.received_commands.push(command);
+        }
+
+        condvar.notify_all();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl State {
+    fn respond_to(&mut self, command: &[u8]) {
+        match command {
+            b"#\x04C0" => {
+                self.send(b"#C2-M:003,004,01.12B26\r\n");
+                self.send_config();
+                self.is_streaming = true;
+            }
+            b"#\x04Cn" => self.send(format!("#Sn{SERIAL_NUMBER}\r\n").as_bytes()),
+            _ => match Command::decode(command) {
+                Some(Command::SetConfig {
+                    start,
+                    stop,
+                    min_amp_dbm,
+                    max_amp_dbm,
+                }) => {
+                    self.start_khz = start.as_khz();
+                    self.stop_khz = stop.as_khz();
+                    self.min_amp_dbm = min_amp_dbm;
+                    self.max_amp_dbm = self
+                        .max_amp_limit_dbm
+                        .map_or(max_amp_dbm, |limit_dbm| max_amp_dbm.min(limit_dbm));
+                    self.is_tracking = false;
+                    self.send_config();
+                }
+                Some(Command::SwitchModuleMain) => self.switch_radio(false),
+                Some(Command::SwitchModuleExp) => self.switch_radio(true),
+                Some(Command::StartTracking { start, step }) => {
+                    self.start_khz = start.as_khz();
+                    self.stop_khz = (start + step * u64::from(SWEEP_LEN - 1)).as_khz();
+                    self.is_tracking = true;
+                    self.send(b"#K\x01\r\n");
+                    self.send_config();
+                }
+                Some(Command::TrackingStep(step)) if self.is_tracking => {
+                    self.send_sweep(Some(step))
+                }
+                _ => (),
+            },
+        }
+    }
+
+    fn switch_radio(&mut self, is_expansion_radio_active: bool) {
+        let (start_khz, stop_khz) = if is_expansion_radio_active {
+            (2_400_000, 2_480_000)
+        } else {
+            (240_000, 340_000)
+        };
+        self.is_expansion_radio_active = is_expansion_radio_active;
+        self.start_khz = start_khz;
+        self.stop_khz = stop_khz;
+        self.is_tracking = false;
+        self.send_config();
+    }
+
+    fn send_config(&mut self) {
+        let (min_freq_khz, max_freq_khz, max_span_khz) = if self.is_expansion_radio_active {
+            (2_350_000, 2_550_000, 85_000)
+        } else {
+            (240_000, 960_000, 300_000)
+        };
+        let step_hz =
+            Frequency::from_khz(self.stop_khz - self.start_khz).as_hz() / u64::from(SWEEP_LEN - 1);
+        let config = format!(
+            "#C2-F:{:07},{step_hz:07},{:04},{:04},{SWEEP_LEN:04},{},{:03},{min_freq_khz:07},{max_freq_khz:07},{max_span_khz:07},{:05},0000,000\r\n",
+            self.start_khz,
+            self.max_amp_dbm,
+            self.min_amp_dbm,
+            u8::from(self.is_expansion_radio_active),
+            if self.is_tracking { 5 } else { 0 },
+            (self.stop_khz - self.start_khz) / u64::from(SWEEP_LEN),
+        );
+        self.send(config.as_bytes());
+    }
+
+    /// Sends a sweep of the noise floor with a peak in the middle, and with the tracking generator's
+    /// level at the index of the tracking step if there is one.
+    fn send_sweep(&mut self, tracking_step: Option<u16>) {
+        let mut sweep = vec![b'$', b'S', SWEEP_LEN as u8];
+        sweep.extend((0..SWEEP_LEN).map(|i| {
+            let amp_dbm = if Some(i) == tracking_step {
+                TRACKING_DBM
+            } else if i == SWEEP_LEN / 2 {
+                PEAK_DBM
+            } else {
+                NOISE_FLOOR_DBM
+            };
+            (amp_dbm * -2.) as u8
+        }));
+        sweep.extend_from_slice(b"\r\n");
+        self.send(&sweep);
+    }
+
+    fn send(&mut self, bytes: &[u8]) {
+        self.output.extend_from_slice(bytes);
+    }
+}
@@ -0,0 +1,185 @@
+mod common;
+
+use std::{
+    sync::{mpsc, Arc},
+    time::{Duration, Instant},
+};
+
+use common::{SimulatedDevice, PEAK_DBM, SERIAL_NUMBER, TRACKING_DBM};
+use rfe::{
+    spectrum_analyzer::{Mode, Model, RadioModule, TrackingStatus},
+    ConnectionError, DeviceType, Error, Frequency, SignalGenerator, SpectrumAnalyzer,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+fn connect() -> (SpectrumAnalyzer, SimulatedDevice) {
+    let device = SimulatedDevice::new();
+    let rfe = SpectrumAnalyzer::connect_with_stream(device.clone()).unwrap();
+    (rfe, device)
+}
+
+#[test]
+fn connect_and_receive_sweeps() {
+    let (rfe, device) = connect();
+    assert_eq!(device.received_commands(), [b"#\x04C0".to_vec()]);
+    assert_eq!(rfe.port_name(), "stream");
+    assert_eq!(rfe.firmware_version(), "01.12B26");
+    assert_eq!(rfe.main_radio_model(), Some(Model::RfeWSub1G));
+    assert_eq!(rfe.expansion_radio_model(), Some(Model::Rfe24G));
+    assert_eq!(rfe.active_radio_module(), RadioModule::Main);
+    assert_eq!(rfe.start_freq(), Frequency::from_mhz(240));
+    assert_eq!(rfe.sweep_len(), 112);
+
+    let sweep = rfe.wait_for_next_sweep_with_timeout(TIMEOUT).unwrap();
+    assert_eq!(sweep.len(), 112);
+    assert_eq!(sweep.iter().copied().reduce(f32::max), Some(PEAK_DBM));
+}
+
+#[test]
+fn report_wrong_device_type_without_waiting_for_timeout() {
+    let start = Instant::now();
+    assert!(matches!(
+        SignalGenerator::connect_with_stream(SimulatedDevice::new()),
+        Err(ConnectionError::WrongDeviceType {
+            expected: DeviceType::SignalGenerator,
+            found: DeviceType::SpectrumAnalyzer,
+        })
+    ));
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn fill_buf_with_next_sweep() {
+    let (rfe, _device) = connect();
+    let mut buf = [0.; 128];
+    assert_eq!(rfe.wait_for_next_sweep_and_fill_buf(&mut buf).unwrap(), 112);
+    assert_eq!(buf[..112].iter().copied().reduce(f32::max), Some(PEAK_DBM));
+
+    let mut small_buf = [0.; 16];
+    assert!(rfe
+        .wait_for_next_sweep_with_timeout_and_fill_buf(TIMEOUT, &mut small_buf)
+        .is_err());
+}
+
+#[test]
+fn change_config() {
+    let (rfe, device) = connect();
+    rfe.set_start_stop(Frequency::from_mhz(400), Frequency::from_mhz(500))
+        .unwrap();
+    assert_eq!(rfe.start_freq(), Frequency::from_mhz(400));
+    assert!(rfe.stop_freq().abs_diff(Frequency::from_mhz(500)) <= rfe.step_size());
+    assert_eq!(
+        device.received_commands().last().unwrap(),
+        b"#\x20C2-F:0400000,0500000,-010,-120"
+    );
+
+    let sweep = rfe.wait_for_next_sweep_with_timeout(TIMEOUT).unwrap();
+    assert_eq!(sweep.len(), 112);
+}
+
+#[test]
+fn report_clamped_amplitudes_without_waiting_for_timeout() {
+    let (rfe, device) = connect();
+    device.clamp_max_amp_dbm(0);
+    let start = Instant::now();
+    assert!(matches!(
+        rfe.set_min_max_amps(-100, 10),
+        Err(Error::ValueClamped(_))
+    ));
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(rfe.max_amp_dbm(), 0);
+}
+
+#[test]
+fn track_signal_generator() {
+    let (rfe, _device) = connect();
+    assert_eq!(
+        rfe.request_tracking(500_000_000, 1_000_000).unwrap(),
+        TrackingStatus::Enabled
+    );
+    for step in 0..3 {
+        assert_eq!(
+            rfe.tracking_step_and_wait(step, TIMEOUT).unwrap(),
+            TRACKING_DBM
+        );
+    }
+    assert_eq!(rfe.mode(), Mode::AnalyzerTracking);
+}
+
+#[test]
+fn switch_radio_modules() {
+    let (rfe, _device) = connect();
+    rfe.activate_expansion_radio().unwrap();
+    assert_eq!(rfe.active_radio_module(), RadioModule::Expansion);
+    assert_eq!(rfe.active_radio_model(), Model::Rfe24G);
+    assert_eq!(rfe.start_freq(), Frequency::from_mhz(2_400));
+    assert!(rfe.activate_expansion_radio().is_err());
+
+    rfe.activate_main_radio().unwrap();
+    assert_eq!(rfe.active_radio_module(), RadioModule::Main);
+    assert_eq!(rfe.active_radio_model(), Model::RfeWSub1G);
+    assert_eq!(rfe.start_freq(), Frequency::from_mhz(240));
+}
+
+#[test]
+fn restore_snapshot() {
+    let (rfe, _device) = connect();
+    let snapshot = rfe.device_snapshot();
+    assert_eq!(snapshot.radio_module, RadioModule::Main);
+    assert_eq!(snapshot.start, Frequency::from_mhz(240));
+
+    rfe.activate_expansion_radio().unwrap();
+    rfe.set_start_stop(Frequency::from_mhz(2_410), Frequency::from_mhz(2_450))
+        .unwrap();
+    rfe.restore(&snapshot).unwrap();
+    assert_eq!(rfe.active_radio_module(), RadioModule::Main);
+    assert_eq!(rfe.start_freq(), snapshot.start);
+    assert_eq!(rfe.stop_freq(), snapshot.stop);
+    assert_eq!(rfe.min_amp_dbm(), snapshot.min_amp_dbm);
+    assert_eq!(rfe.max_amp_dbm(), snapshot.max_amp_dbm);
+}
+
+#[test]
+fn request_serial_number() {
+    let (rfe, device) = connect();
+    assert_eq!(rfe.serial_number().as_deref(), Some(SERIAL_NUMBER));
+
+    // The serial number is only requested once
+    assert_eq!(rfe.serial_number().as_deref(), Some(SERIAL_NUMBER));
+    let requests = device
+        .received_commands()
+        .into_iter()
+        .filter(|command| command == b"#\x04Cn")
+        .count();
+    assert_eq!(requests, 1);
+}
+
+#[test]
+fn no_command_sent_from_reader_thread() {
+    let (rfe, device) = connect();
+    let rfe = Arc::new(rfe);
+    let (tx, rx) = mpsc::channel();
+    let weak_rfe = Arc::downgrade(&rfe);
+    rfe.set_sweep_callback(move |_| {
+        let Some(rfe) = weak_rfe.upgrade() else {
+            return;
+        };
+        let commands_before = device.received_commands().len();
+        let results = [
+            rfe.set_start_stop(Frequency::from_mhz(400), Frequency::from_mhz(500)),
+            rfe.set_sweep_len(240),
+            rfe.activate_main_radio(),
+            rfe.activate_expansion_radio(),
+            rfe.set_mode(Mode::WifiAnalyzer),
+        ];
+        let _ = tx.send((results, device.received_commands().len() - commands_before));
+    });
+
+    let (results, commands_sent) = rx.recv_timeout(TIMEOUT).unwrap();
+    rfe.remove_sweep_callback();
+    for result in results {
+        assert!(matches!(result, Err(Error::InvalidOperation(_))));
+    }
+    assert_eq!(commands_sent, 0);
+}